@@ -0,0 +1,1901 @@
+// Stable simd kernels using the `core::arch` x86 intrinsics.
+//
+// Each kernel is written once against the `Vector` and `FloatVector` traits
+// and then compiled for AVX2, AVX and SSE4.2 by the `arch_dispatch!` macro,
+// which picks the best one at runtime with `is_x86_feature_detected!`. AVX
+// lacks 256 bit integer instructions, so the AVX integer kernels use 128 bit
+// vectors and only benefit from the VEX encoding.
+//
+// `Counter` is sealed and only implemented for the unsigned integers, so the
+// size of a counter type determines which unsigned integer it has the same
+// representation as. The integer kernels reinterpret counter slices as slices
+// of that integer. There are no 128 bit integer lanes so u128 counters always
+// use the scalar kernels.
+
+use crate::backend::{self, Backend};
+use crate::kernels::scalar;
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::math;
+use crate::{Counter, Error};
+#[cfg(target_arch = "x86")]
+use core::arch::x86::*;
+#[cfg(target_arch = "x86_64")]
+use core::arch::x86_64::*;
+use core::convert::TryFrom;
+use core::mem::size_of;
+
+// The largest number of lanes of any vector, used to size the buffers which
+// hold the remainder of a slice that does not fill a whole vector.
+const MAX_LANES: usize = 32;
+
+////////////////////////////////////////////////////////////////////////////////
+// Vector types
+////////////////////////////////////////////////////////////////////////////////
+
+trait Vector: Copy {
+    type Elem: Copy + Default;
+    const LANES: usize;
+    unsafe fn zero() -> Self;
+    unsafe fn load(ptr: *const Self::Elem) -> Self;
+    unsafe fn store(self, ptr: *mut Self::Elem);
+    unsafe fn min(self, other: Self) -> Self;
+    unsafe fn max(self, other: Self) -> Self;
+    unsafe fn all_eq(self, other: Self) -> bool;
+    unsafe fn is_zero(self) -> bool;
+    // Adds every lane of `self` into `acc`, which is treated as u64 lanes.
+    unsafe fn widening_sum(self, acc: Self) -> Self;
+    // Sums `self` treated as u64 lanes, wrapping on overflow.
+    unsafe fn sum_u64(self) -> u64;
+}
+
+macro_rules! impl_vector_128 {
+    ($name:ident, $elem:ty, $lanes:expr, min = $min:expr, max = $max:expr, widen = $widen:expr) => {
+        #[derive(Clone, Copy)]
+        struct $name(__m128i);
+
+        impl Vector for $name {
+            type Elem = $elem;
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            unsafe fn zero() -> Self {
+                $name(_mm_setzero_si128())
+            }
+
+            #[inline(always)]
+            unsafe fn load(ptr: *const $elem) -> Self {
+                $name(_mm_loadu_si128(ptr as *const __m128i))
+            }
+
+            #[inline(always)]
+            unsafe fn store(self, ptr: *mut $elem) {
+                _mm_storeu_si128(ptr as *mut __m128i, self.0)
+            }
+
+            #[inline(always)]
+            unsafe fn min(self, other: Self) -> Self {
+                $name($min(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn max(self, other: Self) -> Self {
+                $name($max(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn all_eq(self, other: Self) -> bool {
+                _mm_movemask_epi8(_mm_cmpeq_epi8(self.0, other.0)) == 0xffff
+            }
+
+            #[inline(always)]
+            unsafe fn is_zero(self) -> bool {
+                _mm_testz_si128(self.0, self.0) == 1
+            }
+
+            #[inline(always)]
+            unsafe fn widening_sum(self, acc: Self) -> Self {
+                $name(_mm_add_epi64(acc.0, $widen(self.0)))
+            }
+
+            #[inline(always)]
+            unsafe fn sum_u64(self) -> u64 {
+                let mut out = [0u64; 2];
+                _mm_storeu_si128(out.as_mut_ptr() as *mut __m128i, self.0);
+                out[0].wrapping_add(out[1])
+            }
+        }
+    };
+}
+
+macro_rules! impl_vector_256 {
+    ($name:ident, $elem:ty, $lanes:expr, min = $min:expr, max = $max:expr, widen = $widen:expr) => {
+        #[derive(Clone, Copy)]
+        struct $name(__m256i);
+
+        impl Vector for $name {
+            type Elem = $elem;
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            unsafe fn zero() -> Self {
+                $name(_mm256_setzero_si256())
+            }
+
+            #[inline(always)]
+            unsafe fn load(ptr: *const $elem) -> Self {
+                $name(_mm256_loadu_si256(ptr as *const __m256i))
+            }
+
+            #[inline(always)]
+            unsafe fn store(self, ptr: *mut $elem) {
+                _mm256_storeu_si256(ptr as *mut __m256i, self.0)
+            }
+
+            #[inline(always)]
+            unsafe fn min(self, other: Self) -> Self {
+                $name($min(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn max(self, other: Self) -> Self {
+                $name($max(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn all_eq(self, other: Self) -> bool {
+                _mm256_movemask_epi8(_mm256_cmpeq_epi8(self.0, other.0)) == -1
+            }
+
+            #[inline(always)]
+            unsafe fn is_zero(self) -> bool {
+                _mm256_testz_si256(self.0, self.0) == 1
+            }
+
+            #[inline(always)]
+            unsafe fn widening_sum(self, acc: Self) -> Self {
+                $name(_mm256_add_epi64(acc.0, $widen(self.0)))
+            }
+
+            #[inline(always)]
+            unsafe fn sum_u64(self) -> u64 {
+                let mut out = [0u64; 4];
+                _mm256_storeu_si256(out.as_mut_ptr() as *mut __m256i, self.0);
+                out.iter().fold(0, |acc, e| acc.wrapping_add(*e))
+            }
+        }
+    };
+}
+
+// Widening helpers, which sum the lanes of a vector into u64 lanes. Unpacking
+// works within 128 bit halves, which does not matter as every lane is summed.
+
+#[inline(always)]
+unsafe fn widen_u8_128(v: __m128i) -> __m128i {
+    _mm_sad_epu8(v, _mm_setzero_si128())
+}
+
+#[inline(always)]
+unsafe fn widen_u16_128(v: __m128i) -> __m128i {
+    let zero = _mm_setzero_si128();
+    let v = _mm_add_epi32(_mm_unpacklo_epi16(v, zero), _mm_unpackhi_epi16(v, zero));
+    widen_u32_128(v)
+}
+
+#[inline(always)]
+unsafe fn widen_u32_128(v: __m128i) -> __m128i {
+    let zero = _mm_setzero_si128();
+    _mm_add_epi64(_mm_unpacklo_epi32(v, zero), _mm_unpackhi_epi32(v, zero))
+}
+
+#[inline(always)]
+unsafe fn widen_u64_128(v: __m128i) -> __m128i {
+    v
+}
+
+#[inline(always)]
+unsafe fn widen_u8_256(v: __m256i) -> __m256i {
+    _mm256_sad_epu8(v, _mm256_setzero_si256())
+}
+
+#[inline(always)]
+unsafe fn widen_u16_256(v: __m256i) -> __m256i {
+    let zero = _mm256_setzero_si256();
+    let v = _mm256_add_epi32(
+        _mm256_unpacklo_epi16(v, zero),
+        _mm256_unpackhi_epi16(v, zero),
+    );
+    widen_u32_256(v)
+}
+
+#[inline(always)]
+unsafe fn widen_u32_256(v: __m256i) -> __m256i {
+    let zero = _mm256_setzero_si256();
+    _mm256_add_epi64(
+        _mm256_unpacklo_epi32(v, zero),
+        _mm256_unpackhi_epi32(v, zero),
+    )
+}
+
+#[inline(always)]
+unsafe fn widen_u64_256(v: __m256i) -> __m256i {
+    v
+}
+
+// There are no unsigned 64 bit min and max instructions before AVX-512, so
+// the sign bit is flipped to make the signed comparison order unsigned values.
+
+#[inline(always)]
+unsafe fn gt_u64_128(a: __m128i, b: __m128i) -> __m128i {
+    let sign = _mm_set1_epi64x(i64::MIN);
+    _mm_cmpgt_epi64(_mm_xor_si128(a, sign), _mm_xor_si128(b, sign))
+}
+
+#[inline(always)]
+unsafe fn min_u64_128(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8(a, b, gt_u64_128(a, b))
+}
+
+#[inline(always)]
+unsafe fn max_u64_128(a: __m128i, b: __m128i) -> __m128i {
+    _mm_blendv_epi8(b, a, gt_u64_128(a, b))
+}
+
+#[inline(always)]
+unsafe fn gt_u64_256(a: __m256i, b: __m256i) -> __m256i {
+    let sign = _mm256_set1_epi64x(i64::MIN);
+    _mm256_cmpgt_epi64(_mm256_xor_si256(a, sign), _mm256_xor_si256(b, sign))
+}
+
+#[inline(always)]
+unsafe fn min_u64_256(a: __m256i, b: __m256i) -> __m256i {
+    _mm256_blendv_epi8(a, b, gt_u64_256(a, b))
+}
+
+#[inline(always)]
+unsafe fn max_u64_256(a: __m256i, b: __m256i) -> __m256i {
+    _mm256_blendv_epi8(b, a, gt_u64_256(a, b))
+}
+
+impl_vector_128!(
+    U8x16,
+    u8,
+    16,
+    min = _mm_min_epu8,
+    max = _mm_max_epu8,
+    widen = widen_u8_128
+);
+impl_vector_128!(
+    U16x8,
+    u16,
+    8,
+    min = _mm_min_epu16,
+    max = _mm_max_epu16,
+    widen = widen_u16_128
+);
+impl_vector_128!(
+    U32x4,
+    u32,
+    4,
+    min = _mm_min_epu32,
+    max = _mm_max_epu32,
+    widen = widen_u32_128
+);
+impl_vector_128!(
+    U64x2,
+    u64,
+    2,
+    min = min_u64_128,
+    max = max_u64_128,
+    widen = widen_u64_128
+);
+impl_vector_256!(
+    U8x32,
+    u8,
+    32,
+    min = _mm256_min_epu8,
+    max = _mm256_max_epu8,
+    widen = widen_u8_256
+);
+impl_vector_256!(
+    U16x16,
+    u16,
+    16,
+    min = _mm256_min_epu16,
+    max = _mm256_max_epu16,
+    widen = widen_u16_256
+);
+impl_vector_256!(
+    U32x8,
+    u32,
+    8,
+    min = _mm256_min_epu32,
+    max = _mm256_max_epu32,
+    widen = widen_u32_256
+);
+impl_vector_256!(
+    U64x4,
+    u64,
+    4,
+    min = min_u64_256,
+    max = max_u64_256,
+    widen = widen_u64_256
+);
+
+// Loads one narrower counter per lane and zero extends it, for converting
+// counters to a wider counter type. `E` is the narrower counter.
+trait WidenLoad<E>: Vector {
+    unsafe fn widen_load(ptr: *const E) -> Self;
+}
+
+macro_rules! impl_widen_load {
+    ($name:ident, $from:ty, $load:ident, $extend:ident) => {
+        impl WidenLoad<$from> for $name {
+            #[inline(always)]
+            unsafe fn widen_load(ptr: *const $from) -> Self {
+                $name($extend($load(ptr as *const u8)))
+            }
+        }
+    };
+}
+
+// Loads the low 128, 64, 32 or 16 bits of a vector, zeroing the rest.
+
+#[inline(always)]
+unsafe fn load_128(ptr: *const u8) -> __m128i {
+    _mm_loadu_si128(ptr as *const __m128i)
+}
+
+#[inline(always)]
+unsafe fn load_64(ptr: *const u8) -> __m128i {
+    _mm_loadl_epi64(ptr as *const __m128i)
+}
+
+#[inline(always)]
+unsafe fn load_32(ptr: *const u8) -> __m128i {
+    _mm_cvtsi32_si128((ptr as *const i32).read_unaligned())
+}
+
+#[inline(always)]
+unsafe fn load_16(ptr: *const u8) -> __m128i {
+    _mm_cvtsi32_si128((ptr as *const u16).read_unaligned() as i32)
+}
+
+impl_widen_load!(U16x8, u8, load_64, _mm_cvtepu8_epi16);
+impl_widen_load!(U32x4, u8, load_32, _mm_cvtepu8_epi32);
+impl_widen_load!(U64x2, u8, load_16, _mm_cvtepu8_epi64);
+impl_widen_load!(U32x4, u16, load_64, _mm_cvtepu16_epi32);
+impl_widen_load!(U64x2, u16, load_32, _mm_cvtepu16_epi64);
+impl_widen_load!(U64x2, u32, load_64, _mm_cvtepu32_epi64);
+impl_widen_load!(U16x16, u8, load_128, _mm256_cvtepu8_epi16);
+impl_widen_load!(U32x8, u8, load_64, _mm256_cvtepu8_epi32);
+impl_widen_load!(U64x4, u8, load_32, _mm256_cvtepu8_epi64);
+impl_widen_load!(U32x8, u16, load_128, _mm256_cvtepu16_epi32);
+impl_widen_load!(U64x4, u16, load_64, _mm256_cvtepu16_epi64);
+impl_widen_load!(U64x4, u32, load_128, _mm256_cvtepu32_epi64);
+
+// Narrows each lane to the narrower counter `E` and stores them, for
+// converting counters to a narrower counter type. Every lane must be at most
+// `MAX`, the largest value of `E`.
+trait NarrowStore<E>: Vector {
+    const MAX: Self::Elem;
+    unsafe fn narrow_store(self, ptr: *mut E);
+}
+
+macro_rules! impl_narrow_store {
+    ($name:ident, $to:ty, $halves:ident, $narrow:ident, $store:ident) => {
+        impl NarrowStore<$to> for $name {
+            const MAX: Self::Elem = <$to>::MAX as Self::Elem;
+
+            #[inline(always)]
+            unsafe fn narrow_store(self, ptr: *mut $to) {
+                let (lo, hi) = $halves(self.0);
+                $store(ptr as *mut u8, $narrow(lo, hi))
+            }
+        }
+    };
+}
+
+// Splits a vector into the 128 bit vectors which are narrowed together. A 128
+// bit vector is narrowed with itself, so only the low half of the result is
+// stored.
+
+#[inline(always)]
+unsafe fn halves_128(v: __m128i) -> (__m128i, __m128i) {
+    (v, v)
+}
+
+#[inline(always)]
+unsafe fn halves_256(v: __m256i) -> (__m128i, __m128i) {
+    (_mm256_castsi256_si128(v), _mm256_extracti128_si256::<1>(v))
+}
+
+// Packs the lanes of two vectors into the low lanes of one. The pack
+// instructions saturate signed lanes, which leaves lanes that fit in the
+// narrower counter unchanged. There is no pack for 64 bit lanes, so their low
+// halves are shuffled together instead.
+
+#[inline(always)]
+unsafe fn narrow_u16_u8(a: __m128i, b: __m128i) -> __m128i {
+    _mm_packus_epi16(a, b)
+}
+
+#[inline(always)]
+unsafe fn narrow_u32_u16(a: __m128i, b: __m128i) -> __m128i {
+    _mm_packus_epi32(a, b)
+}
+
+#[inline(always)]
+unsafe fn narrow_u32_u8(a: __m128i, b: __m128i) -> __m128i {
+    let v = narrow_u32_u16(a, b);
+    narrow_u16_u8(v, v)
+}
+
+#[inline(always)]
+unsafe fn narrow_u64_u32(a: __m128i, b: __m128i) -> __m128i {
+    _mm_unpacklo_epi64(
+        _mm_shuffle_epi32::<0b10_00_10_00>(a),
+        _mm_shuffle_epi32::<0b10_00_10_00>(b),
+    )
+}
+
+#[inline(always)]
+unsafe fn narrow_u64_u16(a: __m128i, b: __m128i) -> __m128i {
+    let v = narrow_u64_u32(a, b);
+    narrow_u32_u16(v, v)
+}
+
+#[inline(always)]
+unsafe fn narrow_u64_u8(a: __m128i, b: __m128i) -> __m128i {
+    let v = narrow_u64_u16(a, b);
+    narrow_u16_u8(v, v)
+}
+
+// Stores the low 128, 64, 32 or 16 bits of a vector.
+
+#[inline(always)]
+unsafe fn store_128(ptr: *mut u8, v: __m128i) {
+    _mm_storeu_si128(ptr as *mut __m128i, v)
+}
+
+#[inline(always)]
+unsafe fn store_64(ptr: *mut u8, v: __m128i) {
+    _mm_storel_epi64(ptr as *mut __m128i, v)
+}
+
+#[inline(always)]
+unsafe fn store_32(ptr: *mut u8, v: __m128i) {
+    (ptr as *mut i32).write_unaligned(_mm_cvtsi128_si32(v))
+}
+
+#[inline(always)]
+unsafe fn store_16(ptr: *mut u8, v: __m128i) {
+    (ptr as *mut u16).write_unaligned(_mm_cvtsi128_si32(v) as u16)
+}
+
+impl_narrow_store!(U16x8, u8, halves_128, narrow_u16_u8, store_64);
+impl_narrow_store!(U32x4, u8, halves_128, narrow_u32_u8, store_32);
+impl_narrow_store!(U32x4, u16, halves_128, narrow_u32_u16, store_64);
+impl_narrow_store!(U64x2, u8, halves_128, narrow_u64_u8, store_16);
+impl_narrow_store!(U64x2, u16, halves_128, narrow_u64_u16, store_32);
+impl_narrow_store!(U64x2, u32, halves_128, narrow_u64_u32, store_64);
+impl_narrow_store!(U16x16, u8, halves_256, narrow_u16_u8, store_128);
+impl_narrow_store!(U32x8, u8, halves_256, narrow_u32_u8, store_64);
+impl_narrow_store!(U32x8, u16, halves_256, narrow_u32_u16, store_128);
+impl_narrow_store!(U64x4, u8, halves_256, narrow_u64_u8, store_32);
+impl_narrow_store!(U64x4, u16, halves_256, narrow_u64_u16, store_64);
+impl_narrow_store!(U64x4, u32, halves_256, narrow_u64_u32, store_128);
+
+#[cfg(any(feature = "std", feature = "libm"))]
+trait FloatVector: Copy {
+    const LANES: usize;
+    unsafe fn zero() -> Self;
+    unsafe fn load(ptr: *const f64) -> Self;
+    unsafe fn splat(value: f64) -> Self;
+    unsafe fn add(self, other: Self) -> Self;
+    unsafe fn sub(self, other: Self) -> Self;
+    unsafe fn mul(self, other: Self) -> Self;
+    unsafe fn div(self, other: Self) -> Self;
+    unsafe fn max(self, other: Self) -> Self;
+    // Zeroes the lanes of `self` where `mask` is not greater than zero.
+    unsafe fn select_positive(self, mask: Self) -> Self;
+    unsafe fn sqrt(self) -> Self;
+    unsafe fn ln(self) -> Self;
+    unsafe fn powf(self, n: f64) -> Self;
+    unsafe fn sum(self) -> f64;
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! impl_float_vector {
+    ($name:ident, $simd:ty, $lanes:expr, $load:ident, $store:ident, $set1:ident, $setzero:ident, $add:ident, $sub:ident, $mul:ident, $div:ident, $max:ident, $sqrt:ident, $and:ident, $cmp_gt:expr) => {
+        #[derive(Clone, Copy)]
+        struct $name($simd);
+
+        impl FloatVector for $name {
+            const LANES: usize = $lanes;
+
+            #[inline(always)]
+            unsafe fn zero() -> Self {
+                $name($setzero())
+            }
+
+            #[inline(always)]
+            unsafe fn load(ptr: *const f64) -> Self {
+                $name($load(ptr))
+            }
+
+            #[inline(always)]
+            unsafe fn splat(value: f64) -> Self {
+                $name($set1(value))
+            }
+
+            #[inline(always)]
+            unsafe fn add(self, other: Self) -> Self {
+                $name($add(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn sub(self, other: Self) -> Self {
+                $name($sub(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn mul(self, other: Self) -> Self {
+                $name($mul(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn div(self, other: Self) -> Self {
+                $name($div(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn max(self, other: Self) -> Self {
+                $name($max(self.0, other.0))
+            }
+
+            #[inline(always)]
+            unsafe fn sqrt(self) -> Self {
+                $name($sqrt(self.0))
+            }
+
+            #[inline(always)]
+            unsafe fn select_positive(self, mask: Self) -> Self {
+                $name($and(self.0, $cmp_gt(mask.0, $setzero())))
+            }
+
+            #[inline(always)]
+            unsafe fn ln(self) -> Self {
+                let mut out = [0.0f64; $lanes];
+                $store(out.as_mut_ptr(), self.0);
+                for e in out.iter_mut() {
+                    *e = math::ln(*e);
+                }
+                $name($load(out.as_ptr()))
+            }
+
+            #[inline(always)]
+            unsafe fn powf(self, n: f64) -> Self {
+                let mut out = [0.0f64; $lanes];
+                $store(out.as_mut_ptr(), self.0);
+                for e in out.iter_mut() {
+                    *e = math::powf(*e, n);
+                }
+                $name($load(out.as_ptr()))
+            }
+
+            #[inline(always)]
+            unsafe fn sum(self) -> f64 {
+                let mut out = [0.0f64; $lanes];
+                $store(out.as_mut_ptr(), self.0);
+                out.iter().sum()
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn cmp_gt_pd_256(a: __m256d, b: __m256d) -> __m256d {
+    _mm256_cmp_pd(a, b, _CMP_GT_OQ)
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl_float_vector!(
+    F64x2,
+    __m128d,
+    2,
+    _mm_loadu_pd,
+    _mm_storeu_pd,
+    _mm_set1_pd,
+    _mm_setzero_pd,
+    _mm_add_pd,
+    _mm_sub_pd,
+    _mm_mul_pd,
+    _mm_div_pd,
+    _mm_max_pd,
+    _mm_sqrt_pd,
+    _mm_and_pd,
+    _mm_cmpgt_pd
+);
+#[cfg(any(feature = "std", feature = "libm"))]
+impl_float_vector!(
+    F64x4,
+    __m256d,
+    4,
+    _mm256_loadu_pd,
+    _mm256_storeu_pd,
+    _mm256_set1_pd,
+    _mm256_setzero_pd,
+    _mm256_add_pd,
+    _mm256_sub_pd,
+    _mm256_mul_pd,
+    _mm256_div_pd,
+    _mm256_max_pd,
+    _mm256_sqrt_pd,
+    _mm256_and_pd,
+    cmp_gt_pd_256
+);
+
+////////////////////////////////////////////////////////////////////////////////
+// Generic kernels
+////////////////////////////////////////////////////////////////////////////////
+
+trait ZipOp {
+    unsafe fn apply<V: Vector>(a: V, b: V) -> V;
+}
+
+struct Min;
+struct Max;
+
+impl ZipOp for Min {
+    #[inline(always)]
+    unsafe fn apply<V: Vector>(a: V, b: V) -> V {
+        a.min(b)
+    }
+}
+
+impl ZipOp for Max {
+    #[inline(always)]
+    unsafe fn apply<V: Vector>(a: V, b: V) -> V {
+        a.max(b)
+    }
+}
+
+trait ZipPredicate {
+    unsafe fn test<V: Vector>(a: V, b: V) -> bool;
+}
+
+struct Disjoint;
+struct Subset;
+struct Superset;
+
+impl ZipPredicate for Disjoint {
+    #[inline(always)]
+    unsafe fn test<V: Vector>(a: V, b: V) -> bool {
+        a.min(b).is_zero()
+    }
+}
+
+impl ZipPredicate for Subset {
+    #[inline(always)]
+    unsafe fn test<V: Vector>(a: V, b: V) -> bool {
+        a.min(b).all_eq(a)
+    }
+}
+
+impl ZipPredicate for Superset {
+    #[inline(always)]
+    unsafe fn test<V: Vector>(a: V, b: V) -> bool {
+        a.max(b).all_eq(a)
+    }
+}
+
+// Copies the remainder of a slice which does not fill a whole vector into a
+// zeroed buffer, so it can be loaded as a vector.
+#[inline(always)]
+fn pad<E: Copy + Default>(rest: &[E]) -> [E; MAX_LANES] {
+    let mut buf = [E::default(); MAX_LANES];
+    buf[..rest.len()].copy_from_slice(rest);
+    buf
+}
+
+#[inline(always)]
+unsafe fn zip_map_kernel<V: Vector, O: ZipOp>(a: &[V::Elem], b: &[V::Elem], out: &mut [V::Elem]) {
+    // The loads and stores below are only in bounds if the lengths match.
+    assert_eq!(a.len(), b.len());
+    assert_eq!(a.len(), out.len());
+    let whole = a.len() - a.len() % V::LANES;
+    let mut i = 0;
+    while i < whole {
+        let res = O::apply(V::load(a.as_ptr().add(i)), V::load(b.as_ptr().add(i)));
+        res.store(out.as_mut_ptr().add(i));
+        i += V::LANES;
+    }
+    if whole < a.len() {
+        let (pad_a, pad_b) = (pad(&a[whole..]), pad(&b[whole..]));
+        let mut pad_out = [V::Elem::default(); MAX_LANES];
+        O::apply(V::load(pad_a.as_ptr()), V::load(pad_b.as_ptr())).store(pad_out.as_mut_ptr());
+        out[whole..].copy_from_slice(&pad_out[..a.len() - whole]);
+    }
+}
+
+#[inline(always)]
+unsafe fn zip_all_kernel<V: Vector, P: ZipPredicate>(a: &[V::Elem], b: &[V::Elem]) -> bool {
+    // The loads below are only in bounds if the lengths match.
+    assert_eq!(a.len(), b.len());
+    let whole = a.len() - a.len() % V::LANES;
+    let mut i = 0;
+    while i < whole {
+        if !P::test(V::load(a.as_ptr().add(i)), V::load(b.as_ptr().add(i))) {
+            return false;
+        }
+        i += V::LANES;
+    }
+    if whole < a.len() {
+        let (pad_a, pad_b) = (pad(&a[whole..]), pad(&b[whole..]));
+        return P::test(V::load(pad_a.as_ptr()), V::load(pad_b.as_ptr()));
+    }
+    true
+}
+
+#[inline(always)]
+unsafe fn total_kernel<V: Vector>(a: &[V::Elem]) -> u64 {
+    let whole = a.len() - a.len() % V::LANES;
+    let mut acc = V::zero();
+    let mut i = 0;
+    while i < whole {
+        acc = V::load(a.as_ptr().add(i)).widening_sum(acc);
+        i += V::LANES;
+    }
+    if whole < a.len() {
+        let pad_a = pad(&a[whole..]);
+        acc = V::load(pad_a.as_ptr()).widening_sum(acc);
+    }
+    acc.sum_u64()
+}
+
+#[inline(always)]
+unsafe fn widen_kernel<V: WidenLoad<E>, E: Copy + Default>(a: &[E], out: &mut [V::Elem]) {
+    // The loads and stores below are only in bounds if the lengths match.
+    assert_eq!(a.len(), out.len());
+    let whole = a.len() - a.len() % V::LANES;
+    let mut i = 0;
+    while i < whole {
+        V::widen_load(a.as_ptr().add(i)).store(out.as_mut_ptr().add(i));
+        i += V::LANES;
+    }
+    if whole < a.len() {
+        let pad_a = pad(&a[whole..]);
+        let mut pad_out = [V::Elem::default(); MAX_LANES];
+        V::widen_load(pad_a.as_ptr()).store(pad_out.as_mut_ptr());
+        out[whole..].copy_from_slice(&pad_out[..a.len() - whole]);
+    }
+}
+
+// A vector with every lane set to `V::MAX`, which the counts are compared with
+// or clamped to before they are narrowed.
+#[inline(always)]
+unsafe fn splat_max<V: NarrowStore<E>, E>() -> V {
+    V::load([V::MAX; MAX_LANES].as_ptr())
+}
+
+#[inline(always)]
+unsafe fn try_narrow_kernel<V: NarrowStore<E>, E>(a: &[V::Elem], out: &mut [E]) -> Result<(), Error>
+where
+    V::Elem: Counter,
+    E: Counter + TryFrom<V::Elem>,
+{
+    // The loads and stores below are only in bounds if the lengths match.
+    assert_eq!(a.len(), out.len());
+    let max = splat_max::<V, E>();
+    let whole = a.len() - a.len() % V::LANES;
+    let mut i = 0;
+    while i < whole {
+        let v = V::load(a.as_ptr().add(i));
+        if !v.min(max).all_eq(v) {
+            // The scalar kernel finds which count does not fit.
+            return scalar::try_cast_counts(a, out);
+        }
+        v.narrow_store(out.as_mut_ptr().add(i));
+        i += V::LANES;
+    }
+    if whole < a.len() {
+        let pad_a = pad(&a[whole..]);
+        let v = V::load(pad_a.as_ptr());
+        if !v.min(max).all_eq(v) {
+            return scalar::try_cast_counts(a, out);
+        }
+        let mut pad_out = [E::default(); MAX_LANES];
+        v.narrow_store(pad_out.as_mut_ptr());
+        out[whole..].copy_from_slice(&pad_out[..a.len() - whole]);
+    }
+    Ok(())
+}
+
+#[inline(always)]
+unsafe fn saturating_narrow_kernel<V: NarrowStore<E>, E: Copy + Default>(
+    a: &[V::Elem],
+    out: &mut [E],
+) {
+    // The loads and stores below are only in bounds if the lengths match.
+    assert_eq!(a.len(), out.len());
+    let max = splat_max::<V, E>();
+    let whole = a.len() - a.len() % V::LANES;
+    let mut i = 0;
+    while i < whole {
+        V::load(a.as_ptr().add(i))
+            .min(max)
+            .narrow_store(out.as_mut_ptr().add(i));
+        i += V::LANES;
+    }
+    if whole < a.len() {
+        let pad_a = pad(&a[whole..]);
+        let mut pad_out = [E::default(); MAX_LANES];
+        V::load(pad_a.as_ptr())
+            .min(max)
+            .narrow_store(pad_out.as_mut_ptr());
+        out[whole..].copy_from_slice(&pad_out[..a.len() - whole]);
+    }
+}
+
+// Converts the counts of a chunk of at most `F::LANES` counters to a float
+// vector, padding with zeros.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn load_f64<N: Counter, F: FloatVector>(chunk: &[N]) -> F {
+    let mut buf = [0.0f64; 4];
+    for (b, count) in buf.iter_mut().zip(chunk.iter()) {
+        *b = count.as_();
+    }
+    F::load(buf.as_ptr())
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn collision_entropy_kernel<N: Counter, F: FloatVector>(a: &[N], total: f64) -> f64 {
+    let total = F::splat(total);
+    let sum = a.chunks(F::LANES).fold(F::zero(), |acc, chunk| {
+        let prob = load_f64::<N, F>(chunk).div(total);
+        acc.add(prob.mul(prob))
+    });
+    -math::log2(sum.sum())
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn shannon_entropy_kernel<N: Counter, F: FloatVector>(a: &[N], total: f64) -> f64 {
+    let total = F::splat(total);
+    let sum = a.chunks(F::LANES).fold(F::zero(), |acc, chunk| {
+        let prob = load_f64::<N, F>(chunk).div(total);
+        acc.add(prob.mul(prob.ln()).select_positive(prob))
+    });
+    -sum.sum()
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn power_sum_kernel<N: Counter, F: FloatVector>(a: &[N], total: f64, alpha: f64) -> f64 {
+    let total = F::splat(total);
+    a.chunks(F::LANES)
+        .fold(F::zero(), |acc, chunk| {
+            acc.add(load_f64::<N, F>(chunk).div(total).powf(alpha))
+        })
+        .sum()
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn dot_kernel<N: Counter, F: FloatVector>(a: &[N], b: &[N]) -> f64 {
+    a.chunks(F::LANES)
+        .zip(b.chunks(F::LANES))
+        .fold(F::zero(), |acc, (a, b)| {
+            acc.add(load_f64::<N, F>(a).mul(load_f64::<N, F>(b)))
+        })
+        .sum()
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn sqrt_dot_kernel<N: Counter, F: FloatVector>(a: &[N], b: &[N]) -> f64 {
+    a.chunks(F::LANES)
+        .zip(b.chunks(F::LANES))
+        .fold(F::zero(), |acc, (a, b)| {
+            acc.add(load_f64::<N, F>(a).mul(load_f64::<N, F>(b)).sqrt())
+        })
+        .sum()
+}
+
+// The term of a divergence for vectors of the probabilities of both
+// distributions. The kernel sums the terms and multiplies the sum by `SCALE`.
+#[cfg(any(feature = "std", feature = "libm"))]
+trait Divergence {
+    const SCALE: f64;
+    unsafe fn term<F: FloatVector>(p: F, q: F) -> F;
+    fn scalar<N: Counter>(a: &[N], b: &[N], total_a: f64, total_b: f64, pseudocount: f64) -> f64;
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+struct KlDivergence;
+#[cfg(any(feature = "std", feature = "libm"))]
+struct CrossEntropy;
+#[cfg(any(feature = "std", feature = "libm"))]
+struct JensenShannon;
+#[cfg(any(feature = "std", feature = "libm"))]
+struct TotalVariation;
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl Divergence for KlDivergence {
+    const SCALE: f64 = 1.0;
+
+    #[inline(always)]
+    unsafe fn term<F: FloatVector>(p: F, q: F) -> F {
+        p.mul(p.div(q).ln()).select_positive(p)
+    }
+
+    #[inline(always)]
+    fn scalar<N: Counter>(a: &[N], b: &[N], total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+        scalar::kl_divergence(a, b, total_a, total_b, pseudocount)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl Divergence for CrossEntropy {
+    const SCALE: f64 = -1.0;
+
+    #[inline(always)]
+    unsafe fn term<F: FloatVector>(p: F, q: F) -> F {
+        p.mul(q.ln()).select_positive(p)
+    }
+
+    #[inline(always)]
+    fn scalar<N: Counter>(a: &[N], b: &[N], total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+        scalar::cross_entropy(a, b, total_a, total_b, pseudocount)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl Divergence for JensenShannon {
+    const SCALE: f64 = 0.5;
+
+    #[inline(always)]
+    unsafe fn term<F: FloatVector>(p: F, q: F) -> F {
+        let m = p.add(q).mul(F::splat(0.5));
+        let p_term = p.mul(p.div(m).ln()).select_positive(p);
+        let q_term = q.mul(q.div(m).ln()).select_positive(q);
+        p_term.add(q_term)
+    }
+
+    #[inline(always)]
+    fn scalar<N: Counter>(a: &[N], b: &[N], total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+        scalar::jensen_shannon_divergence(a, b, total_a, total_b, pseudocount)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl Divergence for TotalVariation {
+    const SCALE: f64 = 0.5;
+
+    #[inline(always)]
+    unsafe fn term<F: FloatVector>(p: F, q: F) -> F {
+        p.sub(q).max(q.sub(p))
+    }
+
+    #[inline(always)]
+    fn scalar<N: Counter>(a: &[N], b: &[N], total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+        scalar::total_variation(a, b, total_a, total_b, pseudocount)
+    }
+}
+
+// Padding with zero counts would add the terms of the pseudocounts of elements
+// which do not exist, so the counts which do not fill a whole vector are left
+// to the scalar kernel.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline(always)]
+unsafe fn divergence_kernel<N: Counter, F: FloatVector, D: Divergence>(
+    a: &[N],
+    b: &[N],
+    total_a: f64,
+    total_b: f64,
+    pseudocount: f64,
+) -> f64 {
+    let whole = a.len() - a.len() % F::LANES;
+    let (pseudocount_v, total_a_v, total_b_v) =
+        (F::splat(pseudocount), F::splat(total_a), F::splat(total_b));
+    let sum = a[..whole]
+        .chunks_exact(F::LANES)
+        .zip(b[..whole].chunks_exact(F::LANES))
+        .fold(F::zero(), |acc, (a, b)| {
+            let p = load_f64::<N, F>(a).add(pseudocount_v).div(total_a_v);
+            let q = load_f64::<N, F>(b).add(pseudocount_v).div(total_b_v);
+            acc.add(D::term(p, q))
+        });
+    D::SCALE * sum.sum() + D::scalar(&a[whole..], &b[whole..], total_a, total_b, pseudocount)
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Dispatch
+////////////////////////////////////////////////////////////////////////////////
+
+// Generates a target feature enabled version of `$kernel` for each of AVX2,
+// AVX and SSE4.2, and a function which calls the best one the cpu supports or
+// `$fallback` if it supports none of them.
+macro_rules! arch_dispatch {
+    (avx2 = $avx2:ty, avx = $avx:ty, sse42 = $sse42:ty;
+    fn $name:ident($($arg:ident: $typ:ty),*) $(-> $ret:ty)? = $kernel:ident::<$($k:ty),*>, else $fallback:path) => {
+        paste::paste! {
+            #[target_feature(enable = "avx2")]
+            unsafe fn [<$name _avx2>]($($arg: $typ),*) $(-> $ret)? {
+                $kernel::<$avx2 $(, $k)*>($($arg),*)
+            }
+
+            #[target_feature(enable = "avx")]
+            unsafe fn [<$name _avx>]($($arg: $typ),*) $(-> $ret)? {
+                $kernel::<$avx $(, $k)*>($($arg),*)
+            }
+
+            #[target_feature(enable = "sse4.2")]
+            unsafe fn [<$name _sse42>]($($arg: $typ),*) $(-> $ret)? {
+                $kernel::<$sse42 $(, $k)*>($($arg),*)
+            }
+
+            #[inline]
+            fn $name($($arg: $typ),*) $(-> $ret)? {
+                // Safety: `backend::current` only returns backends which the
+                // cpu supports.
+                unsafe {
+                    match backend::current() {
+                        Backend::Avx2 => [<$name _avx2>]($($arg),*),
+                        Backend::Avx => [<$name _avx>]($($arg),*),
+                        Backend::Sse42 => [<$name _sse42>]($($arg),*),
+                        Backend::Scalar => $fallback($($arg),*),
+                    }
+                }
+            }
+        }
+    };
+}
+
+macro_rules! arch_dispatch_int {
+    ($elem:ty, $v256:ty, $v128:ty) => {
+        paste::paste! {
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<intersection_ $elem>](a: &[$elem], b: &[$elem], out: &mut [$elem])
+                    = zip_map_kernel::<Min>, else scalar::intersection
+            }
+
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<union_ $elem>](a: &[$elem], b: &[$elem], out: &mut [$elem])
+                    = zip_map_kernel::<Max>, else scalar::union
+            }
+
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<is_disjoint_ $elem>](a: &[$elem], b: &[$elem]) -> bool
+                    = zip_all_kernel::<Disjoint>, else scalar::is_disjoint
+            }
+
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<is_subset_ $elem>](a: &[$elem], b: &[$elem]) -> bool
+                    = zip_all_kernel::<Subset>, else scalar::is_subset
+            }
+
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<is_superset_ $elem>](a: &[$elem], b: &[$elem]) -> bool
+                    = zip_all_kernel::<Superset>, else scalar::is_superset
+            }
+
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<total_ $elem>](a: &[$elem]) -> u64 = total_kernel::<>, else total_fallback
+            }
+        }
+    };
+}
+
+#[inline]
+fn total_fallback<U: Copy + Into<u64>>(a: &[U]) -> u64 {
+    a.iter().fold(0u64, |acc, e| acc.wrapping_add((*e).into()))
+}
+
+arch_dispatch_int!(u8, U8x32, U8x16);
+arch_dispatch_int!(u16, U16x16, U16x8);
+arch_dispatch_int!(u32, U32x8, U32x4);
+arch_dispatch_int!(u64, U64x4, U64x2);
+
+macro_rules! arch_dispatch_widen {
+    ($from:ty, $to:ty, $v256:ty, $v128:ty) => {
+        paste::paste! {
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<cast_counts_ $from _ $to>](a: &[$from], out: &mut [$to])
+                    = widen_kernel::<$from>, else scalar::cast_counts
+            }
+        }
+    };
+}
+
+arch_dispatch_widen!(u8, u16, U16x16, U16x8);
+arch_dispatch_widen!(u8, u32, U32x8, U32x4);
+arch_dispatch_widen!(u8, u64, U64x4, U64x2);
+arch_dispatch_widen!(u16, u32, U32x8, U32x4);
+arch_dispatch_widen!(u16, u64, U64x4, U64x2);
+arch_dispatch_widen!(u32, u64, U64x4, U64x2);
+
+macro_rules! arch_dispatch_narrow {
+    ($from:ty, $to:ty, $v256:ty, $v128:ty) => {
+        paste::paste! {
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<try_cast_counts_ $from _ $to>](a: &[$from], out: &mut [$to]) -> Result<(), Error>
+                    = try_narrow_kernel::<$to>, else scalar::try_cast_counts
+            }
+
+            arch_dispatch! {
+                avx2 = $v256, avx = $v128, sse42 = $v128;
+                fn [<saturating_cast_counts_ $from _ $to>](a: &[$from], out: &mut [$to])
+                    = saturating_narrow_kernel::<$to>, else scalar::saturating_cast_counts
+            }
+        }
+    };
+}
+
+arch_dispatch_narrow!(u16, u8, U16x16, U16x8);
+arch_dispatch_narrow!(u32, u8, U32x8, U32x4);
+arch_dispatch_narrow!(u32, u16, U32x8, U32x4);
+arch_dispatch_narrow!(u64, u8, U64x4, U64x2);
+arch_dispatch_narrow!(u64, u16, U64x4, U64x2);
+arch_dispatch_narrow!(u64, u32, U64x4, U64x2);
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! arch_dispatch_float {
+    ($name:ident, $kernel:ident $(, $arg:ident: $typ:ty)*) => {
+        paste::paste! {
+            #[target_feature(enable = "avx2")]
+            unsafe fn [<$name _avx2>]<N: Counter>(a: &[N], total: f64 $(, $arg: $typ)*) -> f64 {
+                $kernel::<N, F64x4>(a, total $(, $arg)*)
+            }
+
+            #[target_feature(enable = "avx")]
+            unsafe fn [<$name _avx>]<N: Counter>(a: &[N], total: f64 $(, $arg: $typ)*) -> f64 {
+                $kernel::<N, F64x4>(a, total $(, $arg)*)
+            }
+
+            #[target_feature(enable = "sse4.2")]
+            unsafe fn [<$name _sse42>]<N: Counter>(a: &[N], total: f64 $(, $arg: $typ)*) -> f64 {
+                $kernel::<N, F64x2>(a, total $(, $arg)*)
+            }
+
+            #[inline]
+            pub(crate) fn $name<N: Counter>(a: &[N], total: f64 $(, $arg: $typ)*) -> f64 {
+                // Safety: `backend::current` only returns backends which the
+                // cpu supports.
+                unsafe {
+                    match backend::current() {
+                        Backend::Avx2 => [<$name _avx2>](a, total $(, $arg)*),
+                        Backend::Avx => [<$name _avx>](a, total $(, $arg)*),
+                        Backend::Sse42 => [<$name _sse42>](a, total $(, $arg)*),
+                        Backend::Scalar => scalar::$name(a, total $(, $arg)*),
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_float!(collision_entropy, collision_entropy_kernel);
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_float!(shannon_entropy, shannon_entropy_kernel);
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_float!(power_sum, power_sum_kernel, alpha: f64);
+
+// As `arch_dispatch_float`, for kernels which combine two counter slices.
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! arch_dispatch_float_zip {
+    ($name:ident, $kernel:ident) => {
+        paste::paste! {
+            #[target_feature(enable = "avx2")]
+            unsafe fn [<$name _avx2>]<N: Counter>(a: &[N], b: &[N]) -> f64 {
+                $kernel::<N, F64x4>(a, b)
+            }
+
+            #[target_feature(enable = "avx")]
+            unsafe fn [<$name _avx>]<N: Counter>(a: &[N], b: &[N]) -> f64 {
+                $kernel::<N, F64x4>(a, b)
+            }
+
+            #[target_feature(enable = "sse4.2")]
+            unsafe fn [<$name _sse42>]<N: Counter>(a: &[N], b: &[N]) -> f64 {
+                $kernel::<N, F64x2>(a, b)
+            }
+
+            #[inline]
+            pub(crate) fn $name<N: Counter>(a: &[N], b: &[N]) -> f64 {
+                // Safety: `backend::current` only returns backends which the
+                // cpu supports.
+                unsafe {
+                    match backend::current() {
+                        Backend::Avx2 => [<$name _avx2>](a, b),
+                        Backend::Avx => [<$name _avx>](a, b),
+                        Backend::Sse42 => [<$name _sse42>](a, b),
+                        Backend::Scalar => scalar::$name(a, b),
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_float_zip!(dot, dot_kernel);
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_float_zip!(sqrt_dot, sqrt_dot_kernel);
+
+// As `arch_dispatch_float`, for the divergence kernels.
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! arch_dispatch_divergence {
+    ($name:ident, $divergence:ty) => {
+        paste::paste! {
+            #[target_feature(enable = "avx2")]
+            unsafe fn [<$name _avx2>]<N: Counter>(
+                a: &[N],
+                b: &[N],
+                total_a: f64,
+                total_b: f64,
+                pseudocount: f64,
+            ) -> f64 {
+                divergence_kernel::<N, F64x4, $divergence>(a, b, total_a, total_b, pseudocount)
+            }
+
+            #[target_feature(enable = "avx")]
+            unsafe fn [<$name _avx>]<N: Counter>(
+                a: &[N],
+                b: &[N],
+                total_a: f64,
+                total_b: f64,
+                pseudocount: f64,
+            ) -> f64 {
+                divergence_kernel::<N, F64x4, $divergence>(a, b, total_a, total_b, pseudocount)
+            }
+
+            #[target_feature(enable = "sse4.2")]
+            unsafe fn [<$name _sse42>]<N: Counter>(
+                a: &[N],
+                b: &[N],
+                total_a: f64,
+                total_b: f64,
+                pseudocount: f64,
+            ) -> f64 {
+                divergence_kernel::<N, F64x2, $divergence>(a, b, total_a, total_b, pseudocount)
+            }
+
+            #[inline]
+            pub(crate) fn $name<N: Counter>(
+                a: &[N],
+                b: &[N],
+                total_a: f64,
+                total_b: f64,
+                pseudocount: f64,
+            ) -> f64 {
+                // Safety: `backend::current` only returns backends which the
+                // cpu supports.
+                unsafe {
+                    match backend::current() {
+                        Backend::Avx2 => [<$name _avx2>](a, b, total_a, total_b, pseudocount),
+                        Backend::Avx => [<$name _avx>](a, b, total_a, total_b, pseudocount),
+                        Backend::Sse42 => [<$name _sse42>](a, b, total_a, total_b, pseudocount),
+                        Backend::Scalar => scalar::$name(a, b, total_a, total_b, pseudocount),
+                    }
+                }
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_divergence!(kl_divergence, KlDivergence);
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_divergence!(cross_entropy, CrossEntropy);
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_divergence!(jensen_shannon_divergence, JensenShannon);
+#[cfg(any(feature = "std", feature = "libm"))]
+arch_dispatch_divergence!(total_variation, TotalVariation);
+
+////////////////////////////////////////////////////////////////////////////////
+// Kernels for counter slices
+////////////////////////////////////////////////////////////////////////////////
+
+// Reinterprets a counter slice as a slice of the unsigned integer `U`.
+//
+// Safety: `U` must be the unsigned integer with the same size as `N`. As
+// `Counter` is only implemented for unsigned integers they then have the same
+// representation.
+#[inline(always)]
+unsafe fn cast<N, U>(slice: &[N]) -> &[U] {
+    debug_assert_eq!(size_of::<N>(), size_of::<U>());
+    core::slice::from_raw_parts(slice.as_ptr() as *const U, slice.len())
+}
+
+#[inline(always)]
+unsafe fn cast_mut<N, U>(slice: &mut [N]) -> &mut [U] {
+    debug_assert_eq!(size_of::<N>(), size_of::<U>());
+    core::slice::from_raw_parts_mut(slice.as_mut_ptr() as *mut U, slice.len())
+}
+
+// Calls the kernel for the unsigned integer with the same size as `N`, or
+// evaluates `$fallback` for u128 counters.
+macro_rules! by_size {
+    ($N:ty, $name:ident($($arg:expr => $cast:ident),*), $fallback:expr) => {
+        paste::paste! {
+            // Safety: the kernel is chosen by the size of `N`, see `cast`.
+            match size_of::<$N>() {
+                1 => unsafe { [<$name _u8>]($($cast::<$N, u8>($arg)),*) },
+                2 => unsafe { [<$name _u16>]($($cast::<$N, u16>($arg)),*) },
+                4 => unsafe { [<$name _u32>]($($cast::<$N, u32>($arg)),*) },
+                8 => unsafe { [<$name _u64>]($($cast::<$N, u64>($arg)),*) },
+                _ => $fallback,
+            }
+        }
+    };
+}
+
+#[inline]
+pub(crate) fn intersection<N: Counter>(a: &[N], b: &[N], out: &mut [N]) {
+    by_size!(
+        N,
+        intersection(a => cast, b => cast, out => cast_mut),
+        scalar::intersection(a, b, out)
+    )
+}
+
+#[inline]
+pub(crate) fn union<N: Counter>(a: &[N], b: &[N], out: &mut [N]) {
+    by_size!(
+        N,
+        union(a => cast, b => cast, out => cast_mut),
+        scalar::union(a, b, out)
+    )
+}
+
+#[inline]
+pub(crate) fn is_disjoint<N: Counter>(a: &[N], b: &[N]) -> bool {
+    by_size!(
+        N,
+        is_disjoint(a => cast, b => cast),
+        scalar::is_disjoint(a, b)
+    )
+}
+
+#[inline]
+pub(crate) fn is_subset<N: Counter>(a: &[N], b: &[N]) -> bool {
+    by_size!(N, is_subset(a => cast, b => cast), scalar::is_subset(a, b))
+}
+
+#[inline]
+pub(crate) fn is_superset<N: Counter>(a: &[N], b: &[N]) -> bool {
+    by_size!(
+        N,
+        is_superset(a => cast, b => cast),
+        scalar::is_superset(a, b)
+    )
+}
+
+#[inline]
+pub(crate) fn is_any_lesser<N: Counter>(a: &[N], b: &[N]) -> bool {
+    !is_superset(a, b)
+}
+
+#[inline]
+pub(crate) fn is_any_greater<N: Counter>(a: &[N], b: &[N]) -> bool {
+    !is_subset(a, b)
+}
+
+#[inline]
+pub(crate) fn total<N: Counter>(a: &[N]) -> usize {
+    // The kernels sum into u64, which only needs checking where usize is
+    // narrower.
+    let total = by_size!(N, total(a => cast), return scalar::total(a));
+    scalar::total_usize(total.into())
+}
+
+// Converts the counts with the widening kernels if `N2` is at least as wide as
+// `N`, and returns whether it was.
+#[inline]
+fn widen_counts<N: Counter, N2: Counter>(a: &[N], out: &mut [N2]) -> bool {
+    // Safety: the kernel is chosen by the sizes of `N` and `N2`, see `cast`.
+    // Counters of the same size are the same type, so are copied as is.
+    unsafe {
+        match (size_of::<N>(), size_of::<N2>()) {
+            (from, to) if from == to => out.copy_from_slice(cast(a)),
+            (1, 2) => cast_counts_u8_u16(cast(a), cast_mut(out)),
+            (1, 4) => cast_counts_u8_u32(cast(a), cast_mut(out)),
+            (1, 8) => cast_counts_u8_u64(cast(a), cast_mut(out)),
+            (2, 4) => cast_counts_u16_u32(cast(a), cast_mut(out)),
+            (2, 8) => cast_counts_u16_u64(cast(a), cast_mut(out)),
+            (4, 8) => cast_counts_u32_u64(cast(a), cast_mut(out)),
+            _ => return false,
+        }
+    }
+    true
+}
+
+#[inline]
+pub(crate) fn cast_counts<N: Counter, N2: Counter + From<N>>(a: &[N], out: &mut [N2]) {
+    if !widen_counts(a, out) {
+        scalar::cast_counts(a, out)
+    }
+}
+
+#[inline]
+pub(crate) fn try_cast_counts<N: Counter, N2: Counter + TryFrom<N>>(
+    a: &[N],
+    out: &mut [N2],
+) -> Result<(), Error> {
+    if widen_counts(a, out) {
+        return Ok(());
+    }
+    // Safety: the kernel is chosen by the sizes of `N` and `N2`, see `cast`.
+    unsafe {
+        match (size_of::<N>(), size_of::<N2>()) {
+            (2, 1) => try_cast_counts_u16_u8(cast(a), cast_mut(out)),
+            (4, 1) => try_cast_counts_u32_u8(cast(a), cast_mut(out)),
+            (4, 2) => try_cast_counts_u32_u16(cast(a), cast_mut(out)),
+            (8, 1) => try_cast_counts_u64_u8(cast(a), cast_mut(out)),
+            (8, 2) => try_cast_counts_u64_u16(cast(a), cast_mut(out)),
+            (8, 4) => try_cast_counts_u64_u32(cast(a), cast_mut(out)),
+            _ => scalar::try_cast_counts(a, out),
+        }
+    }
+}
+
+#[inline]
+pub(crate) fn saturating_cast_counts<N: Counter, N2: Counter + TryFrom<N>>(
+    a: &[N],
+    out: &mut [N2],
+) {
+    if widen_counts(a, out) {
+        return;
+    }
+    // Safety: the kernel is chosen by the sizes of `N` and `N2`, see `cast`.
+    unsafe {
+        match (size_of::<N>(), size_of::<N2>()) {
+            (2, 1) => saturating_cast_counts_u16_u8(cast(a), cast_mut(out)),
+            (4, 1) => saturating_cast_counts_u32_u8(cast(a), cast_mut(out)),
+            (4, 2) => saturating_cast_counts_u32_u16(cast(a), cast_mut(out)),
+            (8, 1) => saturating_cast_counts_u64_u8(cast(a), cast_mut(out)),
+            (8, 2) => saturating_cast_counts_u64_u16(cast(a), cast_mut(out)),
+            (8, 4) => saturating_cast_counts_u64_u32(cast(a), cast_mut(out)),
+            _ => scalar::saturating_cast_counts(a, out),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Counts which exercise both the low and high values of each lane, along
+    // with zeros so that disjointness and entropy masking are covered.
+    fn counts<U: Counter>(len: usize, seed: u64) -> Vec<U> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state
+                    .wrapping_mul(6364136223846793005)
+                    .wrapping_add(1442695040888963407);
+                match state >> 61 {
+                    0 | 1 => U::zero(),
+                    2 => U::max_value(),
+                    _ => U::from_u64(state >> 62).unwrap(),
+                }
+            })
+            .collect()
+    }
+
+    macro_rules! test_levels {
+        ($test:ident, $elem:ty) => {
+            paste::paste! {
+                #[test]
+                fn $test() {
+                    let levels = [
+                        ("avx2", is_x86_feature_detected!("avx2")),
+                        ("avx", is_x86_feature_detected!("avx")),
+                        ("sse4.2", is_x86_feature_detected!("sse4.2")),
+                    ];
+                    for len in 0..70 {
+                        let a = counts::<$elem>(len, len as u64);
+                        let b = counts::<$elem>(len, len as u64 + 100);
+                        let mut out = vec![0; len];
+                        let mut expected = vec![0; len];
+                        for &(level, detected) in levels.iter() {
+                            if !detected {
+                                continue;
+                            }
+                            unsafe {
+                                let (inter, uni, disjoint, subset, superset, total):
+                                    (
+                                        unsafe fn(&[$elem], &[$elem], &mut [$elem]),
+                                        unsafe fn(&[$elem], &[$elem], &mut [$elem]),
+                                        unsafe fn(&[$elem], &[$elem]) -> bool,
+                                        unsafe fn(&[$elem], &[$elem]) -> bool,
+                                        unsafe fn(&[$elem], &[$elem]) -> bool,
+                                        unsafe fn(&[$elem]) -> u64,
+                                    ) = match level {
+                                    "avx2" => (
+                                        [<intersection_ $elem _avx2>],
+                                        [<union_ $elem _avx2>],
+                                        [<is_disjoint_ $elem _avx2>],
+                                        [<is_subset_ $elem _avx2>],
+                                        [<is_superset_ $elem _avx2>],
+                                        [<total_ $elem _avx2>],
+                                    ),
+                                    "avx" => (
+                                        [<intersection_ $elem _avx>],
+                                        [<union_ $elem _avx>],
+                                        [<is_disjoint_ $elem _avx>],
+                                        [<is_subset_ $elem _avx>],
+                                        [<is_superset_ $elem _avx>],
+                                        [<total_ $elem _avx>],
+                                    ),
+                                    _ => (
+                                        [<intersection_ $elem _sse42>],
+                                        [<union_ $elem _sse42>],
+                                        [<is_disjoint_ $elem _sse42>],
+                                        [<is_subset_ $elem _sse42>],
+                                        [<is_superset_ $elem _sse42>],
+                                        [<total_ $elem _sse42>],
+                                    ),
+                                };
+
+                                inter(&a, &b, &mut out);
+                                scalar::intersection(&a, &b, &mut expected);
+                                assert_eq!(out, expected, "{} {}", level, len);
+                                uni(&a, &b, &mut out);
+                                scalar::union(&a, &b, &mut expected);
+                                assert_eq!(out, expected, "{} {}", level, len);
+
+                                for (x, y) in [(&a, &b), (&a, &expected), (&expected, &a)].iter() {
+                                    assert_eq!(disjoint(x, y), scalar::is_disjoint(x, y));
+                                    assert_eq!(subset(x, y), scalar::is_subset(x, y));
+                                    assert_eq!(superset(x, y), scalar::is_superset(x, y));
+                                }
+                                assert!(disjoint(&a, &vec![0; len]));
+                                assert!(subset(&a, &a) && superset(&a, &a));
+                                assert_eq!(total(&a), total_fallback(&a), "{} {}", level, len);
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    test_levels!(test_levels_u8, u8);
+    test_levels!(test_levels_u16, u16);
+    test_levels!(test_levels_u32, u32);
+    test_levels!(test_levels_u64, u64);
+
+    macro_rules! test_cast_levels {
+        ($test:ident, $from:ty, $to:ty) => {
+            paste::paste! {
+                #[test]
+                fn $test() {
+                    for len in 0..70 {
+                        let a = counts::<$from>(len, len as u64);
+                        let mut expected = vec![0; len];
+                        scalar::cast_counts(&a, &mut expected);
+                        let mut out = vec![0; len];
+                        unsafe {
+                            if is_x86_feature_detected!("avx2") {
+                                [<cast_counts_ $from _ $to _avx2>](&a, &mut out);
+                                assert_eq!(out, expected, "avx2 {}", len);
+                            }
+                            if is_x86_feature_detected!("avx") {
+                                [<cast_counts_ $from _ $to _avx>](&a, &mut out);
+                                assert_eq!(out, expected, "avx {}", len);
+                            }
+                            if is_x86_feature_detected!("sse4.2") {
+                                [<cast_counts_ $from _ $to _sse42>](&a, &mut out);
+                                assert_eq!(out, expected, "sse4.2 {}", len);
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    test_cast_levels!(test_cast_levels_u8_u16, u8, u16);
+    test_cast_levels!(test_cast_levels_u8_u32, u8, u32);
+    test_cast_levels!(test_cast_levels_u8_u64, u8, u64);
+    test_cast_levels!(test_cast_levels_u16_u32, u16, u32);
+    test_cast_levels!(test_cast_levels_u16_u64, u16, u64);
+    test_cast_levels!(test_cast_levels_u32_u64, u32, u64);
+
+    macro_rules! test_narrow_levels {
+        ($test:ident, $from:ty, $to:ty) => {
+            paste::paste! {
+                #[test]
+                fn $test() {
+                    let levels: [(
+                        &str,
+                        bool,
+                        unsafe fn(&[$from], &mut [$to]) -> Result<(), Error>,
+                        unsafe fn(&[$from], &mut [$to]),
+                    ); 3] = [
+                        (
+                            "avx2",
+                            is_x86_feature_detected!("avx2"),
+                            [<try_cast_counts_ $from _ $to _avx2>],
+                            [<saturating_cast_counts_ $from _ $to _avx2>],
+                        ),
+                        (
+                            "avx",
+                            is_x86_feature_detected!("avx"),
+                            [<try_cast_counts_ $from _ $to _avx>],
+                            [<saturating_cast_counts_ $from _ $to _avx>],
+                        ),
+                        (
+                            "sse4.2",
+                            is_x86_feature_detected!("sse4.2"),
+                            [<try_cast_counts_ $from _ $to _sse42>],
+                            [<saturating_cast_counts_ $from _ $to _sse42>],
+                        ),
+                    ];
+                    for len in 0..70 {
+                        let a = counts::<$from>(len, len as u64);
+                        // The same counts clamped so that every one fits.
+                        let fits: Vec<$from> =
+                            a.iter().map(|e| (*e).min(<$to>::MAX as $from)).collect();
+                        for &(level, detected, try_cast, saturating_cast) in levels.iter() {
+                            if !detected {
+                                continue;
+                            }
+                            for x in [&a, &fits].iter() {
+                                let mut out = vec![0; len];
+                                let mut expected = vec![0; len];
+                                unsafe {
+                                    assert_eq!(
+                                        try_cast(x, &mut out),
+                                        scalar::try_cast_counts(x, &mut expected),
+                                        "{} {}",
+                                        level,
+                                        len
+                                    );
+                                    assert_eq!(out, expected, "{} {}", level, len);
+                                    saturating_cast(x, &mut out);
+                                    scalar::saturating_cast_counts(x, &mut expected);
+                                    assert_eq!(out, expected, "{} {}", level, len);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        };
+    }
+
+    test_narrow_levels!(test_narrow_levels_u16_u8, u16, u8);
+    test_narrow_levels!(test_narrow_levels_u32_u8, u32, u8);
+    test_narrow_levels!(test_narrow_levels_u32_u16, u32, u16);
+    test_narrow_levels!(test_narrow_levels_u64_u8, u64, u8);
+    test_narrow_levels!(test_narrow_levels_u64_u16, u64, u16);
+    test_narrow_levels!(test_narrow_levels_u64_u32, u64, u32);
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_entropy_levels() {
+        for len in 1..70 {
+            let mut a = counts::<u32>(len, len as u64);
+            a[0] = 1;
+            let total = a.iter().map(|e| *e as f64).sum();
+            let collision = scalar::collision_entropy(&a, total);
+            let shannon = scalar::shannon_entropy(&a, total);
+            unsafe {
+                if is_x86_feature_detected!("avx2") {
+                    approx::assert_relative_eq!(
+                        collision_entropy_avx2(&a, total),
+                        collision,
+                        epsilon = 1e-12
+                    );
+                    approx::assert_relative_eq!(
+                        shannon_entropy_avx2(&a, total),
+                        shannon,
+                        epsilon = 1e-12
+                    );
+                }
+                if is_x86_feature_detected!("avx") {
+                    approx::assert_relative_eq!(
+                        collision_entropy_avx(&a, total),
+                        collision,
+                        epsilon = 1e-12
+                    );
+                    approx::assert_relative_eq!(
+                        shannon_entropy_avx(&a, total),
+                        shannon,
+                        epsilon = 1e-12
+                    );
+                }
+                if is_x86_feature_detected!("sse4.2") {
+                    approx::assert_relative_eq!(
+                        collision_entropy_sse42(&a, total),
+                        collision,
+                        epsilon = 1e-12
+                    );
+                    approx::assert_relative_eq!(
+                        shannon_entropy_sse42(&a, total),
+                        shannon,
+                        epsilon = 1e-12
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_power_sum_levels() {
+        for len in 1..70 {
+            let mut a = counts::<u32>(len, len as u64);
+            a[0] = 1;
+            let total = a.iter().map(|e| *e as f64).sum();
+            for &alpha in [0.5, 3.0].iter() {
+                let expected = scalar::power_sum(&a, total, alpha);
+                unsafe {
+                    if is_x86_feature_detected!("avx2") {
+                        approx::assert_relative_eq!(
+                            power_sum_avx2(&a, total, alpha),
+                            expected,
+                            max_relative = 1e-12
+                        );
+                    }
+                    if is_x86_feature_detected!("avx") {
+                        approx::assert_relative_eq!(
+                            power_sum_avx(&a, total, alpha),
+                            expected,
+                            max_relative = 1e-12
+                        );
+                    }
+                    if is_x86_feature_detected!("sse4.2") {
+                        approx::assert_relative_eq!(
+                            power_sum_sse42(&a, total, alpha),
+                            expected,
+                            max_relative = 1e-12
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_dot_levels() {
+        for len in 1..70 {
+            let a = counts::<u32>(len, len as u64);
+            let b = counts::<u32>(len, len as u64 + 1);
+            let dot = scalar::dot(&a, &b);
+            let sqrt_dot = scalar::sqrt_dot(&a, &b);
+            unsafe {
+                if is_x86_feature_detected!("avx2") {
+                    approx::assert_relative_eq!(dot_avx2(&a, &b), dot, max_relative = 1e-12);
+                    approx::assert_relative_eq!(
+                        sqrt_dot_avx2(&a, &b),
+                        sqrt_dot,
+                        max_relative = 1e-12
+                    );
+                }
+                if is_x86_feature_detected!("avx") {
+                    approx::assert_relative_eq!(dot_avx(&a, &b), dot, max_relative = 1e-12);
+                    approx::assert_relative_eq!(
+                        sqrt_dot_avx(&a, &b),
+                        sqrt_dot,
+                        max_relative = 1e-12
+                    );
+                }
+                if is_x86_feature_detected!("sse4.2") {
+                    approx::assert_relative_eq!(dot_sse42(&a, &b), dot, max_relative = 1e-12);
+                    approx::assert_relative_eq!(
+                        sqrt_dot_sse42(&a, &b),
+                        sqrt_dot,
+                        max_relative = 1e-12
+                    );
+                }
+            }
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_divergence_levels() {
+        for len in 1..70 {
+            let a = counts::<u32>(len, len as u64);
+            let b = counts::<u32>(len, len as u64 + 1);
+            // Without a pseudocount the zeros give infinite divergences.
+            for &pseudocount in [0.0, 1.0].iter() {
+                let total_a = a.iter().map(|e| *e as f64).sum::<f64>() + pseudocount * len as f64;
+                let total_b = b.iter().map(|e| *e as f64).sum::<f64>() + pseudocount * len as f64;
+                macro_rules! check {
+                    ($name:ident) => {
+                        paste::paste! {
+                            let expected = scalar::$name(&a, &b, total_a, total_b, pseudocount);
+                            unsafe {
+                                if is_x86_feature_detected!("avx2") {
+                                    approx::assert_relative_eq!(
+                                        [<$name _avx2>](&a, &b, total_a, total_b, pseudocount),
+                                        expected,
+                                        epsilon = 1e-12
+                                    );
+                                }
+                                if is_x86_feature_detected!("avx") {
+                                    approx::assert_relative_eq!(
+                                        [<$name _avx>](&a, &b, total_a, total_b, pseudocount),
+                                        expected,
+                                        epsilon = 1e-12
+                                    );
+                                }
+                                if is_x86_feature_detected!("sse4.2") {
+                                    approx::assert_relative_eq!(
+                                        [<$name _sse42>](&a, &b, total_a, total_b, pseudocount),
+                                        expected,
+                                        epsilon = 1e-12
+                                    );
+                                }
+                            }
+                        }
+                    };
+                }
+                check!(kl_divergence);
+                check!(cross_entropy);
+                check!(jensen_shannon_divergence);
+                check!(total_variation);
+            }
+        }
+    }
+
+    #[test]
+    fn test_length_mismatch() {
+        if !is_x86_feature_detected!("sse4.2") {
+            return;
+        }
+        let a = counts::<u32>(37, 1);
+        let short = counts::<u32>(33, 2);
+        let (a2, short2) = (a.clone(), short.clone());
+        let res = std::panic::catch_unwind(move || {
+            let mut out = vec![0; 37];
+            unsafe { intersection_u32_sse42(&a, &short, &mut out) }
+        });
+        assert!(res.is_err());
+        let res = std::panic::catch_unwind(move || unsafe { is_subset_u32_sse42(&a2, &short2) });
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_counter_dispatch() {
+        let a = counts::<usize>(37, 1);
+        let b = counts::<usize>(37, 2);
+        let mut out = vec![0; 37];
+        let mut expected = vec![0; 37];
+        intersection(&a, &b, &mut out);
+        scalar::intersection(&a, &b, &mut expected);
+        assert_eq!(out, expected);
+        assert_eq!(is_subset(&a, &b), scalar::is_subset(&a, &b));
+
+        let a = counts::<u128>(37, 1);
+        let b = counts::<u128>(37, 2);
+        let mut out = vec![0; 37];
+        let mut expected = vec![0; 37];
+        union(&a, &b, &mut out);
+        scalar::union(&a, &b, &mut expected);
+        assert_eq!(out, expected);
+        assert_eq!(is_any_lesser(&a, &b), scalar::is_any_lesser(&a, &b));
+        assert_eq!(is_any_greater(&a, &b), scalar::is_any_greater(&a, &b));
+
+        let small = counts::<u16>(37, 3);
+        assert_eq!(total(&small), scalar::total(&small));
+    }
+}
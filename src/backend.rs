@@ -0,0 +1,318 @@
+use crate::Error;
+#[cfg(feature = "std")]
+use core::cell::Cell;
+use core::sync::atomic::{AtomicU8, Ordering};
+
+/// The implementation used by the multiset methods which have simd versions.
+///
+/// The backend is only used on x86 and x86_64 with one of the `simd`,
+/// `portable_simd` or `stable_simd` features, otherwise the scalar
+/// implementations are always used. By default the best backend supported by
+/// the cpu is detected the first time it is needed and then cached. It can be
+/// changed for the whole program with [`set_backend`], or for a single closure
+/// on the current thread with [`with_backend`].
+///
+/// # Examples
+///
+/// ```
+/// use utote::Backend;
+///
+/// assert!(Backend::Scalar.is_supported());
+/// assert!(Backend::detect().is_supported());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Backend {
+    /// The scalar implementations, which are supported by every cpu.
+    Scalar,
+    /// The SSE4.2 implementations, using 128 bit vectors.
+    Sse42,
+    /// The AVX implementations.
+    Avx,
+    /// The AVX2 implementations, which also require FMA.
+    Avx2,
+}
+
+impl Backend {
+    /// Returns the best backend supported by the cpu.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Backend;
+    ///
+    /// let backend = Backend::detect();
+    /// assert!(backend.is_supported());
+    /// ```
+    #[inline]
+    pub fn detect() -> Backend {
+        [Backend::Avx2, Backend::Avx, Backend::Sse42]
+            .iter()
+            .copied()
+            .find(|backend| backend.is_supported())
+            .unwrap_or(Backend::Scalar)
+    }
+
+    /// Returns true if the cpu supports the features used by the backend.
+    ///
+    /// Without the `std` feature the cpu features can't be detected at
+    /// runtime, so a backend is only supported if the crate was compiled with
+    /// its target features enabled, e.g. with `-C target-feature=+avx2,+fma`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Backend;
+    ///
+    /// assert!(Backend::Scalar.is_supported());
+    /// ```
+    #[inline]
+    pub fn is_supported(self) -> bool {
+        #[cfg(all(feature = "std", any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            match self {
+                Backend::Scalar => true,
+                Backend::Sse42 => is_x86_feature_detected!("sse4.2"),
+                Backend::Avx => is_x86_feature_detected!("avx"),
+                Backend::Avx2 => {
+                    is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")
+                }
+            }
+        }
+        #[cfg(all(not(feature = "std"), any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            match self {
+                Backend::Scalar => true,
+                Backend::Sse42 => cfg!(target_feature = "sse4.2"),
+                Backend::Avx => cfg!(target_feature = "avx"),
+                Backend::Avx2 => cfg!(all(target_feature = "avx2", target_feature = "fma")),
+            }
+        }
+        #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+        {
+            self == Backend::Scalar
+        }
+    }
+
+    #[inline]
+    fn from_u8(value: u8) -> Option<Backend> {
+        match value {
+            0 => Some(Backend::Scalar),
+            1 => Some(Backend::Sse42),
+            2 => Some(Backend::Avx),
+            3 => Some(Backend::Avx2),
+            _ => None,
+        }
+    }
+}
+
+const UNDETECTED: u8 = u8::MAX;
+
+// The backend used by every thread without an override. Only ever holds a
+// backend which the cpu supports, or `UNDETECTED`.
+static BACKEND: AtomicU8 = AtomicU8::new(UNDETECTED);
+
+#[cfg(feature = "std")]
+thread_local! {
+    // Const thread local initializers need a newer toolchain than the minimum
+    // supported version.
+    #[allow(clippy::missing_const_for_thread_local)]
+    static OVERRIDE: Cell<Option<Backend>> = Cell::new(None);
+}
+
+// The backend the dispatched methods should use on this thread. The simd
+// kernels rely on this only returning backends which the cpu supports.
+#[inline]
+pub(crate) fn current() -> Backend {
+    #[cfg(feature = "std")]
+    if let Some(backend) = OVERRIDE.with(Cell::get) {
+        return backend;
+    }
+    match Backend::from_u8(BACKEND.load(Ordering::Relaxed)) {
+        Some(backend) => backend,
+        None => {
+            let backend = Backend::detect();
+            BACKEND.store(backend as u8, Ordering::Relaxed);
+            backend
+        }
+    }
+}
+
+/// Returns the backend used by the multiset methods on the current thread.
+///
+/// # Examples
+///
+/// ```
+/// assert!(utote::backend().is_supported());
+/// ```
+#[inline]
+pub fn backend() -> Backend {
+    current()
+}
+
+/// Sets the backend used by the multiset methods on every thread, except
+/// within [`with_backend`].
+///
+/// Returns an error if the cpu does not support the backend, in which case
+/// the backend is left unchanged.
+///
+/// # Examples
+///
+/// ```
+/// use utote::Backend;
+///
+/// utote::set_backend(Backend::Scalar).unwrap();
+/// assert_eq!(utote::backend(), Backend::Scalar);
+///
+/// // Return to the best backend for the cpu.
+/// utote::set_backend(Backend::detect()).unwrap();
+/// ```
+#[inline]
+pub fn set_backend(backend: Backend) -> Result<(), Error> {
+    if !backend.is_supported() {
+        return Err(Error::UnsupportedBackend(backend));
+    }
+    BACKEND.store(backend as u8, Ordering::Relaxed);
+    Ok(())
+}
+
+/// Calls `f` with the multiset methods using `backend` on the current thread,
+/// returning its result.
+///
+/// The previous backend is restored once `f` returns, or if it panics. Returns
+/// an error without calling `f` if the cpu does not support the backend. Only
+/// available with the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use utote::{Backend, Multiset};
+///
+/// let a = Multiset::from([1u8, 2, 3, 0]);
+/// let b = Multiset::from([3u8, 2, 1, 0]);
+///
+/// let expected = utote::with_backend(Backend::Scalar, || a.union(&b)).unwrap();
+/// assert_eq!(a.union(&b), expected);
+/// ```
+#[cfg(feature = "std")]
+#[inline]
+pub fn with_backend<F, R>(backend: Backend, f: F) -> Result<R, Error>
+where
+    F: FnOnce() -> R,
+{
+    struct Restore(Option<Backend>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            OVERRIDE.with(|cell| cell.set(self.0));
+        }
+    }
+
+    if !backend.is_supported() {
+        return Err(Error::UnsupportedBackend(backend));
+    }
+    let _restore = Restore(OVERRIDE.with(|cell| cell.replace(Some(backend))));
+    Ok(f())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "std")]
+    use crate::Multiset;
+
+    const BACKENDS: [Backend; 4] = [Backend::Scalar, Backend::Sse42, Backend::Avx, Backend::Avx2];
+
+    #[test]
+    fn test_detect() {
+        let detected = Backend::detect();
+        assert!(detected.is_supported());
+        assert!(Backend::Scalar.is_supported());
+        // Nothing better than the detected backend is supported.
+        let best = BACKENDS.iter().rposition(|b| b.is_supported()).unwrap();
+        assert_eq!(BACKENDS[best], detected);
+    }
+
+    #[test]
+    fn test_from_u8() {
+        for backend in BACKENDS.iter() {
+            assert_eq!(Backend::from_u8(*backend as u8), Some(*backend));
+        }
+        assert_eq!(Backend::from_u8(UNDETECTED), None);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_unsupported() {
+        for backend in BACKENDS.iter().filter(|b| !b.is_supported()) {
+            assert_eq!(
+                set_backend(*backend),
+                Err(Error::UnsupportedBackend(*backend))
+            );
+            assert_eq!(
+                with_backend(*backend, || ()),
+                Err(Error::UnsupportedBackend(*backend))
+            );
+        }
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_with_backend() {
+        let before = backend();
+        let res = with_backend(Backend::Scalar, || {
+            assert_eq!(backend(), Backend::Scalar);
+            let nested = with_backend(Backend::detect(), backend);
+            assert_eq!(nested, Ok(Backend::detect()));
+            assert_eq!(backend(), Backend::Scalar);
+            1
+        });
+        assert_eq!(res, Ok(1));
+        assert_eq!(backend(), before);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_with_backend_panic() {
+        let before = backend();
+        let res = std::panic::catch_unwind(|| {
+            with_backend(Backend::Scalar, || panic!("restored on panic")).unwrap();
+        });
+        assert!(res.is_err());
+        assert_eq!(backend(), before);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_backends_agree() {
+        let a = Multiset::<u16, 37>::from_elements(&[0, 3, 3, 5, 20, 36, 36]);
+        let b = Multiset::<u16, 37>::from_elements(&[3, 4, 5, 5, 36]);
+        let expected = with_backend(Backend::Scalar, || {
+            (
+                a.intersection(&b),
+                a.union(&b),
+                a.is_subset(&b),
+                a.total(),
+                a.shannon_entropy(),
+            )
+        })
+        .unwrap();
+        for backend in BACKENDS.iter().filter(|b| b.is_supported()) {
+            let res = with_backend(*backend, || {
+                (
+                    a.intersection(&b),
+                    a.union(&b),
+                    a.is_subset(&b),
+                    a.total(),
+                    a.shannon_entropy(),
+                )
+            })
+            .unwrap();
+            assert_eq!(res.0, expected.0);
+            assert_eq!(res.1, expected.1);
+            assert_eq!(res.2, expected.2);
+            assert_eq!(res.3, expected.3);
+            assert!((res.4 - expected.4).abs() < 1e-12);
+        }
+    }
+}
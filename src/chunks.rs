@@ -15,8 +15,8 @@ doesn't attempt to provide a solution for iterating generally over padded
 chunks.
  */
 
+use core::slice::from_raw_parts_mut;
 use num_traits::Zero;
-use std::slice::from_raw_parts_mut;
 
 // We can implement a much more exacting version of ChunksExact without a
 // remainder because we can ensure that it will only be used on slices of the
@@ -67,7 +67,7 @@ impl<'a, T, const C: usize> Iterator for ChunksExactMut<'a, T, C> {
         if self.slice.is_empty() {
             None
         } else {
-            let tmp = std::mem::replace(&mut self.slice, &mut []);
+            let tmp = core::mem::take(&mut self.slice);
             let (head, tail) = tmp.split_at_mut(C);
             self.slice = tail;
             Some(head)
@@ -166,6 +166,7 @@ where
     }
 }
 
+#[allow(dead_code)]
 trait ChunkPadUtils<T> {
     fn zip_map_chunks_remainder<F, const C: usize>(&self, other: &Self, out: &mut Self, f: F)
     where
@@ -179,6 +180,12 @@ trait ChunkPadUtils<T> {
     fn zip_map_chunks_mut_exact<F, const C: usize>(&mut self, other: &Self, f: F)
     where
         F: FnMut(&mut [T], &[T]);
+    fn map_chunks_mut_remainder<F, const C: usize>(&mut self, f: F)
+    where
+        F: FnMut(&mut [T]);
+    fn map_chunks_mut_exact<F, const C: usize>(&mut self, f: F)
+    where
+        F: FnMut(&mut [T]);
     fn zip_all_chunks_remainder<F, const C: usize>(&self, other: &Self, f: F) -> bool
     where
         F: Fn(&[T], &[T]) -> bool;
@@ -197,6 +204,17 @@ trait ChunkPadUtils<T> {
     fn fold_chunks_exact<Acc, F, const C: usize>(&self, init: Acc, f: F) -> Acc
     where
         F: FnMut(Acc, &[T]) -> Acc;
+    fn zip_fold_chunks_remainder<Acc, F, const C: usize>(
+        &self,
+        other: &Self,
+        init: Acc,
+        f: F,
+    ) -> Acc
+    where
+        F: FnMut(Acc, &[T], &[T]) -> Acc;
+    fn zip_fold_chunks_exact<Acc, F, const C: usize>(&self, other: &Self, init: Acc, f: F) -> Acc
+    where
+        F: FnMut(Acc, &[T], &[T]) -> Acc;
     fn all_chunks_remainder<F, const C: usize>(&self, f: F) -> bool
     where
         F: Fn(&[T]) -> bool;
@@ -269,6 +287,24 @@ where
             .for_each(|(a, b)| f(a, b))
     }
 
+    #[inline]
+    fn map_chunks_mut_remainder<F, const C: usize>(&mut self, mut f: F)
+    where
+        F: FnMut(&mut [T]),
+    {
+        let mut self_chunks = ChunksPadMut::<'_, T, C>::new(self);
+        self_chunks.iter_mut().for_each(&mut f);
+        self_chunks.remainder_with(f);
+    }
+
+    #[inline]
+    fn map_chunks_mut_exact<F, const C: usize>(&mut self, f: F)
+    where
+        F: FnMut(&mut [T]),
+    {
+        self.strict_chunks_exact_mut::<C>().for_each(f)
+    }
+
     #[inline]
     fn zip_all_chunks_remainder<F, const C: usize>(&self, other: &Self, f: F) -> bool
     where
@@ -343,13 +379,53 @@ where
         res
     }
 
+    #[inline]
+    fn zip_fold_chunks_remainder<Acc, F, const C: usize>(
+        &self,
+        other: &Self,
+        init: Acc,
+        mut f: F,
+    ) -> Acc
+    where
+        F: FnMut(Acc, &[T], &[T]) -> Acc,
+    {
+        let mut res = init;
+        let self_chunks = ChunksPad::<'_, T, C>::new(self);
+        let other_chunks = ChunksPad::<'_, T, C>::new(other);
+        for (a, b) in self_chunks.iter().zip(other_chunks.iter()) {
+            res = f(res, a, b);
+        }
+        res = f(res, self_chunks.remainder(), other_chunks.remainder());
+        res
+    }
+
+    #[inline]
+    fn zip_fold_chunks_exact<Acc, F, const C: usize>(
+        &self,
+        other: &Self,
+        init: Acc,
+        mut f: F,
+    ) -> Acc
+    where
+        F: FnMut(Acc, &[T], &[T]) -> Acc,
+    {
+        let mut res = init;
+        for (a, b) in self
+            .strict_chunks_exact::<C>()
+            .zip(other.strict_chunks_exact::<C>())
+        {
+            res = f(res, a, b);
+        }
+        res
+    }
+
     #[inline]
     fn all_chunks_remainder<F, const C: usize>(&self, f: F) -> bool
     where
         F: Fn(&[T]) -> bool,
     {
         let self_chunks = ChunksPad::<'_, T, C>::new(self);
-        self_chunks.iter().all(|slice| f(slice)) && f(self_chunks.remainder())
+        self_chunks.iter().all(&f) && f(self_chunks.remainder())
     }
 
     #[inline]
@@ -357,7 +433,7 @@ where
     where
         F: Fn(&[T]) -> bool,
     {
-        self.strict_chunks_exact::<C>().all(|slice| f(slice))
+        self.strict_chunks_exact::<C>().all(f)
     }
 
     #[inline]
@@ -366,7 +442,7 @@ where
         F: Fn(&[T]) -> bool,
     {
         let self_chunks = ChunksPad::<'_, T, C>::new(self);
-        self_chunks.iter().any(|slice| f(slice)) || f(self_chunks.remainder())
+        self_chunks.iter().any(&f) || f(self_chunks.remainder())
     }
 
     #[inline]
@@ -374,10 +450,11 @@ where
     where
         F: Fn(&[T]) -> bool,
     {
-        self.strict_chunks_exact::<C>().any(|slice| f(slice))
+        self.strict_chunks_exact::<C>().any(f)
     }
 }
 
+#[allow(dead_code)]
 pub(crate) trait ChunkUtils<T> {
     fn zip_map_chunks<F, const C: usize>(&self, other: &Self, out: &mut Self, f: F)
     where
@@ -385,6 +462,9 @@ pub(crate) trait ChunkUtils<T> {
     fn zip_map_chunks_mut<F, const C: usize>(&mut self, other: &Self, f: F)
     where
         F: FnMut(&mut [T], &[T]);
+    fn map_chunks_mut<F, const C: usize>(&mut self, f: F)
+    where
+        F: FnMut(&mut [T]);
     fn zip_all_chunks<F, const C: usize>(&self, other: &Self, f: F) -> bool
     where
         F: Fn(&[T], &[T]) -> bool;
@@ -394,6 +474,9 @@ pub(crate) trait ChunkUtils<T> {
     fn fold_chunks<Acc, F, const C: usize>(&self, init: Acc, f: F) -> Acc
     where
         F: FnMut(Acc, &[T]) -> Acc;
+    fn zip_fold_chunks<Acc, F, const C: usize>(&self, other: &Self, init: Acc, f: F) -> Acc
+    where
+        F: FnMut(Acc, &[T], &[T]) -> Acc;
     fn all_chunks<F, const C: usize>(&self, f: F) -> bool
     where
         F: Fn(&[T]) -> bool;
@@ -411,7 +494,7 @@ where
     where
         F: FnMut(&[T], &[T], &mut [T]),
     {
-        if self.len() % C == 0 {
+        if self.len().is_multiple_of(C) {
             self.zip_map_chunks_exact::<F, C>(other, out, f)
         } else {
             self.zip_map_chunks_remainder::<F, C>(other, out, f)
@@ -423,10 +506,22 @@ where
     where
         F: FnMut(&mut [T], &[T]),
     {
-        if self.len() % C == 0 {
+        if self.len().is_multiple_of(C) {
+            self.zip_map_chunks_mut_exact::<F, C>(other, f)
+        } else {
             self.zip_map_chunks_mut_remainder::<F, C>(other, f)
+        }
+    }
+
+    #[inline]
+    fn map_chunks_mut<F, const C: usize>(&mut self, f: F)
+    where
+        F: FnMut(&mut [T]),
+    {
+        if self.len().is_multiple_of(C) {
+            self.map_chunks_mut_exact::<F, C>(f)
         } else {
-            self.zip_map_chunks_mut_exact::<F, C>(other, f)
+            self.map_chunks_mut_remainder::<F, C>(f)
         }
     }
 
@@ -435,7 +530,7 @@ where
     where
         F: Fn(&[T], &[T]) -> bool,
     {
-        if self.len() % C == 0 {
+        if self.len().is_multiple_of(C) {
             self.zip_all_chunks_exact::<F, C>(other, f)
         } else {
             self.zip_all_chunks_remainder::<F, C>(other, f)
@@ -447,7 +542,7 @@ where
     where
         F: Fn(&[T], &[T]) -> bool,
     {
-        if self.len() % C == 0 {
+        if self.len().is_multiple_of(C) {
             self.zip_any_chunks_exact::<F, C>(other, f)
         } else {
             self.zip_any_chunks_remainder::<F, C>(other, f)
@@ -459,19 +554,31 @@ where
     where
         F: FnMut(Acc, &[T]) -> Acc,
     {
-        if self.len() % C == 0 {
+        if self.len().is_multiple_of(C) {
             self.fold_chunks_exact::<Acc, F, C>(init, f)
         } else {
             self.fold_chunks_remainder::<Acc, F, C>(init, f)
         }
     }
 
+    #[inline]
+    fn zip_fold_chunks<Acc, F, const C: usize>(&self, other: &Self, init: Acc, f: F) -> Acc
+    where
+        F: FnMut(Acc, &[T], &[T]) -> Acc,
+    {
+        if self.len().is_multiple_of(C) {
+            self.zip_fold_chunks_exact::<Acc, F, C>(other, init, f)
+        } else {
+            self.zip_fold_chunks_remainder::<Acc, F, C>(other, init, f)
+        }
+    }
+
     #[inline]
     fn all_chunks<F, const C: usize>(&self, f: F) -> bool
     where
         F: Fn(&[T]) -> bool,
     {
-        if self.len() % C == 0 {
+        if self.len().is_multiple_of(C) {
             self.all_chunks_exact::<F, C>(f)
         } else {
             self.all_chunks_remainder::<F, C>(f)
@@ -483,7 +590,7 @@ where
     where
         F: Fn(&[T]) -> bool,
     {
-        if self.len() % C == 0 {
+        if self.len().is_multiple_of(C) {
             self.any_chunks_exact::<F, C>(f)
         } else {
             self.any_chunks_remainder::<F, C>(f)
@@ -616,6 +723,30 @@ mod tests {
         assert_eq!(this, [2, 3, 4, 5]);
     }
 
+    #[test]
+    fn test_map_chunks_mut_remainder() {
+        const CHUNK: usize = 2;
+        let mut this: [u16; 5] = [1, 2, 3, 4, 5];
+
+        this.map_chunks_mut_remainder::<_, CHUNK>(|slice_this| {
+            slice_this.iter_mut().for_each(|a| *a *= 2);
+        });
+
+        assert_eq!(this, [2, 4, 6, 8, 10]);
+    }
+
+    #[test]
+    fn test_map_chunks_mut_exact() {
+        const CHUNK: usize = 2;
+        let mut this: [u16; 4] = [1, 2, 3, 4];
+
+        this.map_chunks_mut_exact::<_, CHUNK>(|slice_this| {
+            slice_this.iter_mut().for_each(|a| *a *= 2);
+        });
+
+        assert_eq!(this, [2, 4, 6, 8]);
+    }
+
     #[test]
     fn test_zip_all_chunks_remainder() {
         const CHUNK: usize = 2;
@@ -739,6 +870,31 @@ mod tests {
         assert_eq!(res, 4);
     }
 
+    #[test]
+    fn test_zip_fold_chunks_remainder() {
+        const CHUNK: usize = 2;
+        let this: [u16; 5] = [1, 2, 3, 4, 5];
+        let other: [u16; 5] = [5, 4, 3, 2, 1];
+
+        let res = this.zip_fold_chunks_remainder::<u16, _, CHUNK>(&other, 0, |acc, a, b| {
+            acc + a.iter().zip(b.iter()).map(|(a, b)| a * b).sum::<u16>()
+        });
+        assert_eq!(res, 35);
+    }
+
+    #[test]
+    fn test_zip_fold_chunks_exact() {
+        const CHUNK: usize = 2;
+        let this: [u16; 4] = [1, 2, 3, 4];
+        let other: [u16; 4] = [4, 3, 2, 1];
+
+        let res = this.zip_fold_chunks_exact::<u16, _, CHUNK>(&other, 0, |acc, a, b| {
+            assert_eq!(a.len(), CHUNK);
+            acc + a.iter().zip(b.iter()).map(|(a, b)| a * b).sum::<u16>()
+        });
+        assert_eq!(res, 20);
+    }
+
     #[test]
     fn test_all_chunks_remainder() {
         const CHUNK: usize = 2;
@@ -0,0 +1,131 @@
+// Combines the totals and dot products computed by the kernels into the
+// distance and similarity metrics, so that the scalar and simd versions of the
+// methods share the handling of empty multisets.
+
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::math;
+
+// `intersection` and `union` are the totals of the intersection and union.
+// Two empty multisets are identical.
+#[inline]
+pub(crate) fn jaccard_index(intersection: u128, union: u128) -> f64 {
+    if union == 0 {
+        1.0
+    } else {
+        intersection as f64 / union as f64
+    }
+}
+
+// `l1` is the l1 distance, `total_a` and `total_b` the totals of the
+// multisets. Two empty multisets are identical.
+#[inline]
+pub(crate) fn bray_curtis(l1: u128, total_a: u128, total_b: u128) -> f64 {
+    let sum = total_a as f64 + total_b as f64;
+    if sum == 0.0 {
+        0.0
+    } else {
+        l1 as f64 / sum
+    }
+}
+
+// `ab`, `aa` and `bb` are the dot products of the counts. Two empty multisets
+// point the same way, while an empty multiset shares nothing with a non-empty
+// one.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn cosine_similarity(ab: f64, aa: f64, bb: f64) -> f64 {
+    match (aa == 0.0, bb == 0.0) {
+        (true, true) => 1.0,
+        (true, false) | (false, true) => 0.0,
+        (false, false) => ab / (math::sqrt(aa) * math::sqrt(bb)),
+    }
+}
+
+// `sqrt_ab` is the sum of the square roots of the products of the counts, and
+// `total_a` and `total_b` the totals of the multisets. The distance is between
+// the multisets normalised to probability distributions, so an empty multiset
+// is at the maximum distance of 1 from a non-empty one.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn hellinger_distance(sqrt_ab: f64, total_a: u128, total_b: u128) -> f64 {
+    match (total_a == 0, total_b == 0) {
+        (true, true) => 0.0,
+        (true, false) | (false, true) => 1.0,
+        (false, false) => {
+            let coefficient = sqrt_ab / math::sqrt(total_a as f64 * total_b as f64);
+            // Rounding can push the coefficient of identical distributions
+            // just above 1.
+            math::sqrt((1.0 - coefficient).max(0.0))
+        }
+    }
+}
+
+// The totals of the multisets once `pseudocount` is added to each of the `len`
+// counts.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn smoothed_totals(
+    total_a: u128,
+    total_b: u128,
+    len: usize,
+    pseudocount: f64,
+) -> (f64, f64) {
+    assert!(pseudocount >= 0.0, "the pseudocount must not be negative");
+    let extra = pseudocount * len as f64;
+    (total_a as f64 + extra, total_b as f64 + extra)
+}
+
+// The divergences take the smoothed totals of the multisets, and only call
+// `kernel` when both are non-zero. An empty multiset has no support, so it
+// diverges from nothing, while everything diverges infinitely from it.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn kl_divergence(total_a: f64, total_b: f64, kernel: impl FnOnce() -> f64) -> f64 {
+    if total_a == 0.0 {
+        0.0
+    } else if total_b == 0.0 {
+        f64::INFINITY
+    } else {
+        // Rounding can make the divergence of identical distributions
+        // slightly negative.
+        kernel().max(0.0)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn cross_entropy(total_a: f64, total_b: f64, kernel: impl FnOnce() -> f64) -> f64 {
+    if total_a == 0.0 {
+        0.0
+    } else if total_b == 0.0 {
+        f64::INFINITY
+    } else {
+        kernel()
+    }
+}
+
+// The Jensen–Shannon divergence and total variation are bounded, by ln(2) and 1,
+// and take their maximum between an empty and a non-empty multiset.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn jensen_shannon_divergence(
+    total_a: f64,
+    total_b: f64,
+    kernel: impl FnOnce() -> f64,
+) -> f64 {
+    match (total_a == 0.0, total_b == 0.0) {
+        (true, true) => 0.0,
+        (true, false) | (false, true) => core::f64::consts::LN_2,
+        (false, false) => kernel().max(0.0),
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn total_variation(total_a: f64, total_b: f64, kernel: impl FnOnce() -> f64) -> f64 {
+    match (total_a == 0.0, total_b == 0.0) {
+        (true, true) => 0.0,
+        (true, false) | (false, true) => 1.0,
+        (false, false) => kernel(),
+    }
+}
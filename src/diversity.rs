@@ -0,0 +1,464 @@
+//! Ecological diversity and richness indices.
+//!
+//! These treat a multiset as a sample of individuals, with the count of each
+//! element the number of individuals of a species. Every function is generic
+//! over [`MultisetOps`], so it can be used with any multiset type, or a slice
+//! of counters.
+//!
+//! Indices of the proportions of the species are undefined for an empty
+//! multiset, and return [`Error::EmptyMultiset`]. The richness estimators and
+//! rarefaction give zero species for an empty multiset.
+//!
+//! # Examples
+//!
+//! ```
+//! use utote::diversity;
+//! use utote::Multiset;
+//!
+//! let sample = Multiset::from([1u32, 1, 2, 3, 5, 0, 12, 1, 2, 0]);
+//! assert_eq!(diversity::inverse_simpson(&sample), Ok(27.0 / 7.0));
+//! assert_eq!(diversity::goods_coverage(&sample), Ok(1.0 - 3.0 / 27.0));
+//! // Three singletons and two doubletons suggest unseen species.
+//! assert!(diversity::chao1(&sample) > 8.0);
+//! ```
+
+use crate::{Counter, Error, MultisetOps};
+use alloc::vec::Vec;
+use num_traits::AsPrimitive;
+
+// The largest count of a rare species in the ACE estimator.
+const ACE_RARE_THRESHOLD: u128 = 10;
+
+/// Simpson's index: the probability that two individuals drawn with
+/// replacement are of the same species, which is the sum of the squared
+/// proportions of the species. Higher values mean lower diversity.
+///
+/// # Errors
+/// [`Error::EmptyMultiset`] if the multiset is empty.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([2u32, 1, 1, 0]);
+/// assert_eq!(diversity::simpson(&sample), Ok(0.375));
+/// ```
+pub fn simpson<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Result<f64, Error> {
+    let total = nonzero_total(multiset)?;
+    Ok(multiset.iter().fold(0.0, |acc, c| {
+        let prop = <N as AsPrimitive<f64>>::as_(*c) / total;
+        acc + prop * prop
+    }))
+}
+
+/// The inverse of [`simpson`], the effective number of species. This is the
+/// Hill number of order 2.
+///
+/// # Errors
+/// [`Error::EmptyMultiset`] if the multiset is empty.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([3u32, 3, 3, 0]);
+/// assert_eq!(diversity::inverse_simpson(&sample), Ok(3.0));
+/// ```
+pub fn inverse_simpson<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Result<f64, Error> {
+    simpson(multiset).map(|index| 1.0 / index)
+}
+
+/// The Gini–Simpson index: one minus [`simpson`], the probability that two
+/// individuals drawn with replacement are of different species.
+///
+/// # Errors
+/// [`Error::EmptyMultiset`] if the multiset is empty.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([2u32, 1, 1, 0]);
+/// assert_eq!(diversity::gini_simpson(&sample), Ok(0.625));
+/// ```
+pub fn gini_simpson<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Result<f64, Error> {
+    simpson(multiset).map(|index| 1.0 - index)
+}
+
+/// Pielou's evenness: the Shannon entropy of the multiset divided by its
+/// largest possible value, the logarithm of the number of species present.
+///
+/// The evenness is between 0 and 1, and is 1 when every species present has
+/// the same count. It is NaN when only one species is present.
+///
+/// # Errors
+/// [`Error::EmptyMultiset`] if the multiset is empty.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([3u32, 3, 3, 0]);
+/// assert!((diversity::pielou_evenness(&sample).unwrap() - 1.0).abs() < 1e-12);
+/// ```
+#[cfg(any(feature = "std", feature = "libm"))]
+pub fn pielou_evenness<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Result<f64, Error> {
+    nonzero_total(multiset)?;
+    let species = multiset.count_non_zero() as f64;
+    Ok(multiset.shannon_entropy() / crate::math::ln(species))
+}
+
+/// The Berger–Parker dominance: the proportion of the individuals which are
+/// of the most common species.
+///
+/// # Errors
+/// [`Error::EmptyMultiset`] if the multiset is empty.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([2u32, 1, 1, 0]);
+/// assert_eq!(diversity::berger_parker(&sample), Ok(0.5));
+/// ```
+pub fn berger_parker<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Result<f64, Error> {
+    let total = nonzero_total(multiset)?;
+    let max: f64 = (*multiset.count_max()).as_();
+    Ok(max / total)
+}
+
+/// The Chao1 estimate of the number of species, including those not in the
+/// sample, from the numbers of singletons and doubletons: the species seen
+/// once and twice.
+///
+/// With `n` individuals, `s` species, `f1` singletons and `f2` doubletons the
+/// estimate is `s + (n - 1) / n * f1^2 / 2f2`, or the bias corrected
+/// `s + (n - 1) / n * f1 (f1 - 1) / 2` when there are no doubletons.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([1u32, 1, 2, 3, 5, 0, 12, 1, 2, 0]);
+/// assert_eq!(diversity::chao1(&sample), 8.0 + 26.0 / 27.0 * 9.0 / 4.0);
+/// ```
+pub fn chao1<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> f64 {
+    let total = multiset.total_u128();
+    if total == 0 {
+        return 0.0;
+    }
+    let n = total as f64;
+    let species = multiset.count_non_zero() as f64;
+    let f1 = count_of_count(multiset, 1) as f64;
+    let f2 = count_of_count(multiset, 2) as f64;
+    let unseen = if f2 > 0.0 {
+        f1 * f1 / (2.0 * f2)
+    } else {
+        f1 * (f1 - 1.0) / 2.0
+    };
+    species + (n - 1.0) / n * unseen
+}
+
+/// The abundance-based coverage estimate (ACE) of the number of species,
+/// including those not in the sample.
+///
+/// Species with a count of at most 10 are rare. The estimate is the number of
+/// abundant species plus the number of rare species, corrected by the sample
+/// coverage of the rare species and the variation of their counts. When every
+/// rare species is a singleton the coverage is zero, and the estimate is the
+/// [`chao1`] estimate instead.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([1u32, 1, 2, 3, 5, 0, 12, 1, 2, 0]);
+/// assert_eq!(diversity::ace(&sample), 10.6875);
+/// ```
+pub fn ace<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> f64 {
+    let (mut rare, mut abundant, mut rare_total, mut weighted) = (0.0, 0.0, 0u128, 0u128);
+    for count in multiset.iter().map(|c| <N as AsPrimitive<u128>>::as_(*c)) {
+        if count > ACE_RARE_THRESHOLD {
+            abundant += 1.0;
+        } else if count > 0 {
+            rare += 1.0;
+            rare_total += count;
+            weighted += count * (count - 1);
+        }
+    }
+    let f1 = count_of_count(multiset, 1) as u128;
+    if rare_total == 0 {
+        return abundant;
+    }
+    if f1 == rare_total {
+        return chao1(multiset);
+    }
+    let n_rare = rare_total as f64;
+    let coverage = 1.0 - f1 as f64 / n_rare;
+    let variation = (rare / coverage * weighted as f64 / (n_rare * (n_rare - 1.0)) - 1.0).max(0.0);
+    abundant + rare / coverage + f1 as f64 / coverage * variation
+}
+
+/// Good's coverage: the estimated proportion of the population which is of
+/// species in the sample, one minus the proportion of singletons.
+///
+/// # Errors
+/// [`Error::EmptyMultiset`] if the multiset is empty.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([1u32, 2, 0, 5]);
+/// assert_eq!(diversity::goods_coverage(&sample), Ok(0.875));
+/// ```
+pub fn goods_coverage<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Result<f64, Error> {
+    let total = nonzero_total(multiset)?;
+    Ok(1.0 - count_of_count(multiset, 1) as f64 / total)
+}
+
+/// The expected number of species in a subsample of `depth` individuals,
+/// drawn without replacement. This is individual-based rarefaction.
+///
+/// # Panics
+/// If `depth` is greater than the total of the multiset.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([2u32, 1, 1, 0]);
+/// assert_eq!(diversity::rarefaction(&sample, 0), 0.0);
+/// assert_eq!(diversity::rarefaction(&sample, 1), 1.0);
+/// assert_eq!(diversity::rarefaction(&sample, 4), 3.0);
+/// ```
+pub fn rarefaction<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M, depth: usize) -> f64 {
+    let total = multiset.total_u128();
+    assert!(
+        depth as u128 <= total,
+        "the depth must not be greater than the total"
+    );
+    let n = total as f64;
+    count_groups(multiset)
+        .iter()
+        .fold(0.0, |acc, &(count, species)| {
+            // The probability that no individual of a species with `count`
+            // individuals is drawn.
+            let missed = (0..depth).fold(1.0, |missed, j| {
+                let j = j as f64;
+                missed * ((n - count - j) / (n - j)).max(0.0)
+            });
+            acc + species * (1.0 - missed)
+        })
+}
+
+/// The rarefaction curve of the multiset: the [`rarefaction`] at every depth
+/// from 0 to `max_depth` inclusive, in order.
+///
+/// # Panics
+/// If `max_depth` is greater than the total of the multiset.
+///
+/// # Examples
+///
+/// ```
+/// use utote::diversity;
+/// use utote::Multiset;
+///
+/// let sample = Multiset::from([2u32, 1, 1, 0]);
+/// let curve = diversity::rarefaction_curve(&sample, 4);
+/// assert_eq!(curve.len(), 5);
+/// assert_eq!(curve[1], 1.0);
+/// assert_eq!(curve[4], 3.0);
+/// ```
+pub fn rarefaction_curve<N: Counter, M: MultisetOps<N> + ?Sized>(
+    multiset: &M,
+    max_depth: usize,
+) -> Vec<f64> {
+    let total = multiset.total_u128();
+    assert!(
+        max_depth as u128 <= total,
+        "the depth must not be greater than the total"
+    );
+    let n = total as f64;
+    let mut groups: Vec<(f64, f64, f64)> = count_groups(multiset)
+        .into_iter()
+        .map(|(count, species)| (count, species, 1.0))
+        .collect();
+    let mut curve = Vec::with_capacity(max_depth + 1);
+    for depth in 0..=max_depth {
+        curve.push(groups.iter().fold(0.0, |acc, &(_, species, missed)| {
+            acc + species * (1.0 - missed)
+        }));
+        if depth < max_depth {
+            let d = depth as f64;
+            for (count, _, missed) in groups.iter_mut() {
+                *missed *= ((n - *count - d) / (n - d)).max(0.0);
+            }
+        }
+    }
+    curve
+}
+
+// The total of the multiset, or `Error::EmptyMultiset` if it is zero.
+#[inline]
+fn nonzero_total<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Result<f64, Error> {
+    match multiset.total_u128() {
+        0 => Err(Error::EmptyMultiset),
+        total => Ok(total as f64),
+    }
+}
+
+// The number of species with exactly `k` individuals.
+#[inline]
+fn count_of_count<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M, k: u128) -> usize {
+    multiset
+        .iter()
+        .filter(|c| <N as AsPrimitive<u128>>::as_(**c) == k)
+        .count()
+}
+
+// The distinct non-zero counts with the number of species which have them.
+// Species with the same count are equally likely to be drawn, so rarefaction
+// only needs to consider each count once.
+fn count_groups<N: Counter, M: MultisetOps<N> + ?Sized>(multiset: &M) -> Vec<(f64, f64)> {
+    let mut counts: Vec<u128> = multiset
+        .iter()
+        .map(|c| <N as AsPrimitive<u128>>::as_(*c))
+        .filter(|c| *c > 0)
+        .collect();
+    counts.sort_unstable();
+    let mut groups: Vec<(f64, f64)> = Vec::new();
+    let mut prev = None;
+    for count in counts {
+        match groups.last_mut() {
+            Some((_, species)) if prev == Some(count) => *species += 1.0,
+            _ => groups.push((count as f64, 1.0)),
+        }
+        prev = Some(count);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Multiset, MultisetVec};
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use approx::assert_relative_eq;
+
+    fn sample() -> Multiset<u32, 10> {
+        Multiset::from([1, 1, 2, 3, 5, 0, 12, 1, 2, 0])
+    }
+
+    #[test]
+    fn test_simpson() {
+        let a = sample();
+        assert_eq!(simpson(&a), Ok(7.0 / 27.0));
+        assert_eq!(inverse_simpson(&a), Ok(27.0 / 7.0));
+        assert_eq!(gini_simpson(&a), Ok(1.0 - 7.0 / 27.0));
+        let v = MultisetVec::from(a.iter().copied().collect::<Vec<_>>());
+        assert_eq!(simpson(&v), simpson(&a));
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_pielou_evenness() {
+        assert_relative_eq!(
+            pielou_evenness(&sample()).unwrap(),
+            0.8024430194217222,
+            epsilon = 1e-12
+        );
+        let single = Multiset::<u32, 4>::from([0, 5, 0, 0]);
+        assert!(pielou_evenness(&single).unwrap().is_nan());
+    }
+
+    #[test]
+    fn test_berger_parker() {
+        assert_eq!(berger_parker(&sample()), Ok(12.0 / 27.0));
+    }
+
+    #[test]
+    fn test_richness() {
+        let a = sample();
+        assert_eq!(chao1(&a), 61.0 / 6.0);
+        assert_eq!(ace(&a), 10.6875);
+        // Without doubletons chao1 uses the bias corrected form.
+        let b = Multiset::<u32, 5>::from([1, 1, 1, 4, 0]);
+        assert_eq!(chao1(&b), 4.0 + 6.0 / 7.0 * 3.0);
+        // Every rare species is a singleton, so ACE is chao1.
+        let c = Multiset::<u32, 5>::from([1, 1, 20, 0, 0]);
+        assert_eq!(ace(&c), chao1(&c));
+        // Only abundant species.
+        let d = Multiset::<u32, 3>::from([11, 20, 0]);
+        assert_eq!(ace(&d), 2.0);
+        assert_eq!(chao1(&d), 2.0);
+    }
+
+    #[test]
+    fn test_goods_coverage() {
+        assert_eq!(goods_coverage(&sample()), Ok(8.0 / 9.0));
+    }
+
+    #[test]
+    fn test_rarefaction() {
+        let a = sample();
+        // The expected values are exact binomial ratios.
+        let expected = [
+            (0, 0.0),
+            (1, 1.0),
+            (2, 23.0 / 13.0),
+            (5, 90133.0 / 26910.0),
+            (10, 6590.0 / 1311.0),
+            (27, 8.0),
+        ];
+        let curve = rarefaction_curve(&a, 27);
+        assert_eq!(curve.len(), 28);
+        for &(depth, value) in expected.iter() {
+            assert!((rarefaction(&a, depth) - value).abs() < 1e-12);
+            assert!((curve[depth] - value).abs() < 1e-12);
+        }
+        assert!(curve.windows(2).all(|w| w[0] <= w[1]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_rarefaction_too_deep() {
+        rarefaction(&sample(), 28);
+    }
+
+    #[test]
+    fn test_empty() {
+        let empty = Multiset::<u32, 4>::empty();
+        assert_eq!(simpson(&empty), Err(Error::EmptyMultiset));
+        assert_eq!(inverse_simpson(&empty), Err(Error::EmptyMultiset));
+        assert_eq!(gini_simpson(&empty), Err(Error::EmptyMultiset));
+        #[cfg(any(feature = "std", feature = "libm"))]
+        assert_eq!(pielou_evenness(&empty), Err(Error::EmptyMultiset));
+        assert_eq!(berger_parker(&empty), Err(Error::EmptyMultiset));
+        assert_eq!(goods_coverage(&empty), Err(Error::EmptyMultiset));
+        assert_eq!(chao1(&empty), 0.0);
+        assert_eq!(ace(&empty), 0.0);
+        assert_eq!(rarefaction(&empty, 0), 0.0);
+        assert_eq!(rarefaction_curve(&empty, 0), vec![0.0]);
+        let none: &[u32] = &[];
+        assert_eq!(simpson(none), Err(Error::EmptyMultiset));
+    }
+}
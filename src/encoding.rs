@@ -0,0 +1,424 @@
+use crate::{Counter, DecodeError, Multiset};
+use core::mem::{size_of, size_of_val};
+use std::io::{self, Read, Write};
+
+const VERSION: u8 = 1;
+
+const LAYOUT_DENSE: u8 = 0;
+const LAYOUT_SPARSE: u8 = 1;
+const LAYOUT_RLE: u8 = 2;
+
+// A u128 needs at most 19 groups of 7 bits.
+const MAX_VARINT_LEN: usize = 19;
+
+////////////////////////////////////////////////////////////////////////////////
+// Binary encoding
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// Writes the multiset to `writer` in a compact, versioned binary format.
+    ///
+    /// The encoding starts with a header of the format version, the layout
+    /// used, the width of the counter type in bytes and the number of
+    /// elements. The counts follow in whichever of these layouts is smallest:
+    ///
+    /// - __dense__: every count as a little endian integer of the counter
+    ///   width.
+    /// - __sparse__: the number of non-zero counts, then for each non-zero
+    ///   count the gap since the previous non-zero element and the count.
+    /// - __run length__: pairs of run length and count covering every element.
+    ///
+    /// All lengths, gaps and counts outside of the dense layout are written
+    /// as LEB128 variable length integers.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::<u32, 1024>::empty();
+    /// multiset.insert(3, 70_000);
+    /// multiset.insert(900, 2);
+    ///
+    /// let mut bytes = Vec::new();
+    /// multiset.encode(&mut bytes).unwrap();
+    /// assert!(bytes.len() < 16);
+    ///
+    /// let decoded = Multiset::<u32, 1024>::decode(&mut bytes.as_slice()).unwrap();
+    /// assert_eq!(decoded, multiset);
+    /// ```
+    pub fn encode(&self, writer: &mut impl Write) -> io::Result<()> {
+        encode_counts(&self.data, writer)
+    }
+
+    /// Reads a multiset written by [`encode`](Multiset::encode) from `reader`.
+    ///
+    /// The counts may have been written with a different counter type, as
+    /// long as every count fits in `N`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`DecodeError`] describing the problem if the input is
+    /// truncated, was not written by `encode`, does not contain exactly
+    /// `SIZE` elements or contains a count which does not fit in `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{DecodeError, Multiset};
+    ///
+    /// let mut bytes = Vec::new();
+    /// Multiset::from([0u64, 300, 0]).encode(&mut bytes).unwrap();
+    ///
+    /// let decoded = Multiset::<u16, 3>::decode(&mut bytes.as_slice()).unwrap();
+    /// assert_eq!(decoded, Multiset::from([0, 300, 0]));
+    ///
+    /// let result = Multiset::<u8, 3>::decode(&mut bytes.as_slice());
+    /// assert!(matches!(result, Err(DecodeError::CounterOverflow { element: 1 })));
+    ///
+    /// let result = Multiset::<u64, 4>::decode(&mut bytes.as_slice());
+    /// assert!(matches!(result, Err(DecodeError::LengthMismatch { expected: 4, found: 3 })));
+    /// ```
+    pub fn decode(reader: &mut impl Read) -> Result<Self, DecodeError> {
+        let mut res = Self::empty();
+        decode_counts(reader, &mut res.data)?;
+        Ok(res)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Encoding helpers
+////////////////////////////////////////////////////////////////////////////////
+
+fn encode_counts<N: Counter, W: Write>(data: &[N], writer: &mut W) -> io::Result<()> {
+    let width = size_of::<N>();
+    let dense_len = size_of_val(data);
+    let sparse_len = sparse_body_len(data);
+    let rle_len = rle_body_len(data);
+
+    let layout = if dense_len <= sparse_len && dense_len <= rle_len {
+        LAYOUT_DENSE
+    } else if sparse_len <= rle_len {
+        LAYOUT_SPARSE
+    } else {
+        LAYOUT_RLE
+    };
+
+    writer.write_all(&[VERSION, layout, width as u8])?;
+    write_varint(writer, data.len() as u128)?;
+
+    match layout {
+        LAYOUT_DENSE => {
+            for count in data {
+                let count: u128 = count.as_();
+                writer.write_all(&count.to_le_bytes()[..width])?;
+            }
+        }
+        LAYOUT_SPARSE => {
+            let non_zero = data.iter().filter(|count| !count.is_zero()).count();
+            write_varint(writer, non_zero as u128)?;
+            let mut next = 0;
+            for (elem, count) in data.iter().enumerate() {
+                if !count.is_zero() {
+                    write_varint(writer, (elem - next) as u128)?;
+                    write_varint(writer, count.as_())?;
+                    next = elem + 1;
+                }
+            }
+        }
+        _ => {
+            for (run, count) in runs(data) {
+                write_varint(writer, run as u128)?;
+                write_varint(writer, count.as_())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn sparse_body_len<N: Counter>(data: &[N]) -> usize {
+    let mut len = 0;
+    let mut non_zero = 0;
+    let mut next = 0;
+    for (elem, count) in data.iter().enumerate() {
+        if !count.is_zero() {
+            len += varint_len((elem - next) as u128) + varint_len(count.as_());
+            non_zero += 1;
+            next = elem + 1;
+        }
+    }
+    len + varint_len(non_zero)
+}
+
+fn rle_body_len<N: Counter>(data: &[N]) -> usize {
+    runs(data)
+        .map(|(run, count)| varint_len(run as u128) + varint_len(count.as_()))
+        .sum()
+}
+
+// Yields (run length, count) for each maximal run of equal counts.
+fn runs<N: Counter>(data: &[N]) -> impl Iterator<Item = (usize, N)> + '_ {
+    let mut rest = data;
+    core::iter::from_fn(move || {
+        let first = *rest.first()?;
+        let run = rest.iter().take_while(|count| **count == first).count();
+        rest = &rest[run..];
+        Some((run, first))
+    })
+}
+
+fn varint_len(value: u128) -> usize {
+    match 128 - value.leading_zeros() as usize {
+        0 => 1,
+        bits => (bits - 1) / 7 + 1,
+    }
+}
+
+fn write_varint<W: Write>(writer: &mut W, mut value: u128) -> io::Result<()> {
+    let mut buf = [0u8; MAX_VARINT_LEN];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            buf[len] = byte;
+            len += 1;
+            break;
+        }
+        buf[len] = byte | 0x80;
+        len += 1;
+    }
+    writer.write_all(&buf[..len])
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Decoding helpers
+////////////////////////////////////////////////////////////////////////////////
+
+fn decode_counts<N: Counter, R: Read>(reader: &mut R, data: &mut [N]) -> Result<(), DecodeError> {
+    let version = read_u8(reader)?;
+    if version != VERSION {
+        return Err(DecodeError::UnsupportedVersion(version));
+    }
+    let layout = read_u8(reader)?;
+    let width = read_u8(reader)?;
+    if !matches!(width, 1 | 2 | 4 | 8 | 16) {
+        return Err(DecodeError::InvalidCounterWidth(width));
+    }
+    let len = read_varint(reader)?;
+    if len != data.len() as u128 {
+        return Err(DecodeError::LengthMismatch {
+            expected: data.len(),
+            found: len,
+        });
+    }
+
+    match layout {
+        LAYOUT_DENSE => {
+            for (elem, count) in data.iter_mut().enumerate() {
+                let mut buf = [0u8; 16];
+                reader.read_exact(&mut buf[..width as usize])?;
+                *count = to_counter(u128::from_le_bytes(buf), elem)?;
+            }
+        }
+        LAYOUT_SPARSE => {
+            let non_zero = read_varint(reader)?;
+            let mut next: u128 = 0;
+            for _ in 0..non_zero {
+                let elem = next.saturating_add(read_varint(reader)?);
+                if elem >= len {
+                    return Err(DecodeError::ElementOutOfRange {
+                        element: elem,
+                        len: data.len(),
+                    });
+                }
+                let elem = elem as usize;
+                data[elem] = to_counter(read_varint(reader)?, elem)?;
+                next = elem as u128 + 1;
+            }
+        }
+        LAYOUT_RLE => {
+            let mut elem = 0;
+            while elem < data.len() {
+                let run = read_varint(reader)?;
+                if run == 0 || run > (data.len() - elem) as u128 {
+                    return Err(DecodeError::InvalidRun { element: elem });
+                }
+                let run = run as usize;
+                let count = to_counter(read_varint(reader)?, elem)?;
+                for c in data[elem..elem + run].iter_mut() {
+                    *c = count;
+                }
+                elem += run;
+            }
+        }
+        tag => return Err(DecodeError::UnknownLayout(tag)),
+    }
+    Ok(())
+}
+
+fn to_counter<N: Counter>(value: u128, elem: usize) -> Result<N, DecodeError> {
+    N::from_u128(value).ok_or(DecodeError::CounterOverflow { element: elem })
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> Result<u8, DecodeError> {
+    let mut buf = [0u8; 1];
+    reader.read_exact(&mut buf)?;
+    Ok(buf[0])
+}
+
+fn read_varint<R: Read>(reader: &mut R) -> Result<u128, DecodeError> {
+    let mut value = 0u128;
+    for i in 0..MAX_VARINT_LEN {
+        let byte = read_u8(reader)?;
+        let bits = (byte & 0x7f) as u128;
+        // The last group only has room for the top 2 bits of a u128.
+        if i == MAX_VARINT_LEN - 1 && bits > 0x03 {
+            return Err(DecodeError::InvalidVarint);
+        }
+        value |= bits << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    Err(DecodeError::InvalidVarint)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn round_trip<N: Counter, const SIZE: usize>(set: Multiset<N, SIZE>) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        set.encode(&mut bytes).unwrap();
+        let decoded = Multiset::<N, SIZE>::decode(&mut bytes.as_slice()).unwrap();
+        assert_eq!(decoded.data, set.data);
+        bytes
+    }
+
+    #[test]
+    fn test_varint() {
+        for &value in &[0, 1, 127, 128, 300, u64::MAX as u128, u128::MAX] {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value).unwrap();
+            assert_eq!(bytes.len(), varint_len(value));
+            assert_eq!(read_varint(&mut bytes.as_slice()).unwrap(), value);
+        }
+        assert_eq!(varint_len(u128::MAX), MAX_VARINT_LEN);
+
+        let too_long = [0x80u8; MAX_VARINT_LEN + 1];
+        assert!(matches!(
+            read_varint(&mut &too_long[..]),
+            Err(DecodeError::InvalidVarint)
+        ));
+        let mut too_big = [0xffu8; MAX_VARINT_LEN];
+        too_big[MAX_VARINT_LEN - 1] = 0x04;
+        assert!(matches!(
+            read_varint(&mut &too_big[..]),
+            Err(DecodeError::InvalidVarint)
+        ));
+    }
+
+    #[test]
+    fn test_layouts() {
+        // dense
+        let bytes = round_trip(Multiset::from([1u8, 2, 3, 4]));
+        assert_eq!(bytes, [VERSION, LAYOUT_DENSE, 1, 4, 1, 2, 3, 4]);
+
+        // sparse
+        let mut set = Multiset::<u32, 1024>::empty();
+        set.insert(3, 70_000);
+        set.insert(900, 2);
+        let bytes = round_trip(set);
+        assert_eq!(bytes[1], LAYOUT_SPARSE);
+        assert_eq!(bytes.len(), 3 + 2 + 1 + (1 + 3) + (2 + 1));
+
+        // run length
+        let set = Multiset::from([7u16; 500]);
+        let bytes = round_trip(set);
+        assert_eq!(bytes[1], LAYOUT_RLE);
+        assert_eq!(bytes, [VERSION, LAYOUT_RLE, 2, 0xf4, 0x03, 0xf4, 0x03, 7]);
+
+        // empty multisets
+        round_trip(Multiset::<u8, 0>::empty());
+        round_trip(Multiset::<u64, 10>::empty());
+    }
+
+    #[test]
+    fn test_round_trip_widths() {
+        round_trip(Multiset::from([u8::MAX, 0, 1, 0, 0, 0]));
+        round_trip(Multiset::from([u16::MAX, 0, 1, 1, 1, 1]));
+        round_trip(Multiset::from([u32::MAX, 0, 0, 0, 0, 9]));
+        round_trip(Multiset::from([u64::MAX, 3, 2, 1, 0, 0]));
+        round_trip(Multiset::from([u128::MAX, 0, 0, 0, 0, u128::MAX]));
+        round_trip(Multiset::from([usize::MAX, 0, 5, 5, 5, 5]));
+        round_trip(Multiset::from([u128::MAX; 64]));
+    }
+
+    #[test]
+    fn test_decode_errors() {
+        let mut bytes = Vec::new();
+        Multiset::from([1u8, 2, 3, 4]).encode(&mut bytes).unwrap();
+
+        for len in 0..bytes.len() {
+            assert!(matches!(
+                Multiset::<u8, 4>::decode(&mut &bytes[..len]),
+                Err(DecodeError::Truncated)
+            ));
+        }
+
+        let mut corrupt = bytes.clone();
+        corrupt[0] = 9;
+        assert!(matches!(
+            Multiset::<u8, 4>::decode(&mut corrupt.as_slice()),
+            Err(DecodeError::UnsupportedVersion(9))
+        ));
+
+        let mut corrupt = bytes.clone();
+        corrupt[1] = 3;
+        assert!(matches!(
+            Multiset::<u8, 4>::decode(&mut corrupt.as_slice()),
+            Err(DecodeError::UnknownLayout(3))
+        ));
+
+        let mut corrupt = bytes.clone();
+        corrupt[2] = 3;
+        assert!(matches!(
+            Multiset::<u8, 4>::decode(&mut corrupt.as_slice()),
+            Err(DecodeError::InvalidCounterWidth(3))
+        ));
+
+        // sparse element past the end
+        let corrupt = [VERSION, LAYOUT_SPARSE, 1, 4, 2, 1, 1, 2, 1];
+        assert!(matches!(
+            Multiset::<u8, 4>::decode(&mut &corrupt[..]),
+            Err(DecodeError::ElementOutOfRange { element: 4, len: 4 })
+        ));
+
+        // empty run, and a run past the end
+        let corrupt = [VERSION, LAYOUT_RLE, 1, 4, 0, 1];
+        assert!(matches!(
+            Multiset::<u8, 4>::decode(&mut &corrupt[..]),
+            Err(DecodeError::InvalidRun { element: 0 })
+        ));
+        let corrupt = [VERSION, LAYOUT_RLE, 1, 4, 2, 1, 3, 1];
+        assert!(matches!(
+            Multiset::<u8, 4>::decode(&mut &corrupt[..]),
+            Err(DecodeError::InvalidRun { element: 2 })
+        ));
+
+        // counts wider than the counter type
+        let mut bytes = Vec::new();
+        Multiset::from([1u32, 2, 256, 4])
+            .encode(&mut bytes)
+            .unwrap();
+        assert!(matches!(
+            Multiset::<u8, 4>::decode(&mut bytes.as_slice()),
+            Err(DecodeError::CounterOverflow { element: 2 })
+        ));
+        assert_eq!(
+            Multiset::<u16, 4>::decode(&mut bytes.as_slice()).unwrap(),
+            Multiset::from([1, 2, 256, 4])
+        );
+    }
+}
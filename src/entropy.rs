@@ -0,0 +1,248 @@
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::math;
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::Counter;
+#[cfg(any(feature = "std", feature = "libm"))]
+use num_traits::AsPrimitive;
+
+/// The base of the logarithm used by [`renyi_entropy`], which determines the
+/// unit of the entropy.
+///
+/// [`renyi_entropy`]: crate::Multiset::renyi_entropy
+///
+/// # Examples
+///
+/// ```
+/// use utote::{LogBase, Multiset};
+///
+/// # #[cfg(any(feature = "std", feature = "libm"))]
+/// # {
+/// let multiset = Multiset::from([1u8, 1, 0, 0]);
+/// assert_eq!(multiset.renyi_entropy(1.0, LogBase::Two), 1.0);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LogBase {
+    /// The natural logarithm, giving the entropy in nats. This is the base
+    /// used by [`shannon_entropy`](crate::Multiset::shannon_entropy).
+    E,
+    /// The binary logarithm, giving the entropy in bits. This is the base
+    /// used by [`collision_entropy`](crate::Multiset::collision_entropy).
+    Two,
+    /// The common logarithm, giving the entropy in hartleys.
+    Ten,
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+impl LogBase {
+    #[inline]
+    pub(crate) fn log(self, x: f64) -> f64 {
+        match self {
+            LogBase::E => math::ln(x),
+            LogBase::Two => math::log2(x),
+            LogBase::Ten => math::log10(x),
+        }
+    }
+
+    // Converts an entropy from nats, which is exact for `LogBase::E`.
+    #[inline]
+    pub(crate) fn convert_nats(self, nats: f64) -> f64 {
+        match self {
+            LogBase::E => nats,
+            LogBase::Two => nats * core::f64::consts::LOG2_E,
+            LogBase::Ten => nats * core::f64::consts::LOG10_E,
+        }
+    }
+
+    // Converts an entropy from bits, which is exact for `LogBase::Two`.
+    #[inline]
+    pub(crate) fn convert_bits(self, bits: f64) -> f64 {
+        match self {
+            LogBase::E => bits * core::f64::consts::LN_2,
+            LogBase::Two => bits,
+            LogBase::Ten => bits * core::f64::consts::LOG10_2,
+        }
+    }
+}
+
+// Checks the order of a Rényi entropy or Hill number.
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn check_order(order: f64) {
+    assert!(order >= 0.0, "the order must not be negative");
+}
+
+/// The estimator of the entropy of the distribution a multiset was sampled
+/// from, used by [`entropy_with`].
+///
+/// The plug-in estimator, which is the
+/// [`shannon_entropy`](crate::Multiset::shannon_entropy) of the multiset,
+/// underestimates the entropy when the total is small relative to the number
+/// of elements. The other estimators correct for this bias. Every estimate is
+/// in nats, and is zero for an empty multiset.
+///
+/// [`entropy_with`]: crate::Multiset::entropy_with
+///
+/// # Examples
+///
+/// ```
+/// use utote::{EntropyEstimator, Multiset};
+///
+/// # #[cfg(any(feature = "std", feature = "libm"))]
+/// # {
+/// let multiset = Multiset::from([4u8, 2, 3, 0, 2, 4, 0, 0, 2, 1, 1]);
+/// let plug_in = multiset.entropy_with(EntropyEstimator::PlugIn);
+/// assert!(multiset.entropy_with(EntropyEstimator::MillerMadow) > plug_in);
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum EntropyEstimator {
+    /// The maximum likelihood, or plug-in, estimator, which is the entropy of
+    /// the counts divided by the total.
+    PlugIn,
+    /// The Miller–Madow estimator, which adds `(m - 1) / 2n` to the plug-in
+    /// estimate, where `m` is the number of elements with a non-zero count
+    /// and `n` is the total.
+    MillerMadow,
+    /// The coverage-adjusted estimator of Chao and Shen (2003), which scales
+    /// the probabilities by the estimated sample coverage and weights each
+    /// term by the inverse of the probability that its element is seen.
+    ChaoShen,
+    /// The estimator of Grassberger (2003), which replaces the logarithms of
+    /// the counts with a function of the digamma function.
+    Grassberger,
+    /// The James–Stein shrinkage estimator of Hausser and Strimmer (2009),
+    /// which shrinks the probabilities towards the uniform distribution over
+    /// every element of the multiset by an estimated optimal amount.
+    JamesStein,
+}
+
+// The estimators other than the plug-in estimator, which is the
+// `shannon_entropy` of the multiset.
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[inline]
+pub(crate) fn miller_madow(plug_in: f64, non_zero: usize, total: u128) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        plug_in + (non_zero as f64 - 1.0) / (2.0 * total as f64)
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+pub(crate) fn chao_shen<N: Counter>(counts: &[N], total: u128) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let n = total as f64;
+    let mut singletons = counts.iter().filter(|c| **c == N::one()).count() as f64;
+    // With only singletons the coverage would be zero.
+    if singletons == n {
+        singletons = n - 1.0;
+    }
+    let coverage = 1.0 - singletons / n;
+    -counts
+        .iter()
+        .filter(|c| **c > N::zero())
+        .fold(0.0, |acc, c| {
+            let count: f64 = c.as_();
+            let prob = coverage * count / n;
+            acc + prob * math::ln(prob) / (1.0 - math::powf(1.0 - prob, n))
+        })
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+pub(crate) fn grassberger<N: Counter>(counts: &[N], total: u128) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let n = total as f64;
+    let sum = counts
+        .iter()
+        .filter(|c| **c > N::zero())
+        .fold(0.0, |acc, c| {
+            let count: f64 = c.as_();
+            let odd = <N as AsPrimitive<u128>>::as_(*c) % 2 == 1;
+            let sign = if odd { -0.5 } else { 0.5 };
+            let g = digamma(count) + sign * (digamma((count + 1.0) / 2.0) - digamma(count / 2.0));
+            acc + count * g
+        });
+    math::ln(n) - sum / n
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+pub(crate) fn james_stein<N: Counter>(counts: &[N], total: u128) -> f64 {
+    if total == 0 {
+        return 0.0;
+    }
+    let n = total as f64;
+    let target = 1.0 / counts.len() as f64;
+    // The shrinkage intensity is the estimated variance of the frequencies
+    // divided by their squared distance from the target. With a total of one
+    // the variance can't be estimated, so the frequencies are shrunk fully.
+    let lambda = if total == 1 {
+        1.0
+    } else {
+        let (square_sum, distance) = counts.iter().fold((0.0, 0.0), |(s, d), c| {
+            let freq: f64 = <N as AsPrimitive<f64>>::as_(*c) / n;
+            (s + freq * freq, d + (freq - target) * (freq - target))
+        });
+        if distance == 0.0 {
+            1.0
+        } else {
+            ((1.0 - square_sum) / ((n - 1.0) * distance)).clamp(0.0, 1.0)
+        }
+    };
+    -counts.iter().fold(0.0, |acc, c| {
+        let freq: f64 = <N as AsPrimitive<f64>>::as_(*c) / n;
+        let prob = lambda * target + (1.0 - lambda) * freq;
+        if prob > 0.0 {
+            acc + prob * math::ln(prob)
+        } else {
+            acc
+        }
+    })
+}
+
+// The digamma function for positive `x`, using the recurrence
+// `digamma(x) = digamma(x + 1) - 1 / x` to reach the range where the
+// asymptotic expansion is accurate to double precision.
+#[cfg(any(feature = "std", feature = "libm"))]
+fn digamma(x: f64) -> f64 {
+    let mut x = x;
+    let mut res = 0.0;
+    while x < 10.0 {
+        res -= 1.0 / x;
+        x += 1.0;
+    }
+    // The Bernoulli numbers B_2k divided by 2k, for the terms of the expansion
+    // in x^-2k.
+    const COEFFS: [f64; 6] = [
+        1.0 / 12.0,
+        -1.0 / 120.0,
+        1.0 / 252.0,
+        -1.0 / 240.0,
+        1.0 / 132.0,
+        -691.0 / 32760.0,
+    ];
+    let inv = 1.0 / x;
+    let inv2 = inv * inv;
+    let series = COEFFS.iter().rev().fold(0.0, |acc, c| acc * inv2 + c) * inv2;
+    res + math::ln(x) - 0.5 * inv - series
+}
+
+#[cfg(all(test, any(feature = "std", feature = "libm")))]
+mod tests {
+    use super::*;
+    use approx::assert_relative_eq;
+
+    #[test]
+    fn test_digamma() {
+        // -γ, -γ - 2 ln 2 and a value from the asymptotic range.
+        assert_relative_eq!(digamma(1.0), -0.5772156649015329, epsilon = 1e-14);
+        assert_relative_eq!(digamma(0.5), -1.9635100260214235, epsilon = 1e-14);
+        assert_relative_eq!(digamma(100.0), 4.600161852738087, epsilon = 1e-14);
+    }
+}
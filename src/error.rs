@@ -0,0 +1,327 @@
+use crate::Backend;
+use core::fmt::{Display, Formatter, Result};
+#[cfg(feature = "std")]
+use std::io;
+
+/// The error type for fallible multiset operations.
+///
+/// # Examples
+///
+/// ```
+/// use utote::{Error, MultisetVec};
+///
+/// let a = MultisetVec::from(vec![1u8, 2, 3]);
+/// let b = MultisetVec::from(vec![1u8, 2]);
+/// assert_eq!(
+///     a.union(&b),
+///     Err(Error::LengthMismatch { expected: 3, found: 2 })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// Two multisets, or a multiset and some counter storage, were combined
+    /// but do not have the same number of elements.
+    LengthMismatch {
+        /// The number of elements required.
+        expected: usize,
+        /// The number of elements given.
+        found: usize,
+    },
+    /// An element was used which is not within the bounds of the multiset.
+    ElementOutOfRange {
+        /// The element which was out of range.
+        element: usize,
+        /// The number of elements in the multiset.
+        len: usize,
+    },
+    /// An arithmetic operation on the count of an element overflowed, or
+    /// underflowed below zero.
+    Overflow {
+        /// The element whose count overflowed.
+        element: usize,
+    },
+    /// An operation which requires at least one element, or a non-zero
+    /// total, was given an empty multiset.
+    EmptyMultiset,
+    /// A backend was selected which the cpu does not support.
+    UnsupportedBackend(Backend),
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            Error::LengthMismatch { expected, found } => write!(
+                f,
+                "length mismatch: expected {} elements, found {}",
+                expected, found
+            ),
+            Error::ElementOutOfRange { element, len } => write!(
+                f,
+                "element {} out of range for multiset of {} elements",
+                element, len
+            ),
+            Error::Overflow { element } => write!(f, "counter overflow at element {}", element),
+            Error::EmptyMultiset => write!(f, "multiset is empty"),
+            Error::UnsupportedBackend(backend) => {
+                write!(f, "backend {:?} is not supported by the cpu", backend)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// The error type returned when decoding a multiset from the binary format
+/// written by [`Multiset::encode`](crate::Multiset::encode).
+///
+/// # Examples
+///
+/// ```
+/// use utote::{DecodeError, Multiset};
+///
+/// let mut bytes = Vec::new();
+/// Multiset::from([1u8, 2, 3]).encode(&mut bytes).unwrap();
+///
+/// let truncated = &bytes[..bytes.len() - 1];
+/// assert!(matches!(
+///     Multiset::<u8, 3>::decode(&mut &truncated[..]),
+///     Err(DecodeError::Truncated)
+/// ));
+/// ```
+#[cfg(feature = "std")]
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The input ended before a complete multiset was read.
+    Truncated,
+    /// The input was written with a format version this crate cannot read.
+    UnsupportedVersion(u8),
+    /// The layout tag in the header is not a known layout.
+    UnknownLayout(u8),
+    /// The counter width in the header is not the width of any counter type.
+    InvalidCounterWidth(u8),
+    /// The encoded multiset does not have the number of elements required.
+    LengthMismatch {
+        /// The number of elements required.
+        expected: usize,
+        /// The number of elements encoded.
+        found: u128,
+    },
+    /// A sparse entry refers to an element outside of the multiset.
+    ElementOutOfRange {
+        /// The element which was out of range.
+        element: u128,
+        /// The number of elements in the multiset.
+        len: usize,
+    },
+    /// An encoded count does not fit in the counter type being decoded.
+    CounterOverflow {
+        /// The element whose count does not fit.
+        element: usize,
+    },
+    /// A variable length integer was longer than any valid encoding.
+    InvalidVarint,
+    /// A run length encoded run was empty or ran past the end of the multiset.
+    InvalidRun {
+        /// The element at which the run starts.
+        element: usize,
+    },
+    /// Reading from the underlying reader failed.
+    Io(io::Error),
+}
+
+#[cfg(feature = "std")]
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            DecodeError::Truncated => write!(f, "unexpected end of input"),
+            DecodeError::UnsupportedVersion(version) => {
+                write!(f, "unsupported format version {}", version)
+            }
+            DecodeError::UnknownLayout(tag) => write!(f, "unknown layout tag {}", tag),
+            DecodeError::InvalidCounterWidth(width) => {
+                write!(f, "invalid counter width of {} bytes", width)
+            }
+            DecodeError::LengthMismatch { expected, found } => write!(
+                f,
+                "length mismatch: expected {} elements, found {}",
+                expected, found
+            ),
+            DecodeError::ElementOutOfRange { element, len } => write!(
+                f,
+                "element {} out of range for multiset of {} elements",
+                element, len
+            ),
+            DecodeError::CounterOverflow { element } => {
+                write!(f, "counter overflow at element {}", element)
+            }
+            DecodeError::InvalidVarint => write!(f, "invalid variable length integer"),
+            DecodeError::InvalidRun { element } => {
+                write!(f, "invalid run starting at element {}", element)
+            }
+            DecodeError::Io(err) => write!(f, "io error: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            DecodeError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<io::Error> for DecodeError {
+    fn from(err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::UnexpectedEof => DecodeError::Truncated,
+            _ => DecodeError::Io(err),
+        }
+    }
+}
+
+/// The error type returned when parsing a multiset from a string.
+///
+/// Holds the byte offset into the input at which parsing failed, along with
+/// the kind of failure.
+///
+/// # Examples
+///
+/// ```
+/// use utote::{Multiset, ParseErrorKind};
+///
+/// let err = "{0: 3, 7: 1}".parse::<Multiset<u8, 4>>().unwrap_err();
+/// assert_eq!(err.offset(), 7);
+/// assert_eq!(err.kind(), &ParseErrorKind::ElementOutOfRange { len: 4 });
+/// assert_eq!(
+///     err.to_string(),
+///     "element out of range for multiset of 4 elements at byte 7"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ParseError {
+    pub(crate) offset: usize,
+    pub(crate) kind: ParseErrorKind,
+}
+
+impl ParseError {
+    /// The byte offset into the input at which parsing failed.
+    #[inline]
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The kind of failure.
+    #[inline]
+    pub fn kind(&self) -> &ParseErrorKind {
+        &self.kind
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        write!(f, "{} at byte {}", self.kind, self.offset)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for ParseError {}
+
+/// The kinds of failure which can occur when parsing a multiset.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input ended before the multiset was complete.
+    UnexpectedEnd,
+    /// A character was found where something else was expected.
+    UnexpectedChar {
+        /// The character found.
+        found: char,
+        /// A description of what was expected instead.
+        expected: &'static str,
+    },
+    /// A count does not fit in the counter type.
+    CounterOverflow,
+    /// An element is not within the bounds of the multiset.
+    ElementOutOfRange {
+        /// The number of elements in the multiset.
+        len: usize,
+    },
+    /// An element was given a count more than once.
+    DuplicateElement,
+    /// A dense list does not have the number of counts required.
+    LengthMismatch {
+        /// The number of counts required.
+        expected: usize,
+        /// The number of counts given.
+        found: usize,
+    },
+}
+
+impl Display for ParseErrorKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        match self {
+            ParseErrorKind::UnexpectedEnd => write!(f, "unexpected end of input"),
+            ParseErrorKind::UnexpectedChar { found, expected } => {
+                write!(f, "expected {}, found {:?}", expected, found)
+            }
+            ParseErrorKind::CounterOverflow => write!(f, "count too large for counter type"),
+            ParseErrorKind::ElementOutOfRange { len } => {
+                write!(f, "element out of range for multiset of {} elements", len)
+            }
+            ParseErrorKind::DuplicateElement => write!(f, "duplicate element"),
+            ParseErrorKind::LengthMismatch { expected, found } => write!(
+                f,
+                "length mismatch: expected {} counts, found {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = Error::LengthMismatch {
+            expected: 4,
+            found: 2,
+        };
+        assert_eq!(
+            err.to_string(),
+            "length mismatch: expected 4 elements, found 2"
+        );
+        let err = Error::Overflow { element: 3 };
+        assert_eq!(err.to_string(), "counter overflow at element 3");
+        let err = Error::ElementOutOfRange { element: 5, len: 4 };
+        assert_eq!(
+            err.to_string(),
+            "element 5 out of range for multiset of 4 elements"
+        );
+        assert_eq!(Error::EmptyMultiset.to_string(), "multiset is empty");
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_decode_error() {
+        let err = DecodeError::from(io::Error::from(io::ErrorKind::UnexpectedEof));
+        assert!(matches!(err, DecodeError::Truncated));
+        assert_eq!(err.to_string(), "unexpected end of input");
+
+        let err = DecodeError::from(io::Error::from(io::ErrorKind::PermissionDenied));
+        assert!(matches!(err, DecodeError::Io(_)));
+        assert!(std::error::Error::source(&err).is_some());
+
+        let err = DecodeError::InvalidRun { element: 7 };
+        assert_eq!(err.to_string(), "invalid run starting at element 7");
+        assert!(std::error::Error::source(&err).is_none());
+    }
+}
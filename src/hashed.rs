@@ -0,0 +1,411 @@
+use crate::{Counter, Error, Multiset};
+use core::hash::{Hash, Hasher};
+use core::ops::Deref;
+
+// The fingerprint of a multiset is the xor of a hash of each (element, count)
+// pair with a non-zero count. Changing one count only changes its own term, so
+// the fingerprint can be updated in O(1) by xoring out the old term and xoring
+// in the new one.
+//
+// The terms are built from the splitmix64 finalizer over fixed width
+// integers, so the fingerprints are the same on every platform. They are part
+// of the public API and must not change between releases.
+
+const SEED: u64 = 0x9e37_79b9_7f4a_7c15;
+
+#[inline]
+fn mix(mut z: u64) -> u64 {
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[inline]
+fn term<N: Counter>(elem: usize, count: N) -> u64 {
+    if count == N::zero() {
+        return 0;
+    }
+    let count: u128 = count.as_();
+    let h = mix(elem as u64 ^ SEED);
+    let h = mix(h ^ count as u64);
+    mix(h.wrapping_add(SEED) ^ (count >> 64) as u64)
+}
+
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// Returns a 64 bit fingerprint of the multiset, which does not depend on
+    /// the platform or on the version of this crate.
+    ///
+    /// Equal multisets have equal fingerprints, and the empty multiset has a
+    /// fingerprint of zero. This takes O(`SIZE`) time, use a
+    /// [`HashedMultiset`] to keep the fingerprint up to date as the multiset
+    /// changes instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 0, 3]);
+    /// let b = Multiset::from([1u8, 0, 2]);
+    /// assert_ne!(a.fingerprint(), b.fingerprint());
+    /// assert_eq!(Multiset::<u8, 3>::empty().fingerprint(), 0);
+    ///
+    /// // Fingerprints only depend on the non-zero counts, not the counter type.
+    /// assert_eq!(a.fingerprint(), Multiset::from([1u64, 0, 3]).fingerprint());
+    /// ```
+    #[inline]
+    pub fn fingerprint(&self) -> u64 {
+        self.iter()
+            .enumerate()
+            .fold(0, |acc, (elem, count)| acc ^ term(elem, *count))
+    }
+}
+
+/// A [`Multiset`] which keeps its [`fingerprint`](Multiset::fingerprint) up to
+/// date as it is changed, so that it can be hashed in O(1).
+///
+/// The mutating methods update the fingerprint in O(1), regardless of `SIZE`.
+/// `Hash` only writes the fingerprint, which makes `HashedMultiset` a cheap key
+/// for a `HashMap`, for example as a transposition table key in a search where
+/// each step changes a few counts. The read only methods of [`Multiset`] are
+/// available through `Deref`.
+///
+/// # Examples
+///
+/// ```
+/// use utote::{HashedMultiset, Multiset};
+///
+/// let mut hashed = HashedMultiset::new(Multiset::from([1u8, 0, 3]));
+/// hashed.checked_add_count(1, 2).unwrap();
+/// hashed.remove(2);
+///
+/// assert_eq!(hashed.as_multiset(), &Multiset::from([1, 2, 0]));
+/// assert_eq!(hashed.fingerprint(), Multiset::from([1u8, 2, 0]).fingerprint());
+/// assert_eq!(hashed.total(), 3);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct HashedMultiset<N: Counter, const SIZE: usize> {
+    set: Multiset<N, SIZE>,
+    fingerprint: u64,
+}
+
+impl<N: Counter, const SIZE: usize> Hash for HashedMultiset<N, SIZE> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        state.write_u64(self.fingerprint)
+    }
+}
+
+impl<N: Counter, const SIZE: usize> PartialEq for HashedMultiset<N, SIZE> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.fingerprint == other.fingerprint && self.set.data == other.set.data
+    }
+}
+
+impl<N: Counter, const SIZE: usize> Eq for HashedMultiset<N, SIZE> {}
+
+impl<N: Counter, const SIZE: usize> Default for HashedMultiset<N, SIZE> {
+    #[inline]
+    fn default() -> Self {
+        HashedMultiset {
+            set: Multiset::empty(),
+            fingerprint: 0,
+        }
+    }
+}
+
+impl<N: Counter, const SIZE: usize> Deref for HashedMultiset<N, SIZE> {
+    type Target = Multiset<N, SIZE>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.set
+    }
+}
+
+impl<N: Counter, const SIZE: usize> From<Multiset<N, SIZE>> for HashedMultiset<N, SIZE> {
+    #[inline]
+    fn from(set: Multiset<N, SIZE>) -> Self {
+        HashedMultiset::new(set)
+    }
+}
+
+impl<N: Counter, const SIZE: usize> From<HashedMultiset<N, SIZE>> for Multiset<N, SIZE> {
+    #[inline]
+    fn from(hashed: HashedMultiset<N, SIZE>) -> Self {
+        hashed.set
+    }
+}
+
+impl<N: Counter, const SIZE: usize> HashedMultiset<N, SIZE> {
+    /// Wraps `set`, computing its fingerprint in O(`SIZE`).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{HashedMultiset, Multiset};
+    ///
+    /// let set = Multiset::from([1u8, 0, 3]);
+    /// let hashed = HashedMultiset::new(set);
+    /// assert_eq!(hashed.fingerprint(), set.fingerprint());
+    /// ```
+    #[inline]
+    pub fn new(set: Multiset<N, SIZE>) -> Self {
+        HashedMultiset {
+            fingerprint: set.fingerprint(),
+            set,
+        }
+    }
+
+    /// Returns a HashedMultiset with all element counts set to zero.
+    #[inline]
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Returns the fingerprint of the multiset, which is always equal to
+    /// [`Multiset::fingerprint`] of the wrapped multiset.
+    #[inline]
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Returns a reference to the wrapped multiset.
+    #[inline]
+    pub fn as_multiset(&self) -> &Multiset<N, SIZE> {
+        &self.set
+    }
+
+    /// Returns the wrapped multiset.
+    #[inline]
+    pub fn into_inner(self) -> Multiset<N, SIZE> {
+        self.set
+    }
+
+    // Applies `f`, which may only change the count of `elem`, and updates the
+    // fingerprint for the change.
+    #[inline]
+    fn update<R, F>(&mut self, elem: usize, f: F) -> R
+    where
+        F: FnOnce(&mut Multiset<N, SIZE>) -> R,
+    {
+        let before = self.set.get(elem).copied();
+        let res = f(&mut self.set);
+        if let (Some(before), Some(after)) = (before, self.set.get(elem).copied()) {
+            self.fingerprint ^= term(elem, before) ^ term(elem, after);
+        }
+        res
+    }
+
+    /// Sets all element counts in the multiset to zero.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.set.clear();
+        self.fingerprint = 0;
+    }
+
+    /// Set the count of `elem` in the multiset to `amount`.
+    ///
+    /// See [`Multiset::insert`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{HashedMultiset, Multiset};
+    ///
+    /// let mut hashed = HashedMultiset::<u8, 4>::empty();
+    /// hashed.insert(2, 5);
+    /// assert_eq!(hashed.fingerprint(), Multiset::from([0u8, 0, 5, 0]).fingerprint());
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, elem: usize, amount: N) {
+        self.update(elem, |set| set.insert(elem, amount))
+    }
+
+    /// Set the count of `elem` in the multiset to `amount`, or return
+    /// [`Error::ElementOutOfRange`] if `elem` is out of bounds.
+    ///
+    /// See [`Multiset::try_insert`].
+    #[inline]
+    pub fn try_insert(&mut self, elem: usize, amount: N) -> Result<(), Error> {
+        self.update(elem, |set| set.try_insert(elem, amount))
+    }
+
+    /// Set the count of `elem` in the multiset to zero.
+    ///
+    /// See [`Multiset::remove`].
+    #[inline]
+    pub fn remove(&mut self, elem: usize) {
+        self.update(elem, |set| set.remove(elem))
+    }
+
+    /// Set the count of `elem` in the multiset to zero, or return
+    /// [`Error::ElementOutOfRange`] if `elem` is out of bounds.
+    ///
+    /// See [`Multiset::try_remove`].
+    #[inline]
+    pub fn try_remove(&mut self, elem: usize) -> Result<(), Error> {
+        self.update(elem, |set| set.try_remove(elem))
+    }
+
+    /// Adds `n` to the count of `elem`, returning the previous count.
+    ///
+    /// See [`Multiset::add_count`].
+    #[inline]
+    pub fn add_count(&mut self, elem: usize, n: N) -> N {
+        self.checked_add_count(elem, n)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Subtracts `n` from the count of `elem`, returning the previous count.
+    ///
+    /// See [`Multiset::sub_count`].
+    #[inline]
+    pub fn sub_count(&mut self, elem: usize, n: N) -> N {
+        self.checked_sub_count(elem, n)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Checked addition of `n` to the count of `elem`, returning the previous
+    /// count.
+    ///
+    /// See [`Multiset::checked_add_count`].
+    #[inline]
+    pub fn checked_add_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        self.update(elem, |set| set.checked_add_count(elem, n))
+    }
+
+    /// Checked subtraction of `n` from the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// See [`Multiset::checked_sub_count`].
+    #[inline]
+    pub fn checked_sub_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        self.update(elem, |set| set.checked_sub_count(elem, n))
+    }
+
+    /// Saturating addition of `n` to the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// See [`Multiset::saturating_add_count`].
+    #[inline]
+    pub fn saturating_add_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        self.update(elem, |set| set.saturating_add_count(elem, n))
+    }
+
+    /// Saturating subtraction of `n` from the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// See [`Multiset::saturating_sub_count`].
+    #[inline]
+    pub fn saturating_sub_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        self.update(elem, |set| set.saturating_sub_count(elem, n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_fingerprint_values() {
+        // These values are part of the public API, and must not change.
+        assert_eq!(Multiset::<u8, 4>::empty().fingerprint(), 0);
+        assert_eq!(
+            Multiset::from([1u8, 0, 0, 0]).fingerprint(),
+            9639420846141804449
+        );
+        assert_eq!(
+            Multiset::from([0u8, 2, 0, 0]).fingerprint(),
+            2283217684075206078
+        );
+        assert_eq!(
+            Multiset::from([3u32, 1, 0, 7]).fingerprint(),
+            12417133794665783646
+        );
+        assert_eq!(
+            Multiset::from([0, u128::MAX]).fingerprint(),
+            8307975410824898749
+        );
+    }
+
+    #[test]
+    fn test_fingerprint_counter_independent() {
+        let a = Multiset::from([3u8, 1, 0, 7]);
+        assert_eq!(a.fingerprint(), a.cast::<u16>().fingerprint());
+        assert_eq!(a.fingerprint(), a.cast::<u64>().fingerprint());
+        assert_eq!(a.fingerprint(), a.cast::<u128>().fingerprint());
+        assert_eq!(a.fingerprint(), a.cast::<usize>().fingerprint());
+    }
+
+    #[test]
+    fn test_fingerprint_distinct() {
+        // All multisets of 3 elements with counts below 8.
+        let mut seen = HashSet::new();
+        for i in 0..8u16 * 8 * 8 {
+            let set = Multiset::from([i % 8, i / 8 % 8, i / 64]);
+            assert!(seen.insert(set.fingerprint()));
+        }
+    }
+
+    #[test]
+    fn test_mutators_update_fingerprint() {
+        let mut hashed = HashedMultiset::new(Multiset::from([1u8, 0, 3, 255]));
+        let check = |hashed: &HashedMultiset<u8, 4>| {
+            assert_eq!(hashed.fingerprint(), hashed.as_multiset().fingerprint());
+        };
+        check(&hashed);
+        hashed.insert(1, 4);
+        check(&hashed);
+        hashed.insert(9, 4);
+        check(&hashed);
+        hashed.remove(0);
+        check(&hashed);
+        assert_eq!(hashed.try_insert(2, 0), Ok(()));
+        check(&hashed);
+        assert_eq!(
+            hashed.try_remove(4),
+            Err(Error::ElementOutOfRange { element: 4, len: 4 })
+        );
+        check(&hashed);
+        assert_eq!(hashed.checked_add_count(1, 2), Ok(4));
+        check(&hashed);
+        assert_eq!(
+            hashed.checked_add_count(3, 1),
+            Err(Error::Overflow { element: 3 })
+        );
+        check(&hashed);
+        assert_eq!(hashed.checked_sub_count(1, 6), Ok(6));
+        check(&hashed);
+        assert_eq!(hashed.saturating_add_count(0, 200), Ok(0));
+        assert_eq!(hashed.saturating_add_count(0, 200), Ok(200));
+        check(&hashed);
+        assert_eq!(hashed.saturating_sub_count(3, 1), Ok(255));
+        check(&hashed);
+        assert_eq!(hashed.add_count(1, 2), 0);
+        check(&hashed);
+        assert_eq!(hashed.sub_count(1, 2), 2);
+        check(&hashed);
+        assert_eq!(hashed.as_multiset(), &Multiset::from([255, 0, 0, 254]));
+        hashed.clear();
+        check(&hashed);
+        assert_eq!(hashed, HashedMultiset::empty());
+    }
+
+    #[test]
+    fn test_hash_map_key() {
+        let mut seen = HashSet::new();
+        let mut hashed = HashedMultiset::<u16, 8>::empty();
+        assert!(seen.insert(hashed));
+        hashed.insert(3, 2);
+        assert!(seen.insert(hashed));
+        hashed.remove(3);
+        assert!(!seen.insert(hashed));
+        assert!(seen.contains(&HashedMultiset::new(Multiset::from([
+            0, 0, 0, 2, 0, 0, 0, 0
+        ]))));
+        assert_eq!(Multiset::from(hashed), Multiset::empty());
+    }
+}
@@ -0,0 +1,398 @@
+// Iterators over the elements of a multiset, rather than over its raw
+// counters. They all borrow a slice of counters, so the same types are
+// returned by every multiset flavour.
+
+use crate::Counter;
+use alloc::vec::{self, Vec};
+use core::cmp::Ordering;
+use core::iter::{Enumerate, FusedIterator};
+use core::slice::Iter;
+
+/// An iterator over the `(element, count)` pairs of a multiset with a non-zero
+/// count, in element order.
+///
+/// Created by [`Multiset::iter_nonzero`](crate::Multiset::iter_nonzero).
+#[derive(Debug, Clone)]
+pub struct IterNonZero<'a, N: Counter> {
+    iter: Enumerate<Iter<'a, N>>,
+}
+
+impl<'a, N: Counter> IterNonZero<'a, N> {
+    #[inline]
+    pub(crate) fn new(data: &'a [N]) -> Self {
+        IterNonZero {
+            iter: data.iter().enumerate(),
+        }
+    }
+}
+
+impl<'a, N: Counter> Iterator for IterNonZero<'a, N> {
+    type Item = (usize, &'a N);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.find(|(_, count)| **count > N::zero())
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.iter.len()))
+    }
+}
+
+impl<'a, N: Counter> DoubleEndedIterator for IterNonZero<'a, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.rfind(|(_, count)| **count > N::zero())
+    }
+}
+
+impl<'a, N: Counter> FusedIterator for IterNonZero<'a, N> {}
+
+/// An iterator over the elements of a multiset with a non-zero count, in
+/// element order.
+///
+/// Created by [`Multiset::support`](crate::Multiset::support).
+#[derive(Debug, Clone)]
+pub struct Support<'a, N: Counter> {
+    iter: IterNonZero<'a, N>,
+}
+
+impl<'a, N: Counter> Support<'a, N> {
+    #[inline]
+    pub(crate) fn new(data: &'a [N]) -> Self {
+        Support {
+            iter: IterNonZero::new(data),
+        }
+    }
+}
+
+impl<'a, N: Counter> Iterator for Support<'a, N> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        self.iter.next().map(|(elem, _)| elem)
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, N: Counter> DoubleEndedIterator for Support<'a, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<usize> {
+        self.iter.next_back().map(|(elem, _)| elem)
+    }
+}
+
+impl<'a, N: Counter> FusedIterator for Support<'a, N> {}
+
+/// An iterator yielding each element of a multiset as many times as its count,
+/// in element order.
+///
+/// This is not an `ExactSizeIterator`, since the total of a multiset can be
+/// larger than `usize::MAX`.
+///
+/// Created by [`Multiset::elements`](crate::Multiset::elements).
+#[derive(Debug, Clone)]
+pub struct Elements<'a, N: Counter> {
+    iter: IterNonZero<'a, N>,
+    // The element at each end which is part way through being yielded, and
+    // how many more times it is to be yielded.
+    front: (usize, N),
+    back: (usize, N),
+}
+
+impl<'a, N: Counter> Elements<'a, N> {
+    #[inline]
+    pub(crate) fn new(data: &'a [N]) -> Self {
+        Elements {
+            iter: IterNonZero::new(data),
+            front: (0, N::zero()),
+            back: (0, N::zero()),
+        }
+    }
+}
+
+impl<'a, N: Counter> Iterator for Elements<'a, N> {
+    type Item = usize;
+
+    #[inline]
+    fn next(&mut self) -> Option<usize> {
+        loop {
+            if self.front.1 > N::zero() {
+                self.front.1 -= N::one();
+                return Some(self.front.0);
+            }
+            match self.iter.next() {
+                Some((elem, count)) => self.front = (elem, *count),
+                None => break,
+            }
+        }
+        if self.back.1 > N::zero() {
+            self.back.1 -= N::one();
+            return Some(self.back.0);
+        }
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let front: u128 = self.front.1.as_();
+        let back: u128 = self.back.1.as_();
+        let remaining = front.saturating_add(back);
+        if remaining > usize::MAX as u128 {
+            (usize::MAX, None)
+        } else if self.iter.iter.len() == 0 {
+            (remaining as usize, Some(remaining as usize))
+        } else {
+            (remaining as usize, None)
+        }
+    }
+}
+
+impl<'a, N: Counter> DoubleEndedIterator for Elements<'a, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<usize> {
+        loop {
+            if self.back.1 > N::zero() {
+                self.back.1 -= N::one();
+                return Some(self.back.0);
+            }
+            match self.iter.next_back() {
+                Some((elem, count)) => self.back = (elem, *count),
+                None => break,
+            }
+        }
+        if self.front.1 > N::zero() {
+            self.front.1 -= N::one();
+            return Some(self.front.0);
+        }
+        None
+    }
+}
+
+impl<'a, N: Counter> FusedIterator for Elements<'a, N> {}
+
+/// An iterator over `(element, count)` pairs of a multiset sorted by count,
+/// without reordering the multiset itself. Ties are yielded in element order.
+///
+/// Created by [`Multiset::iter_by_count_desc`](crate::Multiset::iter_by_count_desc),
+/// [`Multiset::top_k`](crate::Multiset::top_k) and
+/// [`Multiset::bottom_k`](crate::Multiset::bottom_k).
+#[derive(Debug, Clone)]
+pub struct IterByCount<'a, N: Counter> {
+    iter: vec::IntoIter<(usize, &'a N)>,
+}
+
+// Largest count first, with ties broken by the smaller element.
+#[inline]
+fn cmp_desc<N: Counter>(a: &(usize, &N), b: &(usize, &N)) -> Ordering {
+    b.1.cmp(a.1).then(a.0.cmp(&b.0))
+}
+
+// Smallest count first, with ties broken by the smaller element.
+#[inline]
+fn cmp_asc<N: Counter>(a: &(usize, &N), b: &(usize, &N)) -> Ordering {
+    a.1.cmp(b.1).then(a.0.cmp(&b.0))
+}
+
+impl<'a, N: Counter> IterByCount<'a, N> {
+    #[inline]
+    pub(crate) fn descending(data: &'a [N]) -> Self {
+        let mut pairs: Vec<(usize, &'a N)> = data.iter().enumerate().collect();
+        pairs.sort_unstable_by(cmp_desc);
+        IterByCount {
+            iter: pairs.into_iter(),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn top_k(data: &'a [N], k: usize) -> Self {
+        Self::select(data, k, cmp_desc)
+    }
+
+    #[inline]
+    pub(crate) fn bottom_k(data: &'a [N], k: usize) -> Self {
+        Self::select(data, k, cmp_asc)
+    }
+
+    // Partitions out the first `k` pairs in the given order in linear time, so
+    // only those need to be sorted. The ordering is total, since no two pairs
+    // have the same element, so the result does not depend on the partition.
+    #[inline]
+    fn select<F>(data: &'a [N], k: usize, mut cmp: F) -> Self
+    where
+        F: FnMut(&(usize, &'a N), &(usize, &'a N)) -> Ordering,
+    {
+        let mut pairs: Vec<(usize, &'a N)> = data.iter().enumerate().collect();
+        if k < pairs.len() {
+            pairs.select_nth_unstable_by(k, &mut cmp);
+            pairs.truncate(k);
+        }
+        pairs.sort_unstable_by(cmp);
+        IterByCount {
+            iter: pairs.into_iter(),
+        }
+    }
+}
+
+impl<'a, N: Counter> Iterator for IterByCount<'a, N> {
+    type Item = (usize, &'a N);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, N: Counter> DoubleEndedIterator for IterByCount<'a, N> {
+    #[inline]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
+
+impl<'a, N: Counter> ExactSizeIterator for IterByCount<'a, N> {}
+
+impl<'a, N: Counter> FusedIterator for IterByCount<'a, N> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_iter_nonzero() {
+        let data = [0u8, 3, 0, 1, 2, 0];
+        let pairs: Vec<_> = IterNonZero::new(&data).collect();
+        assert_eq!(pairs, vec![(1, &3), (3, &1), (4, &2)]);
+        let rev: Vec<_> = IterNonZero::new(&data).rev().collect();
+        assert_eq!(rev, vec![(4, &2), (3, &1), (1, &3)]);
+
+        let mut iter = IterNonZero::new(&data);
+        assert_eq!(iter.next(), Some((1, &3)));
+        assert_eq!(iter.next_back(), Some((4, &2)));
+        assert_eq!(iter.next(), Some((3, &1)));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        assert_eq!(IterNonZero::new(&[0u8; 4]).next(), None);
+        assert_eq!(IterNonZero::<u8>::new(&[]).next(), None);
+    }
+
+    #[test]
+    fn test_support() {
+        let data = [0u32, 3, 0, 1, 2, 0];
+        assert_eq!(Support::new(&data).collect::<Vec<_>>(), vec![1, 3, 4]);
+        assert_eq!(Support::new(&data).rev().collect::<Vec<_>>(), vec![4, 3, 1]);
+    }
+
+    #[test]
+    fn test_elements() {
+        let data = [0u16, 3, 0, 1, 2, 0];
+        let elems: Vec<_> = Elements::new(&data).collect();
+        assert_eq!(elems, vec![1, 1, 1, 3, 4, 4]);
+        let rev: Vec<_> = Elements::new(&data).rev().collect();
+        assert_eq!(rev, vec![4, 4, 3, 1, 1, 1]);
+        assert_eq!(Elements::new(&data).count(), 6);
+        assert_eq!(Elements::new(&[0u8, 0]).next(), None);
+    }
+
+    #[test]
+    fn test_elements_both_ends() {
+        let data = [2u8, 0, 1, 3];
+        let mut iter = Elements::new(&data);
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next_back(), Some(2));
+        // The zero count of element 1 has not been skipped yet.
+        assert_eq!(iter.size_hint(), (1, None));
+        assert_eq!(iter.next_back(), Some(0));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+
+        // Meeting in the middle of a single element.
+        let data = [0u8, 4, 0];
+        let mut iter = Elements::new(&data);
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(1));
+        assert_eq!(iter.size_hint(), (2, Some(2)));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), None);
+    }
+
+    #[test]
+    fn test_elements_size_hint() {
+        let data = [u64::MAX, u64::MAX, 1];
+        let mut iter = Elements::new(&data);
+        assert_eq!(iter.size_hint(), (0, None));
+        iter.next();
+        assert_eq!(iter.size_hint(), (u64::MAX as usize - 1, None));
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (u64::MAX as usize - 1, None));
+        iter.next_back();
+        assert_eq!(iter.size_hint(), (usize::MAX, None));
+    }
+
+    #[test]
+    fn test_iter_by_count_desc() {
+        let data = [2u8, 0, 5, 2, 1];
+        let sorted: Vec<_> = IterByCount::descending(&data).collect();
+        assert_eq!(sorted, vec![(2, &5), (0, &2), (3, &2), (4, &1), (1, &0)]);
+        let mut iter = IterByCount::descending(&data);
+        assert_eq!(iter.len(), 5);
+        assert_eq!(iter.next_back(), Some((1, &0)));
+        assert_eq!(iter.len(), 4);
+        // The storage is not reordered.
+        assert_eq!(data, [2, 0, 5, 2, 1]);
+    }
+
+    #[test]
+    fn test_top_bottom_k() {
+        let data = [2u8, 0, 5, 2, 1, 7, 0];
+        let top: Vec<_> = IterByCount::top_k(&data, 3).collect();
+        assert_eq!(top, vec![(5, &7), (2, &5), (0, &2)]);
+        let bottom: Vec<_> = IterByCount::bottom_k(&data, 3).collect();
+        assert_eq!(bottom, vec![(1, &0), (6, &0), (4, &1)]);
+
+        assert_eq!(IterByCount::top_k(&data, 0).len(), 0);
+        assert_eq!(IterByCount::bottom_k(&data, 0).next(), None);
+        assert_eq!(IterByCount::top_k(&data, 3).len(), 3);
+
+        // Asking for more than there are elements gives all of them.
+        let all: Vec<_> = IterByCount::top_k(&data, 100).collect();
+        assert_eq!(all, IterByCount::descending(&data).collect::<Vec<_>>());
+        let all: Vec<_> = IterByCount::bottom_k(&data, 7).collect();
+        let mut rev: Vec<_> = IterByCount::descending(&data).collect();
+        rev.sort_by(|a, b| a.1.cmp(b.1).then(a.0.cmp(&b.0)));
+        assert_eq!(all, rev);
+    }
+
+    #[test]
+    fn test_top_k_matches_sort() {
+        let data: Vec<u16> = (0..200u32).map(|i| ((i * 7919) % 13) as u16).collect();
+        let sorted: Vec<_> = IterByCount::descending(&data).collect();
+        for k in [0, 1, 5, 13, 50, 199, 200].iter() {
+            let top: Vec<_> = IterByCount::top_k(&data, *k).collect();
+            assert_eq!(top, sorted[..*k]);
+            let bottom: Vec<_> = IterByCount::bottom_k(&data, *k).collect();
+            let expected: Vec<_> = sorted.iter().rev().take(*k).collect();
+            // Ties are in element order in both, so compare counts only.
+            assert_eq!(
+                bottom.iter().map(|p| p.1).collect::<Vec<_>>(),
+                expected.iter().map(|p| p.1).collect::<Vec<_>>()
+            );
+        }
+    }
+}
@@ -0,0 +1,268 @@
+// Slice level implementations of the multiset operations which benefit from
+// vectorisation. Every kernel expects slices of the same length, so callers
+// check the lengths first. The `core::arch` kernels load and store through raw
+// pointers, so they also assert it rather than read or write out of bounds.
+//
+// The scalar kernels are used by default. With the `stable_simd` feature on
+// x86 targets they are replaced by the `core::arch` kernels in `arch`, which
+// fall back to the scalar kernels when no suitable cpu feature is detected.
+
+#[cfg(not(all(
+    feature = "stable_simd",
+    any(target_arch = "x86", target_arch = "x86_64")
+)))]
+pub(crate) use self::scalar::*;
+#[cfg(all(
+    feature = "stable_simd",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+pub(crate) use crate::arch::*;
+
+#[allow(dead_code)]
+pub(crate) mod scalar {
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use crate::math;
+    use crate::{Counter, Error};
+    use core::convert::TryFrom;
+    use core::mem::size_of;
+    use num_traits::AsPrimitive;
+
+    #[inline]
+    pub(crate) fn intersection<N: Counter>(a: &[N], b: &[N], out: &mut [N]) {
+        for ((o, a), b) in out.iter_mut().zip(a.iter()).zip(b.iter()) {
+            *o = *a.min(b);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn union<N: Counter>(a: &[N], b: &[N], out: &mut [N]) {
+        for ((o, a), b) in out.iter_mut().zip(a.iter()).zip(b.iter()) {
+            *o = *a.max(b);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn is_disjoint<N: Counter>(a: &[N], b: &[N]) -> bool {
+        a.iter().zip(b.iter()).all(|(a, b)| a.min(b).is_zero())
+    }
+
+    #[inline]
+    pub(crate) fn is_subset<N: Counter>(a: &[N], b: &[N]) -> bool {
+        a.iter().zip(b.iter()).all(|(a, b)| a <= b)
+    }
+
+    #[inline]
+    pub(crate) fn is_superset<N: Counter>(a: &[N], b: &[N]) -> bool {
+        a.iter().zip(b.iter()).all(|(a, b)| a >= b)
+    }
+
+    #[inline]
+    pub(crate) fn is_any_lesser<N: Counter>(a: &[N], b: &[N]) -> bool {
+        a.iter().zip(b.iter()).any(|(a, b)| a < b)
+    }
+
+    #[inline]
+    pub(crate) fn is_any_greater<N: Counter>(a: &[N], b: &[N]) -> bool {
+        a.iter().zip(b.iter()).any(|(a, b)| a > b)
+    }
+
+    #[inline]
+    pub(crate) fn total<N: Counter>(a: &[N]) -> usize {
+        // Counters wider than `usize` would be truncated by `as`, so they are
+        // summed as `u128` and checked instead.
+        if size_of::<N>() > size_of::<usize>() {
+            total_usize(a.iter().fold(0u128, |acc, e| {
+                acc.saturating_add(<N as AsPrimitive<u128>>::as_(*e))
+            }))
+        } else {
+            a.iter().map(|e| <N as AsPrimitive<usize>>::as_(*e)).sum()
+        }
+    }
+
+    // `N2` can represent every value of `N`, so the counts are converted as is.
+    #[inline]
+    pub(crate) fn cast_counts<N: Counter, N2: Counter + From<N>>(a: &[N], out: &mut [N2]) {
+        for (o, a) in out.iter_mut().zip(a.iter()) {
+            *o = N2::from(*a);
+        }
+    }
+
+    #[inline]
+    pub(crate) fn try_cast_counts<N: Counter, N2: Counter + TryFrom<N>>(
+        a: &[N],
+        out: &mut [N2],
+    ) -> Result<(), Error> {
+        for (elem, (o, a)) in out.iter_mut().zip(a.iter()).enumerate() {
+            *o = N2::try_from(*a).map_err(|_| Error::Overflow { element: elem })?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    pub(crate) fn saturating_cast_counts<N: Counter, N2: Counter + TryFrom<N>>(
+        a: &[N],
+        out: &mut [N2],
+    ) {
+        for (o, a) in out.iter_mut().zip(a.iter()) {
+            *o = N2::try_from(*a).unwrap_or_else(|_| N2::max_value());
+        }
+    }
+
+    // Converts a total accumulated in a wider integer to the `usize` returned
+    // by `total`, which `u128` counters and `u64` counters on 32 bit targets
+    // can exceed.
+    #[inline]
+    pub(crate) fn total_usize(total: u128) -> usize {
+        usize::try_from(total).expect("the total of the multiset overflowed usize")
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn collision_entropy<N: Counter>(a: &[N], total: f64) -> f64 {
+        -math::log2(a.iter().fold(0.0, |acc, count| {
+            let freq_f64: f64 = count.as_();
+            acc + math::powf(freq_f64 / total, 2.0)
+        }))
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn shannon_entropy<N: Counter>(a: &[N], total: f64) -> f64 {
+        -a.iter().fold(0.0, |acc, count| {
+            if count > &N::zero() {
+                let freq_f64: f64 = count.as_();
+                let prob = freq_f64 / total;
+                acc + prob * math::ln(prob)
+            } else {
+                acc
+            }
+        })
+    }
+
+    // The sum of the probabilities raised to the power `alpha`, from which
+    // the Rényi entropies of other orders are calculated. `alpha` must be
+    // positive, so that zero counts add nothing.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn power_sum<N: Counter>(a: &[N], total: f64, alpha: f64) -> f64 {
+        a.iter().fold(0.0, |acc, count| {
+            let freq_f64: f64 = count.as_();
+            acc + math::powf(freq_f64 / total, alpha)
+        })
+    }
+
+    // The sum of the products of the counts, which is the dot product of the
+    // counts as vectors.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn dot<N: Counter>(a: &[N], b: &[N]) -> f64 {
+        a.iter().zip(b.iter()).fold(0.0, |acc, (a, b)| {
+            let a: f64 = a.as_();
+            let b: f64 = b.as_();
+            acc + a * b
+        })
+    }
+
+    // The sum of the square roots of the products of the counts.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn sqrt_dot<N: Counter>(a: &[N], b: &[N]) -> f64 {
+        a.iter().zip(b.iter()).fold(0.0, |acc, (a, b)| {
+            let a: f64 = a.as_();
+            let b: f64 = b.as_();
+            acc + math::sqrt(a * b)
+        })
+    }
+
+    // The divergence kernels compare the distributions given by adding
+    // `pseudocount` to every count and dividing by `total_a` or `total_b`,
+    // which include the pseudocounts. The totals must not be zero.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn kl_divergence<N: Counter>(
+        a: &[N],
+        b: &[N],
+        total_a: f64,
+        total_b: f64,
+        pseudocount: f64,
+    ) -> f64 {
+        zip_probs(a, b, total_a, total_b, pseudocount).fold(0.0, |acc, (p, q)| {
+            if p > 0.0 {
+                acc + p * math::ln(p / q)
+            } else {
+                acc
+            }
+        })
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn cross_entropy<N: Counter>(
+        a: &[N],
+        b: &[N],
+        total_a: f64,
+        total_b: f64,
+        pseudocount: f64,
+    ) -> f64 {
+        -zip_probs(a, b, total_a, total_b, pseudocount).fold(0.0, |acc, (p, q)| {
+            if p > 0.0 {
+                acc + p * math::ln(q)
+            } else {
+                acc
+            }
+        })
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn jensen_shannon_divergence<N: Counter>(
+        a: &[N],
+        b: &[N],
+        total_a: f64,
+        total_b: f64,
+        pseudocount: f64,
+    ) -> f64 {
+        let sum = zip_probs(a, b, total_a, total_b, pseudocount).fold(0.0, |acc, (p, q)| {
+            let m = (p + q) / 2.0;
+            let mut acc = acc;
+            if p > 0.0 {
+                acc += p * math::ln(p / m);
+            }
+            if q > 0.0 {
+                acc += q * math::ln(q / m);
+            }
+            acc
+        });
+        0.5 * sum
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub(crate) fn total_variation<N: Counter>(
+        a: &[N],
+        b: &[N],
+        total_a: f64,
+        total_b: f64,
+        pseudocount: f64,
+    ) -> f64 {
+        let sum = zip_probs(a, b, total_a, total_b, pseudocount)
+            .fold(0.0, |acc, (p, q)| acc + (p - q).max(q - p));
+        0.5 * sum
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn zip_probs<'a, N: Counter>(
+        a: &'a [N],
+        b: &'a [N],
+        total_a: f64,
+        total_b: f64,
+        pseudocount: f64,
+    ) -> impl Iterator<Item = (f64, f64)> + 'a {
+        a.iter().zip(b.iter()).map(move |(a, b)| {
+            let a: f64 = a.as_();
+            let b: f64 = b.as_();
+            ((a + pseudocount) / total_a, (b + pseudocount) / total_b)
+        })
+    }
+}
@@ -16,6 +16,13 @@
 //! If you have a known number of things that you want to count and do set-like
 //! operations on, then this crate will likely be useful to you.
 //!
+//! When the number of things is only known at runtime, [`MultisetVec`]
+//! provides the same operations with heap allocated storage. Algorithms which
+//! should work with any kind of multiset, including plain slices of counters,
+//! can be written against the [`MultisetOps`] trait. Counters which already
+//! live in some other buffer can be viewed as a multiset without copying via
+//! [`MultisetSlice`] and [`MultisetSliceMut`].
+//!
 //! # Recipes
 //!
 //! `Multiset` can only keep track of uint counters, but if you have a fixed
@@ -50,12 +57,32 @@
 //!
 //! # Cargo Features
 //!
+//! - __std__: Enabled by default. Without it the crate is `no_std`, but still
+//!   needs `alloc` for [`MultisetVec`]. The entropy methods then need the
+//!   __libm__ feature, and `encode`, `decode`, `DecodeError` and
+//!   `with_backend` are unavailable. The simd backend is chosen from the
+//!   target features enabled at compile time instead of being detected at
+//!   runtime.
+//! - __libm__: Provides the float functions used by the entropy methods with
+//!   the [__libm__ crate](https://docs.rs/libm) when __std__ is disabled.
 //! - __simd__: Requires nightly rust toolchain. Enables simd implementations
-//! using the [__packed_simd__ crate](https://docs.rs/packed_simd_2) crate and
-//! unsatble features: [const_generics](https://github.com/rust-lang/rust/issues/44580)
-//! and [const_evaluatable_checked](https://github.com/rust-lang/rust/issues/76560).
+//!   using the [__packed_simd__ crate](https://docs.rs/packed_simd_2) crate and
+//!   unsatble features: [const_generics](https://github.com/rust-lang/rust/issues/44580)
+//!   and [const_evaluatable_checked](https://github.com/rust-lang/rust/issues/76560).
+//! - __portable_simd__: Requires nightly rust toolchain. Enables the same simd
+//!   implementations as __simd__, built on the portable vector types of
+//!   [`core::simd`](https://doc.rust-lang.org/nightly/core/simd/) instead of
+//!   __packed_simd__, so they can be compiled for any target. Cannot be used
+//!   together with __simd__.
+//! - __stable_simd__: Enables simd implementations on x86 and x86_64 which
+//!   work on the stable toolchain, using the `core::arch` intrinsics with
+//!   runtime detection of SSE4.2, AVX and AVX2. This speeds up the comparison,
+//!   `intersection`, `union`, `total` and entropy methods.
 //! - __rand__: Enables [`choose_random`](Multiset::choose_random) methods for
-//! multiset structs using the [__rand__ crate](https://docs.rs/rand).
+//!   multiset structs using the [__rand__ crate](https://docs.rs/rand).
+//! - __serde__: Enables `Serialize` and `Deserialize` for multiset structs
+//!   using the [__serde__ crate](https://docs.rs/serde). See the [`serde`]
+//!   module for the available representations.
 //!
 //! # Performance
 //!
@@ -67,17 +94,89 @@
 //!
 //! The most simple way to improve performance is to use the simd
 //! implementations by turning on the `simd` feature of Utote. If you can use
-//! the nightly toolchain then this should be utilised.
+//! the nightly toolchain then this should be utilised. On the stable toolchain
+//! the `stable_simd` feature provides simd implementations of the most common
+//! operations on x86 and x86_64.
+//!
+//! On x86 and x86_64 the simd implementations are chosen at runtime, using the
+//! best [`Backend`] supported by the cpu. This is detected once and cached. A
+//! backend can be forced with [`set_backend`], for example to avoid AVX on
+//! cpus where it lowers the clock speed, or for a single closure with
+//! [`with_backend`], which is useful to compare the backends in tests and
+//! benchmarks.
+//!
+//! ```
+//! use utote::{Backend, Multiset};
+//!
+//! let a = Multiset::from([1u16, 2, 3, 0]);
+//! let b = Multiset::from([3u16, 2, 1, 0]);
+//!
+//! for backend in [Backend::Scalar, Backend::Sse42, Backend::Avx, Backend::Avx2].iter() {
+//!     if backend.is_supported() {
+//!         utote::set_backend(*backend).unwrap();
+//!         assert_eq!(a.union(&b), Multiset::from([3, 2, 3, 0]));
+//!     }
+//! }
+//!
+//! // Return to the best backend for the cpu.
+//! utote::set_backend(Backend::detect()).unwrap();
+//! ```
 
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
 #![cfg_attr(
     feature = "simd",
-    feature(const_generics, const_evaluatable_checked),
+    feature(const_generics, const_evaluatable_checked, cfg_overflow_checks),
     allow(incomplete_features)
 )]
+#![cfg_attr(
+    feature = "portable_simd",
+    feature(portable_simd, generic_const_exprs, cfg_overflow_checks),
+    allow(incomplete_features)
+)]
+
+#[cfg(all(feature = "simd", feature = "portable_simd"))]
+compile_error!("the `simd` and `portable_simd` features cannot be enabled together");
 
+extern crate alloc;
+
+#[cfg(all(
+    feature = "stable_simd",
+    any(target_arch = "x86", target_arch = "x86_64")
+))]
+mod arch;
+mod backend;
+#[cfg(feature = "std")]
+pub use backend::with_backend;
+pub use backend::{backend, set_backend, Backend};
+#[cfg(any(feature = "simd", feature = "portable_simd"))]
+mod chunks;
+mod distance;
+pub mod diversity;
+#[cfg(feature = "std")]
+mod encoding;
+mod entropy;
+pub use entropy::{EntropyEstimator, LogBase};
+mod error;
+#[cfg(feature = "std")]
+pub use error::DecodeError;
+pub use error::{Error, ParseError, ParseErrorKind};
+mod hashed;
+pub use hashed::HashedMultiset;
+mod iter;
+pub use iter::{Elements, IterByCount, IterNonZero, Support};
+mod kernels;
+#[cfg(any(feature = "std", feature = "libm"))]
+mod math;
 mod multiset;
 pub use multiset::*;
-#[cfg(feature = "simd")]
-mod chunks;
-#[cfg(feature = "simd")]
+mod multiset_ops;
+pub use multiset_ops::MultisetOps;
+mod multiset_slice;
+pub use multiset_slice::*;
+mod multiset_vec;
+pub use multiset_vec::*;
+mod parse;
+#[cfg(feature = "serde")]
+pub mod serde;
+#[cfg(any(feature = "simd", feature = "portable_simd"))]
 mod simd;
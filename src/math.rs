@@ -0,0 +1,74 @@
+// The float functions used by the entropy and distance methods. These come from std when it
+// is available, and otherwise from the `libm` crate.
+
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn ln(x: f64) -> f64 {
+    x.ln()
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn ln(x: f64) -> f64 {
+    libm::log(x)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn log2(x: f64) -> f64 {
+    x.log2()
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn log2(x: f64) -> f64 {
+    libm::log2(x)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn powf(x: f64, y: f64) -> f64 {
+    x.powf(y)
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn powf(x: f64, y: f64) -> f64 {
+    libm::pow(x, y)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn sqrt(x: f64) -> f64 {
+    x.sqrt()
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn sqrt(x: f64) -> f64 {
+    libm::sqrt(x)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn log10(x: f64) -> f64 {
+    x.log10()
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn log10(x: f64) -> f64 {
+    libm::log10(x)
+}
+
+#[cfg(feature = "std")]
+#[inline]
+pub(crate) fn exp(x: f64) -> f64 {
+    x.exp()
+}
+
+#[cfg(not(feature = "std"))]
+#[inline]
+pub(crate) fn exp(x: f64) -> f64 {
+    libm::exp(x)
+}
@@ -1,18 +1,45 @@
-#[cfg(feature = "simd")]
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+use crate::distance;
+#[cfg(all(
+    not(any(feature = "simd", feature = "portable_simd")),
+    any(feature = "std", feature = "libm")
+))]
+use crate::entropy;
+use crate::iter::{Elements, IterByCount, IterNonZero, Support};
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+use crate::kernels;
+#[cfg(all(
+    not(any(feature = "simd", feature = "portable_simd")),
+    any(feature = "std", feature = "libm")
+))]
+use crate::math;
+#[cfg(any(feature = "simd", feature = "portable_simd"))]
 use crate::simd::SimdTypes;
-use num_traits::{AsPrimitive, One, Unsigned, Zero};
-#[cfg(all(not(feature = "simd"), feature = "rand"))]
-use rand::{Rng, RngCore};
-#[cfg(not(feature = "simd"))]
-use std::cmp::Ordering;
-use std::fmt::{Debug, Display, Formatter, Result};
-use std::hash::{Hash, Hasher};
-use std::iter::FromIterator;
-use std::mem;
-use std::ops::{
+#[cfg(all(
+    not(any(feature = "simd", feature = "portable_simd")),
+    any(feature = "std", feature = "libm")
+))]
+use crate::{EntropyEstimator, LogBase};
+use crate::{Error, MultisetOps};
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt::{Debug, Display, Formatter, Result};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{
     Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
 };
-use std::slice::{Iter, IterMut, SliceIndex};
+use core::slice::{Iter, IterMut, SliceIndex};
+use num_traits::{
+    AsPrimitive, Bounded, CheckedAdd, CheckedMul, CheckedSub, FromPrimitive, One, SaturatingAdd,
+    SaturatingMul, SaturatingSub, Unsigned, WrappingAdd, WrappingMul, WrappingSub, Zero,
+};
+#[cfg(all(
+    not(any(feature = "simd", feature = "portable_simd")),
+    feature = "rand"
+))]
+use rand::{Rng, RngCore};
 
 mod sealed {
     pub trait Sealed {}
@@ -21,6 +48,7 @@ mod sealed {
     impl Sealed for u16 {}
     impl Sealed for u32 {}
     impl Sealed for u64 {}
+    impl Sealed for u128 {}
     impl Sealed for usize {}
 }
 
@@ -39,6 +67,15 @@ pub trait CounterArithmetic:
     + RemAssign
     + Sub
     + SubAssign
+    + CheckedAdd
+    + CheckedMul
+    + CheckedSub
+    + SaturatingAdd
+    + SaturatingMul
+    + SaturatingSub
+    + WrappingAdd
+    + WrappingMul
+    + WrappingSub
 {
     // empty
 }
@@ -47,6 +84,7 @@ impl CounterArithmetic for u8 {}
 impl CounterArithmetic for u16 {}
 impl CounterArithmetic for u32 {}
 impl CounterArithmetic for u64 {}
+impl CounterArithmetic for u128 {}
 impl CounterArithmetic for usize {}
 
 // Collects various properties into one trait for more concise implementations.
@@ -67,7 +105,10 @@ pub trait CounterBasic:
     + Unsigned
     + Zero
     + AsPrimitive<usize>
+    + AsPrimitive<u128>
     + AsPrimitive<f64>
+    + Bounded
+    + FromPrimitive
 {
     // empty
 }
@@ -76,16 +117,17 @@ impl CounterBasic for u8 {}
 impl CounterBasic for u16 {}
 impl CounterBasic for u32 {}
 impl CounterBasic for u64 {}
+impl CounterBasic for u128 {}
 impl CounterBasic for usize {}
 
-#[cfg(not(feature = "simd"))]
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 /// The Counter trait simplifies the use of Multiset with generics. This trait
 /// is sealed and not implementable outside of this crate.
 pub trait Counter: sealed::Sealed + CounterArithmetic + CounterBasic {
     // empty
 }
 
-#[cfg(feature = "simd")]
+#[cfg(any(feature = "simd", feature = "portable_simd"))]
 #[doc(hidden)]
 pub trait Counter: sealed::Sealed + CounterArithmetic + CounterBasic + SimdTypes {
     // empty
@@ -95,6 +137,7 @@ impl Counter for u8 {}
 impl Counter for u16 {}
 impl Counter for u32 {}
 impl Counter for u64 {}
+impl Counter for u128 {}
 impl Counter for usize {}
 
 /// A stack allocated multiset of unsigned integers.
@@ -178,6 +221,7 @@ impl Counter for usize {}
 ///     println!("{}", ms.is_empty());
 /// }
 /// ```
+#[repr(transparent)]
 pub struct Multiset<N: Counter, const SIZE: usize> {
     pub(crate) data: [N; SIZE],
 }
@@ -194,6 +238,49 @@ impl<N: Counter, const SIZE: usize> Debug for Multiset<N, SIZE> {
     }
 }
 
+/// Formats the multiset in sparse notation, mapping each element with a
+/// non-zero count to its count. The alternate flag (`{:#}`) formats the dense
+/// list of every count instead. Both forms can be parsed back with
+/// [`FromStr`](std::str::FromStr).
+///
+/// # Examples
+///
+/// ```
+/// use utote::Multiset;
+///
+/// let multiset = Multiset::from([3u8, 0, 1, 0]);
+/// assert_eq!(format!("{}", multiset), "{0: 3, 2: 1}");
+/// assert_eq!(format!("{:#}", multiset), "[3, 0, 1, 0]");
+/// assert_eq!(format!("{}", Multiset::<u8, 4>::empty()), "{}");
+/// ```
+impl<N: Counter, const SIZE: usize> Display for Multiset<N, SIZE> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result {
+        if f.alternate() {
+            write!(f, "[")?;
+            for (i, count) in self.data.iter().enumerate() {
+                if i > 0 {
+                    write!(f, ", ")?;
+                }
+                write!(f, "{}", count)?;
+            }
+            write!(f, "]")
+        } else {
+            write!(f, "{{")?;
+            let mut first = true;
+            for (elem, count) in self.data.iter().enumerate() {
+                if !count.is_zero() {
+                    if !first {
+                        write!(f, ", ")?;
+                    }
+                    write!(f, "{}: {}", elem, count)?;
+                    first = false;
+                }
+            }
+            write!(f, "}}")
+        }
+    }
+}
+
 impl<N: Counter, const SIZE: usize> Hash for Multiset<N, SIZE> {
     #[inline]
     fn hash<H: Hasher>(&self, state: &mut H) {
@@ -210,15 +297,17 @@ impl<N: Counter, const SIZE: usize> Clone for Multiset<N, SIZE> {
 
 impl<N: Counter, const SIZE: usize> Copy for Multiset<N, SIZE> {}
 
+// With the simd features `PartialEq` and `Eq` are implemented in the simd
+// module.
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> PartialEq for Multiset<N, SIZE> {
-    // Array compare will always use memcmp because N will be a unit, so no
-    // need for SIMD
     #[inline]
     fn eq(&self, other: &Self) -> bool {
         self.data == other.data
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> Eq for Multiset<N, SIZE> {}
 
 impl<N: Counter, I: SliceIndex<[N]>, const SIZE: usize> Index<I> for Multiset<N, SIZE> {
@@ -240,10 +329,8 @@ impl<N: Counter, I: SliceIndex<[N]>, const SIZE: usize> IndexMut<I> for Multiset
 impl<N: Counter, const SIZE: usize> FromIterator<N> for Multiset<N, SIZE> {
     #[inline]
     fn from_iter<T: IntoIterator<Item = N>>(iter: T) -> Self {
-        // Safety: iter chained with repeated zeroes guarantees that we have
-        // enough elements to fully initialise the Multiset.
-        let mut res: Self = unsafe { Multiset::new_uninitialized() };
-        let it = iter.into_iter().chain(std::iter::repeat(N::zero()));
+        let mut res = Multiset::empty();
+        let it = iter.into_iter().chain(core::iter::repeat(N::zero()));
         res.iter_mut().zip(it).for_each(|(r, e)| *r = e);
         res
     }
@@ -256,13 +343,29 @@ impl<'a, N: 'a + Counter, const SIZE: usize> FromIterator<&'a N> for Multiset<N,
     }
 }
 
+// Panics like `from_elements` when an element is out of bounds, and when a
+// count would overflow.
+impl<N: Counter, const SIZE: usize> Extend<usize> for Multiset<N, SIZE> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        self.extend_elements(iter)
+    }
+}
+
+impl<'a, N: Counter, const SIZE: usize> Extend<&'a usize> for Multiset<N, SIZE> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a usize>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
 impl<N: Counter, const SIZE: usize> IntoIterator for Multiset<N, SIZE> {
     type Item = N;
-    type IntoIter = std::array::IntoIter<N, SIZE>;
+    type IntoIter = core::array::IntoIter<N, SIZE>;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
-        std::array::IntoIter::new(self.data)
+        IntoIterator::into_iter(self.data)
     }
 }
 
@@ -307,10 +410,17 @@ impl<N: Counter, const SIZE: usize> From<Multiset<N, SIZE>> for [N; SIZE] {
     }
 }
 
-impl<N: Counter, const SIZE: usize> From<&[N]> for Multiset<N, SIZE> {
+impl<N: Counter, const SIZE: usize> TryFrom<&[N]> for Multiset<N, SIZE> {
+    type Error = Error;
+
     #[inline]
-    fn from(slice: &[N]) -> Self {
-        slice.iter().collect()
+    fn try_from(slice: &[N]) -> core::result::Result<Self, Self::Error> {
+        <[N; SIZE]>::try_from(slice)
+            .map(Multiset::from)
+            .map_err(|_| Error::LengthMismatch {
+                expected: SIZE,
+                found: slice.len(),
+            })
     }
 }
 
@@ -328,7 +438,7 @@ impl<'a, N: Counter, const SIZE: usize> From<&'a mut Multiset<N, SIZE>> for &'a
 }
 
 /// Partial order based on proper sub/super sets
-#[cfg(not(feature = "simd"))]
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> PartialOrd for Multiset<N, SIZE> {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -386,20 +496,21 @@ impl<N: Counter, const SIZE: usize> Default for Multiset<N, SIZE> {
 // Common ops implementations for Multiset
 ////////////////////////////////////////////////////////////////////////////////
 
-// todo: use SIMD for ops?
+// With the simd features `Add`, `Sub` and `Mul` are implemented in the simd
+// module. Division has no integer simd instruction, so `Div` and `Rem` are
+// always scalar.
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> Add for Multiset<N, SIZE> {
     type Output = Multiset<N, SIZE>;
 
     #[inline]
     fn add(self, rhs: Self) -> Self::Output {
-        self.into_iter()
-            .zip(rhs.into_iter())
-            .map(|(a, b)| a + b)
-            .collect()
+        self.into_iter().zip(rhs).map(|(a, b)| a + b).collect()
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> Add<N> for Multiset<N, SIZE> {
     type Output = Multiset<N, SIZE>;
 
@@ -409,15 +520,15 @@ impl<N: Counter, const SIZE: usize> Add<N> for Multiset<N, SIZE> {
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> AddAssign for Multiset<N, SIZE> {
     #[inline]
     fn add_assign(&mut self, rhs: Self) {
-        self.iter_mut()
-            .zip(rhs.into_iter())
-            .for_each(|(l, r)| *l += r);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l += r);
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> AddAssign<N> for Multiset<N, SIZE> {
     #[inline]
     fn add_assign(&mut self, rhs: N) {
@@ -430,10 +541,7 @@ impl<N: Counter, const SIZE: usize> Div for Multiset<N, SIZE> {
 
     #[inline]
     fn div(self, rhs: Self) -> Self::Output {
-        self.into_iter()
-            .zip(rhs.into_iter())
-            .map(|(a, b)| a / b)
-            .collect()
+        self.into_iter().zip(rhs).map(|(a, b)| a / b).collect()
     }
 }
 
@@ -449,9 +557,7 @@ impl<N: Counter, const SIZE: usize> Div<N> for Multiset<N, SIZE> {
 impl<N: Counter, const SIZE: usize> DivAssign for Multiset<N, SIZE> {
     #[inline]
     fn div_assign(&mut self, rhs: Self) {
-        self.iter_mut()
-            .zip(rhs.into_iter())
-            .for_each(|(l, r)| *l /= r);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l /= r);
     }
 }
 
@@ -462,18 +568,17 @@ impl<N: Counter, const SIZE: usize> DivAssign<N> for Multiset<N, SIZE> {
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> Mul for Multiset<N, SIZE> {
     type Output = Multiset<N, SIZE>;
 
     #[inline]
     fn mul(self, rhs: Self) -> Self::Output {
-        self.into_iter()
-            .zip(rhs.into_iter())
-            .map(|(a, b)| a * b)
-            .collect()
+        self.into_iter().zip(rhs).map(|(a, b)| a * b).collect()
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> Mul<N> for Multiset<N, SIZE> {
     type Output = Multiset<N, SIZE>;
 
@@ -483,15 +588,15 @@ impl<N: Counter, const SIZE: usize> Mul<N> for Multiset<N, SIZE> {
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> MulAssign for Multiset<N, SIZE> {
     #[inline]
     fn mul_assign(&mut self, rhs: Self) {
-        self.iter_mut()
-            .zip(rhs.into_iter())
-            .for_each(|(l, r)| *l *= r);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l *= r);
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> MulAssign<N> for Multiset<N, SIZE> {
     #[inline]
     fn mul_assign(&mut self, rhs: N) {
@@ -504,10 +609,7 @@ impl<N: Counter, const SIZE: usize> Rem for Multiset<N, SIZE> {
 
     #[inline]
     fn rem(self, rhs: Self) -> Self::Output {
-        self.into_iter()
-            .zip(rhs.into_iter())
-            .map(|(a, b)| a % b)
-            .collect()
+        self.into_iter().zip(rhs).map(|(a, b)| a % b).collect()
     }
 }
 
@@ -523,9 +625,7 @@ impl<N: Counter, const SIZE: usize> Rem<N> for Multiset<N, SIZE> {
 impl<N: Counter, const SIZE: usize> RemAssign for Multiset<N, SIZE> {
     #[inline]
     fn rem_assign(&mut self, rhs: Self) {
-        self.iter_mut()
-            .zip(rhs.into_iter())
-            .for_each(|(l, r)| *l %= r);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l %= r);
     }
 }
 
@@ -536,18 +636,17 @@ impl<N: Counter, const SIZE: usize> RemAssign<N> for Multiset<N, SIZE> {
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> Sub for Multiset<N, SIZE> {
     type Output = Multiset<N, SIZE>;
 
     #[inline]
     fn sub(self, rhs: Self) -> Self::Output {
-        self.into_iter()
-            .zip(rhs.into_iter())
-            .map(|(a, b)| a - b)
-            .collect()
+        self.into_iter().zip(rhs).map(|(a, b)| a - b).collect()
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> Sub<N> for Multiset<N, SIZE> {
     type Output = Multiset<N, SIZE>;
 
@@ -557,15 +656,15 @@ impl<N: Counter, const SIZE: usize> Sub<N> for Multiset<N, SIZE> {
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> SubAssign for Multiset<N, SIZE> {
     #[inline]
     fn sub_assign(&mut self, rhs: Self) {
-        self.iter_mut()
-            .zip(rhs.into_iter())
-            .for_each(|(l, r)| *l -= r);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l -= r);
     }
 }
 
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
 impl<N: Counter, const SIZE: usize> SubAssign<N> for Multiset<N, SIZE> {
     #[inline]
     fn sub_assign(&mut self, rhs: N) {
@@ -606,36 +705,6 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
         Multiset { data }
     }
 
-    // Safety: The Multiset created by this function must only be used once the
-    // Multiset.data array is guaranteed to be fully initialised.
-    #[inline]
-    unsafe fn new_uninitialized() -> Self {
-        Multiset {
-            data: mem::MaybeUninit::<[N; SIZE]>::uninit().assume_init(),
-        }
-    }
-
-    #[inline]
-    pub(crate) fn zip_map<N2, N3, F>(
-        &self,
-        other: &Multiset<N2, SIZE>,
-        mut f: F,
-    ) -> Multiset<N3, SIZE>
-    where
-        N2: Counter,
-        N3: Counter,
-        F: FnMut(N, N2) -> N3,
-    {
-        // Safety: both self and other *must* be the same length as res,
-        // therefore every element of res is guaranteed to be initialised upon
-        // completing the iter_mut.
-        let mut res = unsafe { Multiset::new_uninitialized() };
-        res.iter_mut()
-            .zip(self.iter().zip(other.iter()))
-            .for_each(|(r, (a, b))| *r = f(*a, *b));
-        res
-    }
-
     /// Returns a Multiset of the given array size with all element counts set
     /// to zero.
     ///
@@ -679,7 +748,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// incrementing the count of each element as it occurs in the iterator.
     ///
     /// # Panics
-    /// If any item in the iterator is out of bounds of the Multiset.
+    /// If any item in the iterator is out of bounds of the Multiset. See
+    /// [`try_from_elements`] for a fallible alternative.
+    ///
+    /// [`try_from_elements`]: Multiset::try_from_elements
     ///
     /// # Examples
     ///
@@ -704,6 +776,42 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
         })
     }
 
+    /// Constructs a Multiset from an iterator of elements in the multiset,
+    /// incrementing the count of each element as it occurs in the iterator.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if any item in the iterator is
+    /// out of bounds of the Multiset, or [`Error::Overflow`] if the count of
+    /// an element would overflow the counter type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let multiset = Multiset::<u8, 4>::try_from_elements(&[1, 1, 0, 2, 2, 2]);
+    /// assert_eq!(multiset, Ok(Multiset::from([1, 2, 3, 0])));
+    ///
+    /// let multiset = Multiset::<u8, 4>::try_from_elements(&[1, 4]);
+    /// assert_eq!(multiset, Err(Error::ElementOutOfRange { element: 4, len: 4 }));
+    /// ```
+    #[inline]
+    pub fn try_from_elements<'a, I>(elements: I) -> core::result::Result<Self, Error>
+    where
+        I: IntoIterator<Item = &'a usize>,
+    {
+        let mut res = Multiset::empty();
+        for &elem in elements {
+            let count: &mut N = res.get_mut(elem).ok_or(Error::ElementOutOfRange {
+                element: elem,
+                len: SIZE,
+            })?;
+            *count = count
+                .checked_add(&N::one())
+                .ok_or(Error::Overflow { element: elem })?;
+        }
+        Ok(res)
+    }
+
     /// Return an [Iter](`std::slice::Iter`) of the element counts in the
     /// Multiset.
     #[inline]
@@ -718,6 +826,115 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
         self.data.iter_mut()
     }
 
+    /// Returns an iterator over the `(element, count)` pairs with a non-zero
+    /// count, in element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([0u8, 3, 0, 1]);
+    /// let pairs: Vec<_> = multiset.iter_nonzero().collect();
+    /// assert_eq!(pairs, vec![(1, &3), (3, &1)]);
+    /// ```
+    #[inline]
+    pub fn iter_nonzero(&self) -> IterNonZero<'_, N> {
+        IterNonZero::new(&self.data)
+    }
+
+    /// Returns an iterator over the elements with a non-zero count, in
+    /// element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([0u8, 3, 0, 1]);
+    /// assert_eq!(multiset.support().collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    #[inline]
+    pub fn support(&self) -> Support<'_, N> {
+        Support::new(&self.data)
+    }
+
+    /// Returns an iterator yielding each element as many times as its count,
+    /// in element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([0u8, 3, 0, 1]);
+    /// let elements: Vec<_> = multiset.elements().collect();
+    /// assert_eq!(elements, vec![1, 1, 1, 3]);
+    /// ```
+    #[inline]
+    pub fn elements(&self) -> Elements<'_, N> {
+        Elements::new(&self.data)
+    }
+
+    /// Returns an iterator over all the `(element, count)` pairs, largest
+    /// count first. Elements with equal counts are in element order.
+    ///
+    /// The multiset itself is not reordered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([2u8, 0, 5, 2]);
+    /// let sorted: Vec<_> = multiset.iter_by_count_desc().collect();
+    /// assert_eq!(sorted, vec![(2, &5), (0, &2), (3, &2), (1, &0)]);
+    /// ```
+    #[inline]
+    pub fn iter_by_count_desc(&self) -> IterByCount<'_, N> {
+        IterByCount::descending(&self.data)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// largest counts, largest first, or over all of them if there are fewer
+    /// than `k` elements. Elements with equal counts are in element order.
+    ///
+    /// The `k` pairs are selected in linear time, so only they are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([2u8, 0, 5, 2]);
+    /// let top: Vec<_> = multiset.top_k(2).collect();
+    /// assert_eq!(top, vec![(2, &5), (0, &2)]);
+    /// ```
+    #[inline]
+    pub fn top_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::top_k(&self.data, k)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// smallest counts, smallest first, or over all of them if there are fewer
+    /// than `k` elements. Elements with equal counts are in element order.
+    ///
+    /// The `k` pairs are selected in linear time, so only they are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([2u8, 0, 5, 2]);
+    /// let bottom: Vec<_> = multiset.bottom_k(2).collect();
+    /// assert_eq!(bottom, vec![(1, &0), (0, &2)]);
+    /// ```
+    #[inline]
+    pub fn bottom_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::bottom_k(&self.data, k)
+    }
+
     /// Sets all element counts in the multiset to zero.
     ///
     /// # Examples
@@ -784,6 +1001,11 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
 
     /// Set the count of `elem` in the multiset to `amount`.
     ///
+    /// Does nothing if `elem` is out of bounds of the Multiset, see
+    /// [`try_insert`] for an alternative which reports this.
+    ///
+    /// [`try_insert`]: Multiset::try_insert
+    ///
     /// # Examples
     ///
     /// ```
@@ -801,6 +1023,28 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
         }
     }
 
+    /// Set the count of `elem` in the multiset to `amount`, or return
+    /// [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// Multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.try_insert(2, 5), Ok(()));
+    /// assert_eq!(multiset.get(2), Some(&5));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.try_insert(4, 5), Err(err));
+    /// ```
+    #[inline]
+    pub fn try_insert(&mut self, elem: usize, amount: N) -> core::result::Result<(), Error> {
+        *self.count_mut(elem)? = amount;
+        Ok(())
+    }
+
     /// Set the count of `elem` in the multiset to `amount`, without doing
     /// bounds checking.
     ///
@@ -833,6 +1077,11 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
 
     /// Set the count of `elem` in the multiset to zero.
     ///
+    /// Does nothing if `elem` is out of bounds of the Multiset, see
+    /// [`try_remove`] for an alternative which reports this.
+    ///
+    /// [`try_remove`]: Multiset::try_remove
+    ///
     /// # Examples
     ///
     /// ```
@@ -850,6 +1099,27 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
         }
     }
 
+    /// Set the count of `elem` in the multiset to zero, or return
+    /// [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// Multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.try_remove(1), Ok(()));
+    /// assert_eq!(multiset.get(1), Some(&0));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.try_remove(4), Err(err));
+    /// ```
+    #[inline]
+    pub fn try_remove(&mut self, elem: usize) -> core::result::Result<(), Error> {
+        self.try_insert(elem, N::zero())
+    }
+
     /// Set the count of `elem` in the multiset to zero, without doing bounds
     /// checking.
     ///
@@ -1014,10 +1284,12 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let c = Multiset::from([0, 1, 0, 0]);
     /// assert_eq!(a.intersection(&b), c);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn intersection(&self, other: &Self) -> Self {
-        self.zip_map(other, |e1, e2| e1.min(e2))
+        let mut res = Self::empty();
+        kernels::intersection(&self.data, &other.data, &mut res.data);
+        res
     }
 
     /// Returns a multiset which is the union of `self` and `other`.
@@ -1036,10 +1308,12 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let c = Multiset::from([1, 2, 3, 0]);
     /// assert_eq!(a.union(&b), c);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn union(&self, other: &Self) -> Self {
-        self.zip_map(other, |e1, e2| e1.max(e2))
+        let mut res = Self::empty();
+        kernels::union(&self.data, &other.data, &mut res.data);
+        res
     }
 
     /// Returns a multiset which is the difference of `self` and `other`.
@@ -1059,9 +1333,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let c = Multiset::from([1, 1, 0, 0]);
     /// assert_eq!(a.difference(&b), c);
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn difference(&self, other: &Self) -> Self {
-        self.zip_map(other, |e1, e2| {
+        self.zip_with(other, |e1, e2| {
             if e1 > N::zero() && e2 > N::zero() {
                 e1.min(e2)
             } else {
@@ -1089,9 +1364,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let c = Multiset::from([1, 1, 3, 0]);
     /// assert_eq!(a.symmetric_difference(&b), c);
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn symmetric_difference(&self, other: &Self) -> Self {
-        self.zip_map(other, |e1, e2| {
+        self.zip_with(other, |e1, e2| {
             if e1 > N::zero() && e2 > N::zero() {
                 e1.min(e2)
             } else {
@@ -1110,7 +1386,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([1u8, 0, 0, 0]);
     /// assert_eq!(multiset.count_non_zero(), 1);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn count_non_zero(&self) -> usize {
         self.iter().fold(0, |acc, &elem| {
@@ -1128,7 +1404,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([1u8, 0, 0, 0]);
     /// assert_eq!(multiset.count_zero(), 3);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn count_zero(&self) -> usize {
         SIZE - self.count_non_zero()
@@ -1145,7 +1421,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([0u8, 5, 0, 0]);
     /// assert_eq!(multiset.is_singleton(), true);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_singleton(&self) -> bool {
         self.count_non_zero() == 1
@@ -1171,12 +1447,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let c = Multiset::from([0, 1, 1, 0]);
     /// assert_eq!(a.is_disjoint(&c), false);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_disjoint(&self, other: &Self) -> bool {
-        self.iter()
-            .zip(other.iter())
-            .all(|(a, b)| a.min(b) == &N::zero())
+        kernels::is_disjoint(&self.data, &other.data)
     }
 
     /// Returns `true` if `self` is a subset of `other`.
@@ -1198,10 +1472,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     ///
     /// assert_eq!(a.is_subset(&Multiset::empty()), false);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_subset(&self, other: &Self) -> bool {
-        self.iter().zip(other.iter()).all(|(a, b)| a <= b)
+        kernels::is_subset(&self.data, &other.data)
     }
 
     /// Returns `true` if `self` is a superset of `other`.
@@ -1223,10 +1497,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     ///
     /// assert_eq!(a.is_superset(&Multiset::empty()), true);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_superset(&self, other: &Self) -> bool {
-        self.iter().zip(other.iter()).all(|(a, b)| a >= b)
+        kernels::is_superset(&self.data, &other.data)
     }
 
     /// Returns `true` if `self` is a proper subset of `other`.
@@ -1247,7 +1521,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let b = Multiset::from([1, 3, 0, 0]);
     /// assert_eq!(a.is_proper_subset(&b), true);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_proper_subset(&self, other: &Self) -> bool {
         self != other && self.is_subset(other)
@@ -1271,7 +1545,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let b = Multiset::from([1, 1, 0, 0]);
     /// assert_eq!(a.is_proper_superset(&b), true);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_proper_superset(&self, other: &Self) -> bool {
         self != other && self.is_superset(other)
@@ -1293,10 +1567,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let b = Multiset::from([1, 3, 0, 0]);
     /// assert_eq!(a.is_any_lesser(&b), true);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_any_lesser(&self, other: &Self) -> bool {
-        self.iter().zip(other.iter()).any(|(a, b)| a < b)
+        kernels::is_any_lesser(&self.data, &other.data)
     }
 
     /// Returns `true` if any element count in `self` is greater than that
@@ -1316,10 +1590,10 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let b = Multiset::from([1, 1, 4, 0]);
     /// assert_eq!(a.is_any_greater(&b), true);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn is_any_greater(&self, other: &Self) -> bool {
-        self.iter().zip(other.iter()).any(|(a, b)| a > b)
+        kernels::is_any_greater(&self.data, &other.data)
     }
 
     /// Returns `true` if all elements have a count of zero.
@@ -1342,6 +1616,15 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// counts.
     ///
     /// This function converts counts to `usize` to try and avoid overflows.
+    /// For large `u64` or `u128` counts use [`total_u128`] or
+    /// [`checked_total`], which accumulate in a wider type.
+    ///
+    /// [`total_u128`]: Multiset::total_u128
+    /// [`checked_total`]: Multiset::checked_total
+    ///
+    /// # Panics
+    /// If the counter type is wider than `usize`, such as `u128`, and the total
+    /// does not fit in a `usize`.
     ///
     /// # Examples
     ///
@@ -1351,12 +1634,50 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([1u8, 2, 3, 4]);
     /// assert_eq!(multiset.total(), 10);
     /// ```
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn total(&self) -> usize {
-        self.iter()
-            .map(|e| <N as AsPrimitive<usize>>::as_(*e))
-            .sum()
+        kernels::total(&self.data)
+    }
+
+    /// The total of the multiset, accumulated as `u128`.
+    ///
+    /// This cannot overflow for any counter type narrower than `u128`. With
+    /// `u128` counters the result saturates at `u128::MAX`, see
+    /// [`checked_total`] to detect this.
+    ///
+    /// [`checked_total`]: Multiset::checked_total
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([u64::MAX, u64::MAX]);
+    /// assert_eq!(multiset.total_u128(), 2 * u64::MAX as u128);
+    /// ```
+    #[inline]
+    pub fn total_u128(&self) -> u128 {
+        MultisetOps::total_u128(&self.data[..])
+    }
+
+    /// The total of the multiset, accumulated as `u128`, or
+    /// [`Error::Overflow`] naming the element at which the total overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let multiset = Multiset::from([u64::MAX, u64::MAX]);
+    /// assert_eq!(multiset.checked_total(), Ok(2 * u64::MAX as u128));
+    ///
+    /// let multiset = Multiset::from([1, 2, u128::MAX]);
+    /// assert_eq!(multiset.checked_total(), Err(Error::Overflow { element: 2 }));
+    /// ```
+    #[inline]
+    pub fn checked_total(&self) -> core::result::Result<u128, Error> {
+        MultisetOps::checked_total(&self.data[..])
     }
 
     /// Returns a tuple containing the element and a reference to the largest
@@ -1370,6 +1691,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([2u16, 0, 5, 3]);
     /// assert_eq!(multiset.elem_count_max(), (2, &5));
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn elem_count_max(&self) -> (usize, &N) {
         // iter cannot be empty, so it's fine to unwrap
@@ -1389,6 +1711,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([2u8, 0, 5, 3]);
     /// assert_eq!(multiset.elem_max(), 2);
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn elem_max(&self) -> usize {
         self.elem_count_max().0
@@ -1404,6 +1727,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([2u8, 0, 5, 3]);
     /// assert_eq!(multiset.count_max(), &5);
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn count_max(&self) -> &N {
         // iter cannot be empty, so it's fine to unwrap
@@ -1421,6 +1745,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([2u8, 0, 5, 3]);
     /// assert_eq!(multiset.elem_count_min(), (1, &0));
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn elem_count_min(&self) -> (usize, &N) {
         // iter cannot be empty, so it's fine to unwrap
@@ -1440,6 +1765,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([2u8, 0, 5, 3]);
     /// assert_eq!(multiset.elem_min(), 1);
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn elem_min(&self) -> usize {
         self.elem_count_min().0
@@ -1455,6 +1781,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let multiset = Multiset::from([2u8, 0, 5, 3]);
     /// assert_eq!(multiset.count_min(), &0);
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn count_min(&self) -> &N {
         // iter cannot be empty, so it's fine to unwrap
@@ -1473,6 +1800,7 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// let result = Multiset::from([0, 0, 5, 0]);
     /// assert_eq!(multiset, result);
     /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn choose(&mut self, elem: usize) {
         let mut res = [N::zero(); SIZE];
@@ -1500,18 +1828,18 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// assert_eq!(multiset.is_singleton(), true);
     /// ```
     #[cfg(feature = "rand")]
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
     #[inline]
     pub fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
-        let total = self.total();
+        let total = self.total_u128();
         if total == 0 {
             return;
         }
         let choice_value = rng.gen_range(1..=total);
         let mut res = [N::zero(); SIZE];
-        let mut acc = 0;
+        let mut acc = 0u128;
         for (i, elem) in self.iter().enumerate() {
-            acc += <N as AsPrimitive<usize>>::as_(*elem);
+            acc = acc.saturating_add(<N as AsPrimitive<u128>>::as_(*elem));
             if acc >= choice_value {
                 // Safety: `i` cannot be outside of `res`.
                 unsafe { *res.get_unchecked_mut(i) = *elem }
@@ -1534,22 +1862,17 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// ```
     ///
     /// # Warning
-    /// Should not be used if [`Multiset::total`] or any counter in the
-    /// multiset cannot be converted to `f64`. The conversions are handled by
+    /// The total is accumulated with [`Multiset::total_u128`], so it cannot
+    /// overflow, but it and the counts are converted to `f64` and so lose
+    /// precision above 2^53. The conversions are handled by
     /// [`AsPrimitive<f64>`].
     ///
     /// [`AsPrimitive<f64>`]: num_traits::AsPrimitive
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
     #[inline]
     pub fn collision_entropy(&self) -> f64 {
-        let total: f64 = self.total().as_();
-        -self
-            .into_iter()
-            .fold(0.0, |acc, count| {
-                let freq_f64: f64 = count.as_();
-                acc + (freq_f64 / total).powf(2.0)
-            })
-            .log2()
+        kernels::collision_entropy(&self.data, self.total_u128().as_())
     }
 
     /// Calculate the shannon entropy of the multiset. Uses ln rather than log2.
@@ -1565,59 +1888,1493 @@ impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
     /// ```
     ///
     /// # Warning
-    /// Should not be used if [`Multiset::total`] or any counter in the
-    /// multiset cannot be converted to `f64`. The conversions are handled by
+    /// The total is accumulated with [`Multiset::total_u128`], so it cannot
+    /// overflow, but it and the counts are converted to `f64` and so lose
+    /// precision above 2^53. The conversions are handled by
     /// [`AsPrimitive<f64>`].
     ///
     /// [`AsPrimitive<f64>`]: num_traits::AsPrimitive
-    #[cfg(not(feature = "simd"))]
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
     #[inline]
     pub fn shannon_entropy(&self) -> f64 {
-        let total: f64 = self.total().as_();
-        -self.into_iter().fold(0.0, |acc, count| {
-            if count > &N::zero() {
-                let freq_f64: f64 = count.as_();
-                let prob = freq_f64 / total;
-                acc + prob * prob.ln()
-            } else {
-                acc
-            }
-        })
+        kernels::shannon_entropy(&self.data, self.total_u128().as_())
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use approx::assert_relative_eq;
-    #[cfg(feature = "rand")]
-    use rand::prelude::*;
-    use std::panic;
 
-    fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(
-        f: F,
-    ) -> std::thread::Result<R> {
-        let prev_hook = panic::take_hook();
-        panic::set_hook(Box::new(|_| {}));
-        let result = panic::catch_unwind(f);
-        panic::set_hook(prev_hook);
-        result
+    /// Calculate the Rényi entropy of order `alpha` of the multiset, with
+    /// logarithms in the given base.
+    ///
+    /// The order weights the elements by their counts. An order of 0 gives
+    /// the Hartley entropy, the logarithm of the number of elements with a
+    /// non-zero count, and the entropy tends to the
+    /// [`min_entropy`](Multiset::min_entropy) as the order tends to infinity,
+    /// which may be given as [`f64::INFINITY`]. Orders 1 and 2 are the
+    /// [`shannon_entropy`](Multiset::shannon_entropy) and the
+    /// [`collision_entropy`](Multiset::collision_entropy), with identical
+    /// results in their own bases. The entropy of an empty multiset is zero,
+    /// whereas its collision entropy is NaN.
+    ///
+    /// # Panics
+    /// If `alpha` is negative or NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{LogBase, Multiset};
+    ///
+    /// let multiset = Multiset::from([2u8, 1, 1, 0]);
+    /// assert!((multiset.renyi_entropy(0.0, LogBase::E) - 3f64.ln()).abs() < 1e-12);
+    /// assert_eq!(multiset.renyi_entropy(1.0, LogBase::E), multiset.shannon_entropy());
+    /// assert_eq!(multiset.renyi_entropy(2.0, LogBase::Two), multiset.collision_entropy());
+    /// assert_eq!(multiset.renyi_entropy(f64::INFINITY, LogBase::Two), 1.0);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn renyi_entropy(&self, alpha: f64, base: LogBase) -> f64 {
+        entropy::check_order(alpha);
+        if self.is_empty() {
+            0.0
+        } else if alpha == 1.0 {
+            base.convert_nats(self.shannon_entropy())
+        } else if alpha == 2.0 {
+            base.convert_bits(self.collision_entropy())
+        } else if alpha == 0.0 {
+            base.log(self.count_non_zero() as f64)
+        } else if alpha == f64::INFINITY {
+            let max: f64 = self.count_max().as_();
+            -base.log(max / self.total_u128() as f64)
+        } else {
+            base.log(kernels::power_sum(
+                &self.data,
+                self.total_u128().as_(),
+                alpha,
+            )) / (1.0 - alpha)
+        }
     }
 
-    #[test]
-    fn test_index() {
-        let set = Multiset::from([1u8, 2, 3, 4]);
-        assert_eq!(set.index(1), &2);
-        assert_eq!(set.index(2..), &[3, 4]);
+    /// Calculate the min-entropy of the multiset in bits: the negative
+    /// logarithm of the largest probability. This is the Rényi entropy of
+    /// infinite order, and is never greater than the
+    /// [`renyi_entropy`](Multiset::renyi_entropy) of any other order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([2u8, 1, 1, 0]);
+    /// assert_eq!(multiset.min_entropy(), 1.0);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn min_entropy(&self) -> f64 {
+        self.renyi_entropy(f64::INFINITY, LogBase::Two)
     }
 
-    #[test]
-    fn test_index_mut() {
-        let mut set = Multiset::from([1u8, 2, 3, 4]);
-        *set.index_mut(1) = 3;
-        assert_eq!(set, Multiset::from([1u8, 3, 3, 4]));
-        set.index_mut(2..).iter_mut().for_each(|e| *e = 5);
-        assert_eq!(set, Multiset::from([1u8, 3, 5, 5]))
+    /// Calculate the Hill number of order `q` of the multiset, the effective
+    /// number of elements. This is the number of equally common elements
+    /// which would give the same [`renyi_entropy`](Multiset::renyi_entropy)
+    /// of order `q`, and so does not depend on the base of the logarithm.
+    ///
+    /// An order of 0 counts the elements with a non-zero count, order 1 is
+    /// the exponential of the [`shannon_entropy`](Multiset::shannon_entropy),
+    /// order 2 the inverse Simpson index and infinite order the inverse of the
+    /// largest probability. The Hill number of an empty multiset is zero.
+    ///
+    /// # Panics
+    /// If `q` is negative or NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([2u8, 1, 1, 0]);
+    /// assert_eq!(multiset.hill_number(0.0), 3.0);
+    /// assert_eq!(multiset.hill_number(f64::INFINITY), 2.0);
+    /// assert!((multiset.hill_number(2.0) - 8.0 / 3.0).abs() < 1e-12);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn hill_number(&self, q: f64) -> f64 {
+        entropy::check_order(q);
+        if self.is_empty() {
+            0.0
+        } else if q == 0.0 {
+            self.count_non_zero() as f64
+        } else if q == 1.0 {
+            math::exp(self.shannon_entropy())
+        } else if q == f64::INFINITY {
+            let max: f64 = self.count_max().as_();
+            self.total_u128() as f64 / max
+        } else {
+            let sum = kernels::power_sum(&self.data, self.total_u128().as_(), q);
+            math::powf(sum, 1.0 / (1.0 - q))
+        }
+    }
+
+    /// Estimate the entropy, in nats, of the distribution this multiset was
+    /// sampled from, with the given [`EntropyEstimator`].
+    ///
+    /// The [`shannon_entropy`](Multiset::shannon_entropy) is the plug-in
+    /// estimate, which is biased downwards when the total is small relative
+    /// to the number of elements. The other estimators correct this bias.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{EntropyEstimator, Multiset};
+    ///
+    /// let multiset = Multiset::from([4u8, 2, 3, 0, 2, 4, 0, 0, 2, 1, 1]);
+    /// let plug_in = multiset.entropy_with(EntropyEstimator::PlugIn);
+    /// assert_eq!(plug_in, multiset.shannon_entropy());
+    /// let miller_madow = multiset.entropy_with(EntropyEstimator::MillerMadow);
+    /// assert!((miller_madow - plug_in - 7.0 / 38.0).abs() < 1e-12);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn entropy_with(&self, estimator: EntropyEstimator) -> f64 {
+        let total = self.total_u128();
+        match estimator {
+            EntropyEstimator::PlugIn => self.shannon_entropy(),
+            EntropyEstimator::MillerMadow => {
+                entropy::miller_madow(self.shannon_entropy(), self.count_non_zero(), total)
+            }
+            EntropyEstimator::ChaoShen => entropy::chao_shen(&self.data, total),
+            EntropyEstimator::Grassberger => entropy::grassberger(&self.data, total),
+            EntropyEstimator::JamesStein => entropy::james_stein(&self.data, total),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Distances
+////////////////////////////////////////////////////////////////////////////////
+
+// With the simd features these methods are implemented in the simd module.
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// The l1, or Manhattan, distance between two multisets: the sum of the
+    /// absolute differences of the counts. This is the total of the
+    /// [`union`](Multiset::union) less the total of the
+    /// [`intersection`](Multiset::intersection).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 0]);
+    /// let b = Multiset::from([0u8, 1, 3, 0]);
+    /// assert_eq!(a.l1_distance(&b), 5.0);
+    /// ```
+    #[inline]
+    pub fn l1_distance(&self, other: &Self) -> f64 {
+        (self.union(other).total_u128() - self.intersection(other).total_u128()) as f64
+    }
+
+    /// The l2, or Euclidean, distance between two multisets: the square root
+    /// of the sum of the squared differences of the counts.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 0]);
+    /// let b = Multiset::from([0u8, 1, 3, 0]);
+    /// assert_eq!(a.l2_distance(&b), 11f64.sqrt());
+    /// ```
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn l2_distance(&self, other: &Self) -> f64 {
+        let diff = self.abs_difference(other);
+        math::sqrt(kernels::dot(&diff.data, &diff.data))
+    }
+
+    /// The l∞, or Chebyshev, distance between two multisets: the largest
+    /// absolute difference of the counts. This is zero for multisets with no
+    /// elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 0]);
+    /// let b = Multiset::from([0u8, 1, 3, 0]);
+    /// assert_eq!(a.linf_distance(&b), 3.0);
+    /// ```
+    #[inline]
+    pub fn linf_distance(&self, other: &Self) -> f64 {
+        if SIZE == 0 {
+            0.0
+        } else {
+            self.abs_difference(other).count_max().as_()
+        }
+    }
+
+    /// The generalised Jaccard index of two multisets: the total of their
+    /// [`intersection`](Multiset::intersection) divided by the total of their
+    /// [`union`](Multiset::union).
+    ///
+    /// The index is between 0 and 1, and is 1 for identical multisets,
+    /// including two empty ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 0]);
+    /// let b = Multiset::from([0u8, 1, 3, 0]);
+    /// assert_eq!(a.jaccard_index(&b), 1.0 / 6.0);
+    /// assert_eq!(Multiset::<u8, 4>::empty().jaccard_index(&Multiset::empty()), 1.0);
+    /// ```
+    #[inline]
+    pub fn jaccard_index(&self, other: &Self) -> f64 {
+        distance::jaccard_index(
+            self.intersection(other).total_u128(),
+            self.union(other).total_u128(),
+        )
+    }
+
+    /// The cosine similarity of two multisets, treating their counts as
+    /// vectors.
+    ///
+    /// The similarity is between 0 and 1. It is 1 for two empty multisets,
+    /// and 0 when only one of the multisets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 0]);
+    /// let b = Multiset::from([2u8, 4, 0, 0]);
+    /// let c = Multiset::from([0u8, 0, 3, 0]);
+    /// assert!((a.cosine_similarity(&b) - 1.0).abs() < 1e-12);
+    /// assert_eq!(a.cosine_similarity(&c), 0.0);
+    /// ```
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn cosine_similarity(&self, other: &Self) -> f64 {
+        distance::cosine_similarity(
+            kernels::dot(&self.data, &other.data),
+            kernels::dot(&self.data, &self.data),
+            kernels::dot(&other.data, &other.data),
+        )
+    }
+
+    /// The Bray–Curtis dissimilarity of two multisets: their
+    /// [`l1_distance`](Multiset::l1_distance) divided by the sum of their
+    /// totals.
+    ///
+    /// The dissimilarity is between 0 and 1, and is 0 for identical
+    /// multisets, including two empty ones.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 0]);
+    /// let b = Multiset::from([0u8, 1, 3, 0]);
+    /// assert_eq!(a.bray_curtis_dissimilarity(&b), 5.0 / 7.0);
+    /// ```
+    #[inline]
+    pub fn bray_curtis_dissimilarity(&self, other: &Self) -> f64 {
+        distance::bray_curtis(
+            self.union(other).total_u128() - self.intersection(other).total_u128(),
+            self.total_u128(),
+            other.total_u128(),
+        )
+    }
+
+    /// The Hellinger distance between two multisets, normalised to probability
+    /// distributions by their totals.
+    ///
+    /// The distance is between 0 and 1. It is 0 for two empty multisets, and 1
+    /// when only one of the multisets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 0]);
+    /// let b = Multiset::from([2u8, 4, 0, 0]);
+    /// let c = Multiset::from([0u8, 0, 3, 0]);
+    /// assert!(a.hellinger_distance(&b) < 1e-7);
+    /// assert_eq!(a.hellinger_distance(&c), 1.0);
+    /// ```
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn hellinger_distance(&self, other: &Self) -> f64 {
+        distance::hellinger_distance(
+            kernels::sqrt_dot(&self.data, &other.data),
+            self.total_u128(),
+            other.total_u128(),
+        )
+    }
+
+    // The absolute differences of the counts. The union is never less than the
+    // intersection, so the subtraction cannot wrap.
+    #[inline]
+    fn abs_difference(&self, other: &Self) -> Self {
+        self.union(other).wrapping_sub(&self.intersection(other))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Divergences
+////////////////////////////////////////////////////////////////////////////////
+
+// With the simd features these methods are implemented in the simd module.
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+#[cfg(any(feature = "std", feature = "libm"))]
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// The Kullback–Leibler divergence, in nats, of the distribution of this
+    /// multiset from the distribution of `other`, where each distribution is
+    /// the counts divided by the total.
+    ///
+    /// The divergence is infinite when an element occurs in this multiset but
+    /// not in `other`. Use
+    /// [`kl_divergence_with_pseudocount`](Multiset::kl_divergence_with_pseudocount)
+    /// to smooth the distributions instead. An empty multiset has a divergence
+    /// of 0 from any multiset, and any other multiset an infinite divergence
+    /// from an empty one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([2u8, 1, 1, 0]);
+    /// let b = Multiset::from([1u8, 1, 1, 1]);
+    /// assert!((a.kl_divergence(&b) - 0.5 * 2f64.ln()).abs() < 1e-12);
+    /// assert_eq!(b.kl_divergence(&a), f64::INFINITY);
+    /// ```
+    #[inline]
+    pub fn kl_divergence(&self, other: &Self) -> f64 {
+        self.kl_divergence_with_pseudocount(other, 0.0)
+    }
+
+    /// The Kullback–Leibler divergence, in nats, after adding `pseudocount`
+    /// to every count of both multisets. Any positive pseudocount makes the
+    /// divergence finite.
+    ///
+    /// # Panics
+    /// If `pseudocount` is negative or NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([2u8, 1, 1, 0]);
+    /// let b = Multiset::from([1u8, 1, 1, 1]);
+    /// let result = b.kl_divergence_with_pseudocount(&a, 1.0);
+    /// assert!((result - 0.25 * (4f64 / 3.0).ln()).abs() < 1e-12);
+    /// ```
+    #[inline]
+    pub fn kl_divergence_with_pseudocount(&self, other: &Self, pseudocount: f64) -> f64 {
+        let (total_a, total_b) =
+            distance::smoothed_totals(self.total_u128(), other.total_u128(), SIZE, pseudocount);
+        distance::kl_divergence(total_a, total_b, || {
+            kernels::kl_divergence(&self.data, &other.data, total_a, total_b, pseudocount)
+        })
+    }
+
+    /// The cross entropy, in nats, of the distribution of `other` relative to
+    /// the distribution of this multiset. This is the
+    /// [`shannon_entropy`](Multiset::shannon_entropy) of this multiset plus its
+    /// [`kl_divergence`](Multiset::kl_divergence) from `other`.
+    ///
+    /// The cross entropy is infinite when an element occurs in this multiset
+    /// but not in `other`. Use
+    /// [`cross_entropy_with_pseudocount`](Multiset::cross_entropy_with_pseudocount)
+    /// to smooth the distributions instead. The cross entropy of an empty
+    /// multiset is 0, and of any other multiset relative to an empty one is
+    /// infinite.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([2u8, 1, 1, 0]);
+    /// let b = Multiset::from([1u8, 1, 1, 1]);
+    /// assert!((a.cross_entropy(&b) - 4f64.ln()).abs() < 1e-12);
+    /// assert_eq!(b.cross_entropy(&a), f64::INFINITY);
+    /// ```
+    #[inline]
+    pub fn cross_entropy(&self, other: &Self) -> f64 {
+        self.cross_entropy_with_pseudocount(other, 0.0)
+    }
+
+    /// The cross entropy, in nats, after adding `pseudocount` to every count
+    /// of both multisets. Any positive pseudocount makes the cross entropy
+    /// finite.
+    ///
+    /// # Panics
+    /// If `pseudocount` is negative or NaN.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([2u8, 1, 1, 0]);
+    /// let b = Multiset::from([1u8, 1, 1, 1]);
+    /// assert!(b.cross_entropy_with_pseudocount(&a, 1.0).is_finite());
+    /// ```
+    #[inline]
+    pub fn cross_entropy_with_pseudocount(&self, other: &Self, pseudocount: f64) -> f64 {
+        let (total_a, total_b) =
+            distance::smoothed_totals(self.total_u128(), other.total_u128(), SIZE, pseudocount);
+        distance::cross_entropy(total_a, total_b, || {
+            kernels::cross_entropy(&self.data, &other.data, total_a, total_b, pseudocount)
+        })
+    }
+
+    /// The Jensen–Shannon divergence, in nats, between the distributions of
+    /// two multisets: the mean of their Kullback–Leibler divergences from the
+    /// average of the distributions.
+    ///
+    /// Unlike [`kl_divergence`](Multiset::kl_divergence) this is symmetric and
+    /// always finite, as the average distribution covers the support of both
+    /// multisets. It is between 0 and ln(2), and is ln(2) when only one of the
+    /// multisets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 0, 0, 0]);
+    /// let b = Multiset::from([0u8, 3, 0, 0]);
+    /// assert!((a.jensen_shannon_divergence(&b) - 2f64.ln()).abs() < 1e-12);
+    /// assert_eq!(a.jensen_shannon_divergence(&a), 0.0);
+    /// ```
+    #[inline]
+    pub fn jensen_shannon_divergence(&self, other: &Self) -> f64 {
+        let (total_a, total_b) = (self.total_u128() as f64, other.total_u128() as f64);
+        distance::jensen_shannon_divergence(total_a, total_b, || {
+            kernels::jensen_shannon_divergence(&self.data, &other.data, total_a, total_b, 0.0)
+        })
+    }
+
+    /// The total variation distance between the distributions of two
+    /// multisets: half the sum of the absolute differences of the
+    /// probabilities.
+    ///
+    /// The distance is always finite, between 0 and 1, and is 1 when only one
+    /// of the multisets is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([2u8, 1, 1, 0]);
+    /// let b = Multiset::from([1u8, 1, 1, 1]);
+    /// assert_eq!(a.total_variation(&b), 0.25);
+    /// ```
+    #[inline]
+    pub fn total_variation(&self, other: &Self) -> f64 {
+        let (total_a, total_b) = (self.total_u128() as f64, other.total_u128() as f64);
+        distance::total_variation(total_a, total_b, || {
+            kernels::total_variation(&self.data, &other.data, total_a, total_b, 0.0)
+        })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Counter conversions
+////////////////////////////////////////////////////////////////////////////////
+
+// With the simd features these methods are implemented in the simd module.
+// Otherwise they use the conversion kernels, which widen and narrow counters
+// with simd on x86 with the `stable_simd` feature.
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// Converts every count to the counter type `N2`, which must be able to
+    /// represent every value of `N` without loss.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([1u8, 255, 0]);
+    /// let wide: Multiset<u64, 3> = multiset.cast();
+    /// assert_eq!(wide, Multiset::from([1u64, 255, 0]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn cast<N2>(&self) -> Multiset<N2, SIZE>
+    where
+        N2: Counter + From<N>,
+    {
+        let mut res = Multiset::empty();
+        kernels::cast_counts(&self.data, &mut res.data);
+        res
+    }
+
+    /// Converts every count to the counter type `N2`, or returns
+    /// [`Error::Overflow`] naming the first element whose count does not fit
+    /// in `N2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let multiset = Multiset::from([1u32, 255, 0]);
+    /// assert_eq!(multiset.try_cast::<u8>(), Ok(Multiset::from([1u8, 255, 0])));
+    ///
+    /// let multiset = Multiset::from([1u32, 256, 0]);
+    /// assert_eq!(multiset.try_cast::<u8>(), Err(Error::Overflow { element: 1 }));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn try_cast<N2>(&self) -> core::result::Result<Multiset<N2, SIZE>, Error>
+    where
+        N2: Counter + TryFrom<N>,
+    {
+        let mut res = Multiset::empty();
+        kernels::try_cast_counts(&self.data, &mut res.data)?;
+        Ok(res)
+    }
+
+    /// Converts every count to the counter type `N2`, clamping counts which
+    /// do not fit to the maximum value of `N2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([1u32, 256, 0]);
+    /// assert_eq!(multiset.saturating_cast::<u8>(), Multiset::from([1u8, 255, 0]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn saturating_cast<N2>(&self) -> Multiset<N2, SIZE>
+    where
+        N2: Counter + TryFrom<N>,
+    {
+        let mut res = Multiset::empty();
+        kernels::saturating_cast_counts(&self.data, &mut res.data);
+        res
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Functional transforms
+////////////////////////////////////////////////////////////////////////////////
+
+// These work on the fixed size arrays directly, so the compiler can usually
+// vectorise them when the closure is simple arithmetic or comparisons.
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// Returns a multiset with `f` applied to every count. The counter type of
+    /// the result can differ from `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([1u8, 2, 0, 4]);
+    /// assert_eq!(multiset.map(|count| count * 2), Multiset::from([2, 4, 0, 8]));
+    ///
+    /// let wide: Multiset<u32, 4> = multiset.map(|count| count as u32 * 1000);
+    /// assert_eq!(wide, Multiset::from([1000, 2000, 0, 4000]));
+    /// ```
+    #[inline]
+    pub fn map<N2, F>(&self, mut f: F) -> Multiset<N2, SIZE>
+    where
+        N2: Counter,
+        F: FnMut(N) -> N2,
+    {
+        let mut res = Multiset::empty();
+        res.iter_mut()
+            .zip(self.iter())
+            .for_each(|(r, a)| *r = f(*a));
+        res
+    }
+
+    /// Returns a multiset with `f` applied to each pair of counts of the same
+    /// element in `self` and `other`. The counter types of `other` and of the
+    /// result can differ from `N`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([1u8, 2, 0, 4]);
+    /// let b = Multiset::from([3u8, 1, 1, 4]);
+    /// let res = a.zip_with(&b, |a, b| if a > b { a - b } else { b - a });
+    /// assert_eq!(res, Multiset::from([2, 1, 1, 0]));
+    ///
+    /// let wide: Multiset<u16, 4> = a.zip_with(&b, |a, b| a as u16 * b as u16);
+    /// assert_eq!(wide, Multiset::from([3, 2, 0, 16]));
+    /// ```
+    #[inline]
+    pub fn zip_with<N2, N3, F>(&self, other: &Multiset<N2, SIZE>, mut f: F) -> Multiset<N3, SIZE>
+    where
+        N2: Counter,
+        N3: Counter,
+        F: FnMut(N, N2) -> N3,
+    {
+        let mut res = Multiset::empty();
+        res.iter_mut()
+            .zip(self.iter().zip(other.iter()))
+            .for_each(|(r, (a, b))| *r = f(*a, *b));
+        res
+    }
+
+    /// Keeps only the counts for which `f(elem, count)` returns `true`,
+    /// setting the others to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([1u8, 5, 0, 4, 2]);
+    /// multiset.retain(|elem, count| elem != 3 && count > 1);
+    /// assert_eq!(multiset, Multiset::from([0, 5, 0, 0, 2]));
+    /// ```
+    #[inline]
+    pub fn retain<F>(&mut self, mut f: F)
+    where
+        F: FnMut(usize, N) -> bool,
+    {
+        self.iter_mut().enumerate().for_each(|(elem, count)| {
+            if !f(elem, *count) {
+                *count = N::zero();
+            }
+        });
+    }
+
+    /// Returns a multiset with the counts of the elements where `mask` is
+    /// `true`, and zero elsewhere.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([1u8, 5, 0, 4]);
+    /// let res = multiset.filter_elems(&[true, false, true, true]);
+    /// assert_eq!(res, Multiset::from([1, 0, 0, 4]));
+    /// ```
+    #[inline]
+    pub fn filter_elems(&self, mask: &[bool; SIZE]) -> Self {
+        let mut res = *self;
+        res.iter_mut().zip(mask.iter()).for_each(|(count, keep)| {
+            *count = if *keep { *count } else { N::zero() };
+        });
+        res
+    }
+
+    /// Folds every `(element, count)` pair into an accumulator, in element
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let multiset = Multiset::from([1u8, 5, 0, 4]);
+    /// // The sum of the elements, each repeated by its count.
+    /// let sum = multiset.fold_counts(0, |acc, elem, count| acc + elem * count as usize);
+    /// assert_eq!(sum, 17);
+    /// ```
+    #[inline]
+    pub fn fold_counts<B, F>(&self, init: B, mut f: F) -> B
+    where
+        F: FnMut(B, usize, N) -> B,
+    {
+        self.iter()
+            .enumerate()
+            .fold(init, |acc, (elem, count)| f(acc, elem, *count))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Counting
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// Adds `n` to the count of `elem`, returning the previous count.
+    ///
+    /// Like the arithmetic operators of the counter type this panics rather
+    /// than wrapping. [`checked_add_count`] and [`saturating_add_count`] are
+    /// the non-panicking alternatives.
+    ///
+    /// # Panics
+    /// If `elem` is out of bounds of the Multiset, or if the count would
+    /// overflow.
+    ///
+    /// [`checked_add_count`]: Multiset::checked_add_count
+    /// [`saturating_add_count`]: Multiset::saturating_add_count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.add_count(1, 3), 2);
+    /// assert_eq!(multiset.get(1), Some(&5));
+    /// ```
+    #[inline]
+    pub fn add_count(&mut self, elem: usize, n: N) -> N {
+        self.checked_add_count(elem, n)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Subtracts `n` from the count of `elem`, returning the previous count.
+    ///
+    /// Like the arithmetic operators of the counter type this panics rather
+    /// than wrapping. [`checked_sub_count`] and [`saturating_sub_count`] are
+    /// the non-panicking alternatives.
+    ///
+    /// # Panics
+    /// If `elem` is out of bounds of the Multiset, or if `n` is larger than
+    /// the count.
+    ///
+    /// [`checked_sub_count`]: Multiset::checked_sub_count
+    /// [`saturating_sub_count`]: Multiset::saturating_sub_count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.sub_count(1, 2), 2);
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// ```
+    #[inline]
+    pub fn sub_count(&mut self, elem: usize, n: N) -> N {
+        self.checked_sub_count(elem, n)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Increments the count of `elem` by one, returning the previous count.
+    ///
+    /// See [`add_count`](Multiset::add_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.increment(2), 0);
+    /// assert_eq!(multiset.get(2), Some(&1));
+    /// ```
+    #[inline]
+    pub fn increment(&mut self, elem: usize) -> N {
+        self.add_count(elem, N::one())
+    }
+
+    /// Decrements the count of `elem` by one, returning the previous count.
+    ///
+    /// See [`sub_count`](Multiset::sub_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.decrement(1), 2);
+    /// assert_eq!(multiset.get(1), Some(&1));
+    /// ```
+    #[inline]
+    pub fn decrement(&mut self, elem: usize) -> N {
+        self.sub_count(elem, N::one())
+    }
+
+    /// Increments the count of each element yielded by `elements`. This is
+    /// what the [`Extend`] implementation calls.
+    ///
+    /// # Panics
+    /// If an element is out of bounds of the Multiset, or if a count would
+    /// overflow. Elements which were counted before the panic are not rolled
+    /// back. [`checked_extend_elements`] and [`saturating_extend_elements`]
+    /// are the non-panicking alternatives.
+    ///
+    /// [`checked_extend_elements`]: Multiset::checked_extend_elements
+    /// [`saturating_extend_elements`]: Multiset::saturating_extend_elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([0u8, 0, 0, 0]);
+    /// multiset.extend_elements(vec![1, 1, 3]);
+    /// assert_eq!(multiset, Multiset::from([0, 2, 0, 1]));
+    /// ```
+    #[inline]
+    pub fn extend_elements<I>(&mut self, elements: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        if let Err(e) = self.checked_extend_elements(elements) {
+            panic!("{}", e)
+        }
+    }
+
+    /// Checked addition of `n` to the count of `elem`, returning the previous
+    /// count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// Multiset, or [`Error::Overflow`] if the count would overflow, in which
+    /// case the count is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 255]);
+    /// assert_eq!(multiset.checked_add_count(1, 3), Ok(2));
+    /// assert_eq!(multiset.get(1), Some(&5));
+    /// assert_eq!(multiset.checked_add_count(3, 1), Err(Error::Overflow { element: 3 }));
+    /// assert_eq!(multiset.get(3), Some(&255));
+    /// ```
+    #[inline]
+    pub fn checked_add_count(&mut self, elem: usize, n: N) -> core::result::Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev
+            .checked_add(&n)
+            .ok_or(Error::Overflow { element: elem })?;
+        Ok(prev)
+    }
+
+    /// Checked subtraction of `n` from the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// Multiset, or [`Error::Overflow`] if `n` is larger than the count, in
+    /// which case the count is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.checked_sub_count(1, 2), Ok(2));
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// assert_eq!(multiset.checked_sub_count(0, 2), Err(Error::Overflow { element: 0 }));
+    /// assert_eq!(multiset.get(0), Some(&1));
+    /// ```
+    #[inline]
+    pub fn checked_sub_count(&mut self, elem: usize, n: N) -> core::result::Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev
+            .checked_sub(&n)
+            .ok_or(Error::Overflow { element: elem })?;
+        Ok(prev)
+    }
+
+    /// Checked increment of the count of `elem` by one, returning the previous
+    /// count.
+    ///
+    /// See [`checked_add_count`](Multiset::checked_add_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.checked_increment(2), Ok(0));
+    /// assert_eq!(multiset.get(2), Some(&1));
+    /// ```
+    #[inline]
+    pub fn checked_increment(&mut self, elem: usize) -> core::result::Result<N, Error> {
+        self.checked_add_count(elem, N::one())
+    }
+
+    /// Checked decrement of the count of `elem` by one, returning the previous
+    /// count.
+    ///
+    /// See [`checked_sub_count`](Multiset::checked_sub_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.checked_decrement(1), Ok(2));
+    /// assert_eq!(multiset.checked_decrement(2), Err(Error::Overflow { element: 2 }));
+    /// ```
+    #[inline]
+    pub fn checked_decrement(&mut self, elem: usize) -> core::result::Result<N, Error> {
+        self.checked_sub_count(elem, N::one())
+    }
+
+    /// Saturating addition of `n` to the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// Multiset. Otherwise the count is clamped at the maximum value of the
+    /// counter type instead of overflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 250]);
+    /// assert_eq!(multiset.saturating_add_count(3, 10), Ok(250));
+    /// assert_eq!(multiset.get(3), Some(&255));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.saturating_add_count(4, 1), Err(err));
+    /// ```
+    #[inline]
+    pub fn saturating_add_count(&mut self, elem: usize, n: N) -> core::result::Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev.saturating_add(&n);
+        Ok(prev)
+    }
+
+    /// Saturating subtraction of `n` from the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// Multiset. Otherwise the count is clamped at zero instead of
+    /// underflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.saturating_sub_count(1, 5), Ok(2));
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// ```
+    #[inline]
+    pub fn saturating_sub_count(&mut self, elem: usize, n: N) -> core::result::Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev.saturating_sub(&n);
+        Ok(prev)
+    }
+
+    /// Saturating increment of the count of `elem` by one, returning the
+    /// previous count.
+    ///
+    /// See [`saturating_add_count`](Multiset::saturating_add_count) for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 255]);
+    /// assert_eq!(multiset.saturating_increment(3), Ok(255));
+    /// assert_eq!(multiset.get(3), Some(&255));
+    /// ```
+    #[inline]
+    pub fn saturating_increment(&mut self, elem: usize) -> core::result::Result<N, Error> {
+        self.saturating_add_count(elem, N::one())
+    }
+
+    /// Saturating decrement of the count of `elem` by one, returning the
+    /// previous count.
+    ///
+    /// See [`saturating_sub_count`](Multiset::saturating_sub_count) for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.saturating_decrement(2), Ok(0));
+    /// assert_eq!(multiset.get(2), Some(&0));
+    /// ```
+    #[inline]
+    pub fn saturating_decrement(&mut self, elem: usize) -> core::result::Result<N, Error> {
+        self.saturating_sub_count(elem, N::one())
+    }
+
+    /// Increment the count of each element yielded by `elements`, stopping
+    /// at the first error.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if an element is out of bounds of
+    /// the Multiset, or [`Error::Overflow`] if a count would overflow. Elements
+    /// which were counted before the error are not rolled back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let mut multiset = Multiset::from([0u8, 0, 0, 0]);
+    /// assert_eq!(multiset.checked_extend_elements(vec![1, 1, 3]), Ok(()));
+    /// assert_eq!(multiset, Multiset::from([0, 2, 0, 1]));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.checked_extend_elements(vec![0, 4, 0]), Err(err));
+    /// assert_eq!(multiset, Multiset::from([1, 2, 0, 1]));
+    /// ```
+    #[inline]
+    pub fn checked_extend_elements<I>(&mut self, elements: I) -> core::result::Result<(), Error>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for elem in elements {
+            self.checked_increment(elem)?;
+        }
+        Ok(())
+    }
+
+    /// Increment the count of each element yielded by `elements`, clamping
+    /// counts at the maximum value of the counter type.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] at the first element which is out
+    /// of bounds of the Multiset. Elements which were counted before the error
+    /// are not rolled back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let mut multiset = Multiset::from([0u8, 0, 0, 254]);
+    /// assert_eq!(multiset.saturating_extend_elements(vec![1, 3, 3, 3]), Ok(()));
+    /// assert_eq!(multiset, Multiset::from([0, 1, 0, 255]));
+    /// ```
+    #[inline]
+    pub fn saturating_extend_elements<I>(&mut self, elements: I) -> core::result::Result<(), Error>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for elem in elements {
+            self.saturating_increment(elem)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn count_mut(&mut self, elem: usize) -> core::result::Result<&mut N, Error> {
+        let len = SIZE;
+        self.get_mut(elem)
+            .ok_or(Error::ElementOutOfRange { element: elem, len })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Checked, saturating & wrapping arithmetic
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE> {
+    /// Checked elementwise addition. Returns the multiset where each count is
+    /// `self[i] + other[i]`, or [`Error::Overflow`] naming the first element
+    /// whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let a = Multiset::from([1u8, 2]);
+    /// let b = Multiset::from([3, 4]);
+    /// assert_eq!(a.checked_add(&b), Ok(Multiset::from([4, 6])));
+    ///
+    /// let a = Multiset::from([200u8, 1]);
+    /// let b = Multiset::from([100, 1]);
+    /// assert_eq!(a.checked_add(&b), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_add(&self, other: &Self) -> core::result::Result<Self, Error> {
+        self.try_zip_map(other, |a, b| a.checked_add(&b))
+    }
+
+    /// Checked addition of `rhs` to every count. Returns [`Error::Overflow`]
+    /// naming the first element whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let a = Multiset::from([1u8, 2]);
+    /// assert_eq!(a.checked_add_scalar(3), Ok(Multiset::from([4, 5])));
+    ///
+    /// let a = Multiset::from([250u8, 1]);
+    /// assert_eq!(a.checked_add_scalar(10), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_add_scalar(&self, rhs: N) -> core::result::Result<Self, Error> {
+        self.try_zip_map(&Multiset::repeat(rhs), |a, b| a.checked_add(&b))
+    }
+
+    /// Checked elementwise subtraction. Returns the multiset where each count is
+    /// `self[i] - other[i]`, or [`Error::Overflow`] naming the first element
+    /// whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let a = Multiset::from([3u8, 2]);
+    /// let b = Multiset::from([1, 2]);
+    /// assert_eq!(a.checked_sub(&b), Ok(Multiset::from([2, 0])));
+    ///
+    /// let a = Multiset::from([3u8, 1]);
+    /// let b = Multiset::from([1, 2]);
+    /// assert_eq!(a.checked_sub(&b), Err(Error::Overflow { element: 1 }));
+    /// ```
+    #[inline]
+    pub fn checked_sub(&self, other: &Self) -> core::result::Result<Self, Error> {
+        self.try_zip_map(other, |a, b| a.checked_sub(&b))
+    }
+
+    /// Checked subtraction of `rhs` from every count. Returns
+    /// [`Error::Overflow`] naming the first element whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let a = Multiset::from([3u8, 2]);
+    /// assert_eq!(a.checked_sub_scalar(2), Ok(Multiset::from([1, 0])));
+    ///
+    /// let a = Multiset::from([3u8, 1]);
+    /// assert_eq!(a.checked_sub_scalar(2), Err(Error::Overflow { element: 1 }));
+    /// ```
+    #[inline]
+    pub fn checked_sub_scalar(&self, rhs: N) -> core::result::Result<Self, Error> {
+        self.try_zip_map(&Multiset::repeat(rhs), |a, b| a.checked_sub(&b))
+    }
+
+    /// Checked elementwise multiplication. Returns the multiset where each count is
+    /// `self[i] * other[i]`, or [`Error::Overflow`] naming the first element
+    /// whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let a = Multiset::from([2u8, 3]);
+    /// let b = Multiset::from([4, 5]);
+    /// assert_eq!(a.checked_mul(&b), Ok(Multiset::from([8, 15])));
+    ///
+    /// let a = Multiset::from([16u8, 2]);
+    /// let b = Multiset::from([16, 2]);
+    /// assert_eq!(a.checked_mul(&b), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_mul(&self, other: &Self) -> core::result::Result<Self, Error> {
+        self.try_zip_map(other, |a, b| a.checked_mul(&b))
+    }
+
+    /// Checked multiplication of every count by `rhs`. Returns
+    /// [`Error::Overflow`] naming the first element whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, Multiset};
+    ///
+    /// let a = Multiset::from([2u8, 3]);
+    /// assert_eq!(a.checked_mul_scalar(4), Ok(Multiset::from([8, 12])));
+    ///
+    /// let a = Multiset::from([128u8, 1]);
+    /// assert_eq!(a.checked_mul_scalar(2), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_mul_scalar(&self, rhs: N) -> core::result::Result<Self, Error> {
+        self.try_zip_map(&Multiset::repeat(rhs), |a, b| a.checked_mul(&b))
+    }
+
+    /// Saturating elementwise addition. Counts which would overflow are clamped
+    /// to the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([200u8, 1]);
+    /// let b = Multiset::from([100, 1]);
+    /// assert_eq!(a.saturating_add(&b), Multiset::from([255, 2]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn saturating_add(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a.saturating_add(&b))
+    }
+
+    /// Saturating addition of `rhs` to every count. Counts which would
+    /// overflow are clamped to the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([250u8, 1]);
+    /// assert_eq!(a.saturating_add_scalar(10), Multiset::from([255, 11]));
+    /// ```
+    #[inline]
+    pub fn saturating_add_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.saturating_add(&rhs)).collect()
+    }
+
+    /// Saturating elementwise subtraction. Counts which would overflow are clamped
+    /// to the bounds of the counter type instead.
+    ///
+    /// This is truncated subtraction, or monus: counts never go below zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([3u8, 1]);
+    /// let b = Multiset::from([1, 2]);
+    /// assert_eq!(a.saturating_sub(&b), Multiset::from([2, 0]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn saturating_sub(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a.saturating_sub(&b))
+    }
+
+    /// Saturating subtraction of `rhs` from every count. Counts which would
+    /// overflow are clamped to the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([3u8, 1]);
+    /// assert_eq!(a.saturating_sub_scalar(2), Multiset::from([1, 0]));
+    /// ```
+    #[inline]
+    pub fn saturating_sub_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.saturating_sub(&rhs)).collect()
+    }
+
+    /// Saturating elementwise multiplication. Counts which would overflow are clamped
+    /// to the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([16u8, 2]);
+    /// let b = Multiset::from([16, 2]);
+    /// assert_eq!(a.saturating_mul(&b), Multiset::from([255, 4]));
+    /// ```
+    #[inline]
+    pub fn saturating_mul(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a.saturating_mul(&b))
+    }
+
+    /// Saturating multiplication of every count by `rhs`. Counts which would
+    /// overflow are clamped to the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([128u8, 1]);
+    /// assert_eq!(a.saturating_mul_scalar(2), Multiset::from([255, 2]));
+    /// ```
+    #[inline]
+    pub fn saturating_mul_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.saturating_mul(&rhs)).collect()
+    }
+
+    /// Wrapping elementwise addition. Counts which would overflow wrap around
+    /// at the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([200u8, 1]);
+    /// let b = Multiset::from([100, 1]);
+    /// assert_eq!(a.wrapping_add(&b), Multiset::from([44, 2]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn wrapping_add(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a.wrapping_add(&b))
+    }
+
+    /// Wrapping addition of `rhs` to every count. Counts which would
+    /// overflow wrap around at the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([250u8, 1]);
+    /// assert_eq!(a.wrapping_add_scalar(10), Multiset::from([4, 11]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn wrapping_add_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.wrapping_add(&rhs)).collect()
+    }
+
+    /// Wrapping elementwise subtraction. Counts which would overflow wrap around
+    /// at the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([3u8, 1]);
+    /// let b = Multiset::from([1, 2]);
+    /// assert_eq!(a.wrapping_sub(&b), Multiset::from([2, 255]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn wrapping_sub(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a.wrapping_sub(&b))
+    }
+
+    /// Wrapping subtraction of `rhs` from every count. Counts which would
+    /// overflow wrap around at the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([3u8, 1]);
+    /// assert_eq!(a.wrapping_sub_scalar(2), Multiset::from([1, 255]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn wrapping_sub_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.wrapping_sub(&rhs)).collect()
+    }
+
+    /// Wrapping elementwise multiplication. Counts which would overflow wrap around
+    /// at the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([16u8, 2]);
+    /// let b = Multiset::from([16, 2]);
+    /// assert_eq!(a.wrapping_mul(&b), Multiset::from([0, 4]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn wrapping_mul(&self, other: &Self) -> Self {
+        self.zip_with(other, |a, b| a.wrapping_mul(&b))
+    }
+
+    /// Wrapping multiplication of every count by `rhs`. Counts which would
+    /// overflow wrap around at the bounds of the counter type instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::Multiset;
+    ///
+    /// let a = Multiset::from([128u8, 1]);
+    /// assert_eq!(a.wrapping_mul_scalar(2), Multiset::from([0, 2]));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn wrapping_mul_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.wrapping_mul(&rhs)).collect()
+    }
+
+    #[inline]
+    fn try_zip_map<F>(&self, other: &Self, f: F) -> core::result::Result<Self, Error>
+    where
+        F: Fn(N, N) -> Option<N>,
+    {
+        let mut res = Multiset::empty();
+        for (elem, (out, (a, b))) in res
+            .iter_mut()
+            .zip(self.iter().zip(other.iter()))
+            .enumerate()
+        {
+            *out = f(*a, *b).ok_or(Error::Overflow { element: elem })?;
+        }
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use crate::{EntropyEstimator, LogBase};
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use approx::assert_relative_eq;
+    #[cfg(feature = "rand")]
+    use rand::prelude::*;
+    use std::panic;
+
+    fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(
+        f: F,
+    ) -> std::thread::Result<R> {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(f);
+        panic::set_hook(prev_hook);
+        result
+    }
+
+    #[test]
+    fn test_index() {
+        let set = Multiset::from([1u8, 2, 3, 4]);
+        assert_eq!(set.index(1), &2);
+        assert_eq!(set.index(2..), &[3, 4]);
+    }
+
+    #[test]
+    fn test_index_mut() {
+        let mut set = Multiset::from([1u8, 2, 3, 4]);
+        *set.index_mut(1) = 3;
+        assert_eq!(set, Multiset::from([1u8, 3, 3, 4]));
+        set.index_mut(2..).iter_mut().for_each(|e| *e = 5);
+        assert_eq!(set, Multiset::from([1u8, 3, 5, 5]))
     }
 
     #[test]
@@ -1686,12 +3443,208 @@ mod tests {
     }
 
     #[test]
-    fn test_zip_map() {
+    fn test_counting() {
+        let mut set = Multiset::from([1u8, 254, 0, 0]);
+        assert_eq!(set.add_count(0, 4), 1);
+        assert_eq!(set.increment(1), 254);
+        assert_eq!(set.sub_count(0, 2), 5);
+        assert_eq!(set.decrement(0), 3);
+        set.extend_elements(vec![3, 3]);
+        assert_eq!(set, Multiset::from([2, 255, 0, 2]));
+
+        let res = catch_unwind_silent(|| Multiset::from([255u8, 0]).increment(0));
+        assert!(res.is_err());
+        let res = catch_unwind_silent(|| Multiset::from([1u8, 0]).decrement(1));
+        assert!(res.is_err());
+        let res = catch_unwind_silent(|| Multiset::from([1u8, 0]).add_count(2, 1));
+        assert!(res.is_err());
+        let res = catch_unwind_silent(|| Multiset::<u8, 2>::empty().extend_elements(vec![0, 2]));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_checked_counting() {
+        let mut set = Multiset::from([1u8, 254, 0, 0]);
+        assert_eq!(set.checked_add_count(0, 4), Ok(1));
+        assert_eq!(set.checked_increment(1), Ok(254));
+        assert_eq!(
+            set.checked_increment(1),
+            Err(Error::Overflow { element: 1 })
+        );
+        assert_eq!(set.checked_sub_count(0, 5), Ok(5));
+        assert_eq!(
+            set.checked_decrement(0),
+            Err(Error::Overflow { element: 0 })
+        );
+        assert_eq!(set, Multiset::from([0, 255, 0, 0]));
+
+        let err = Error::ElementOutOfRange { element: 4, len: 4 };
+        assert_eq!(set.checked_add_count(4, 1), Err(err));
+        assert_eq!(set.checked_sub_count(4, 1), Err(err));
+        assert_eq!(set.checked_increment(4), Err(err));
+        assert_eq!(set.checked_decrement(4), Err(err));
+    }
+
+    #[test]
+    fn test_saturating_counting() {
+        let mut set = Multiset::from([1u8, 254, 0, 0]);
+        assert_eq!(set.saturating_add_count(1, 4), Ok(254));
+        assert_eq!(set.saturating_increment(1), Ok(255));
+        assert_eq!(set.saturating_sub_count(0, 5), Ok(1));
+        assert_eq!(set.saturating_decrement(0), Ok(0));
+        assert_eq!(set.saturating_increment(3), Ok(0));
+        assert_eq!(set, Multiset::from([0, 255, 0, 1]));
+
+        let err = Error::ElementOutOfRange { element: 9, len: 4 };
+        assert_eq!(set.saturating_add_count(9, 1), Err(err));
+        assert_eq!(set.saturating_decrement(9), Err(err));
+    }
+
+    #[test]
+    fn test_extend_elements() {
+        let mut set = Multiset::<u8, 4>::empty();
+        assert_eq!(set.checked_extend_elements(vec![0, 0, 2]), Ok(()));
+        assert_eq!(set, Multiset::from([2, 0, 1, 0]));
+        assert_eq!(
+            set.checked_extend_elements(vec![3; 256]),
+            Err(Error::Overflow { element: 3 })
+        );
+        assert_eq!(set, Multiset::from([2, 0, 1, 255]));
+        assert_eq!(set.saturating_extend_elements(vec![3, 3, 1]), Ok(()));
+        assert_eq!(set, Multiset::from([2, 1, 1, 255]));
+        assert_eq!(
+            set.saturating_extend_elements(vec![1, 5, 1]),
+            Err(Error::ElementOutOfRange { element: 5, len: 4 })
+        );
+        assert_eq!(set, Multiset::from([2, 2, 1, 255]));
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut set = Multiset::<u8, 4>::empty();
+        set.extend(vec![0, 3, 3]);
+        set.extend(&[1, 3]);
+        assert_eq!(set, Multiset::from([1, 1, 0, 3]));
+
+        let res = catch_unwind_silent(|| Multiset::<u8, 4>::empty().extend(vec![4]));
+        assert!(res.is_err());
+        let res = catch_unwind_silent(|| Multiset::<u8, 4>::repeat(255).extend(vec![0]));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = Multiset::from([250u8, 1, 0, 3]);
+        let b = Multiset::from([5u8, 1, 0, 2]);
+        assert_eq!(a.checked_add(&b), Ok(Multiset::from([255, 2, 0, 5])));
+        assert_eq!(a.checked_sub(&b), Ok(Multiset::from([245, 0, 0, 1])));
+        assert_eq!(b.checked_sub(&a), Err(Error::Overflow { element: 0 }));
+        assert_eq!(a.checked_mul(&b), Err(Error::Overflow { element: 0 }));
+        assert_eq!(b.checked_mul(&b), Ok(Multiset::from([25, 1, 0, 4])));
+
+        assert_eq!(a.checked_add_scalar(5), Ok(Multiset::from([255, 6, 5, 8])));
+        assert_eq!(a.checked_add_scalar(6), Err(Error::Overflow { element: 0 }));
+        assert_eq!(a.checked_sub_scalar(1), Err(Error::Overflow { element: 2 }));
+        assert_eq!(a.checked_mul_scalar(1), Ok(a));
+        assert_eq!(
+            Multiset::from([1u8, 1, 0, 200]).checked_mul_scalar(2),
+            Err(Error::Overflow { element: 3 })
+        );
+    }
+
+    #[test]
+    fn test_saturating_arithmetic() {
+        let a = Multiset::from([250u8, 1, 0, 3]);
+        let b = Multiset::from([10u8, 2, 0, 1]);
+        assert_eq!(a.saturating_add(&b), Multiset::from([255, 3, 0, 4]));
+        assert_eq!(a.saturating_sub(&b), Multiset::from([240, 0, 0, 2]));
+        assert_eq!(a.saturating_mul(&b), Multiset::from([255, 2, 0, 3]));
+        assert_eq!(
+            a.saturating_add_scalar(10),
+            Multiset::from([255, 11, 10, 13])
+        );
+        assert_eq!(a.saturating_sub_scalar(2), Multiset::from([248, 0, 0, 1]));
+        assert_eq!(a.saturating_mul_scalar(2), Multiset::from([255, 2, 0, 6]));
+
+        let long = Multiset::<u16, 37>::repeat(u16::MAX - 1);
+        let ones = Multiset::<u16, 37>::repeat(1);
+        assert_eq!(long.saturating_add(&ones), Multiset::repeat(u16::MAX));
+        assert_eq!(long.saturating_add(&long), Multiset::repeat(u16::MAX));
+        assert_eq!(ones.saturating_sub(&long), Multiset::empty());
+    }
+
+    #[test]
+    fn test_wrapping_arithmetic() {
+        let a = Multiset::from([250u8, 1, 0, 3]);
+        let b = Multiset::from([10u8, 2, 0, 1]);
+        assert_eq!(a.wrapping_add(&b), Multiset::from([4, 3, 0, 4]));
+        assert_eq!(a.wrapping_sub(&b), Multiset::from([240, 255, 0, 2]));
+        assert_eq!(a.wrapping_mul(&b), Multiset::from([196, 2, 0, 3]));
+        assert_eq!(a.wrapping_add_scalar(10), Multiset::from([4, 11, 10, 13]));
+        assert_eq!(a.wrapping_sub_scalar(2), Multiset::from([248, 255, 254, 1]));
+        assert_eq!(a.wrapping_mul_scalar(2), Multiset::from([244, 2, 0, 6]));
+
+        let long = Multiset::<u16, 37>::repeat(u16::MAX);
+        let ones = Multiset::<u16, 37>::repeat(1);
+        assert_eq!(long.wrapping_add(&ones), Multiset::empty());
+        assert_eq!(Multiset::empty().wrapping_sub(&ones), long);
+        assert_eq!(long.wrapping_mul(&long), ones);
+    }
+
+    #[test]
+    fn test_zip_with() {
         let set1: Multiset<u8, 4> = Multiset::from([1, 5, 2, 8]);
         let set2: Multiset<u8, 4> = Multiset::from([1, 5, 2, 8]);
-        let result = set1.zip_map(&set2, |e1, e2| e1 + e2);
+        let result = set1.zip_with(&set2, |e1, e2| e1 + e2);
         let expected = Multiset::from([2, 10, 4, 16]);
-        assert_eq!(result, expected)
+        assert_eq!(result, expected);
+
+        let set3: Multiset<u64, 4> = Multiset::from([300, 0, 1, 2]);
+        let result: Multiset<u128, 4> = set1.zip_with(&set3, |a, b| a as u128 * b as u128);
+        assert_eq!(result, Multiset::from([300, 0, 2, 16]));
+    }
+
+    #[test]
+    fn test_map() {
+        let set: Multiset<u8, 5> = Multiset::from([1, 5, 0, 8, 255]);
+        assert_eq!(set.map(|c| c / 2), Multiset::from([0, 2, 0, 4, 127]));
+        let wide: Multiset<u16, 5> = set.map(|c| c as u16 + 1);
+        assert_eq!(wide, Multiset::from([2, 6, 1, 9, 256]));
+        assert_eq!(set.map(|c| c), set);
+    }
+
+    #[test]
+    fn test_retain() {
+        let mut set: Multiset<u16, 6> = Multiset::from([1, 5, 0, 8, 3, 2]);
+        set.retain(|_, count| count % 2 == 0);
+        assert_eq!(set, Multiset::from([0, 0, 0, 8, 0, 2]));
+        set.retain(|elem, _| elem < 4);
+        assert_eq!(set, Multiset::from([0, 0, 0, 8, 0, 0]));
+        set.retain(|_, _| false);
+        assert!(set.is_empty());
+    }
+
+    #[test]
+    fn test_filter_elems() {
+        let set: Multiset<u32, 4> = Multiset::from([1, 5, 0, 8]);
+        assert_eq!(set.filter_elems(&[true; 4]), set);
+        assert_eq!(set.filter_elems(&[false; 4]), Multiset::empty());
+        assert_eq!(
+            set.filter_elems(&[false, true, true, false]),
+            Multiset::from([0, 5, 0, 0])
+        );
+    }
+
+    #[test]
+    fn test_fold_counts() {
+        let set: Multiset<u8, 4> = Multiset::from([1, 5, 0, 8]);
+        let total = set.fold_counts(0usize, |acc, _, count| acc + count as usize);
+        assert_eq!(total, set.total());
+        let visited = set.fold_counts(Vec::new(), |mut acc, elem, count| {
+            acc.push((elem, count));
+            acc
+        });
+        assert_eq!(visited, vec![(0, 1), (1, 5), (2, 0), (3, 8)]);
     }
 
     #[test]
@@ -1744,6 +3697,29 @@ mod tests {
         assert_eq!(set.get(1), Some(&4))
     }
 
+    #[test]
+    fn try_from_slice() {
+        let slice: &[u16] = &[5, 4, 3];
+        assert_eq!(
+            Multiset::<u16, 3>::try_from(slice),
+            Ok(Multiset::from([5, 4, 3]))
+        );
+        assert_eq!(
+            Multiset::<u16, 4>::try_from(slice),
+            Err(Error::LengthMismatch {
+                expected: 4,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Multiset::<u16, 2>::try_from(slice),
+            Err(Error::LengthMismatch {
+                expected: 2,
+                found: 3
+            })
+        );
+    }
+
     #[test]
     fn to_array() {
         let set: Multiset<u16, 3> = Multiset::from([5, 4, 3]);
@@ -1751,6 +3727,19 @@ mod tests {
         assert_eq!(arr, [5, 4, 3])
     }
 
+    #[test]
+    fn test_try_from_elements() {
+        let result = Multiset::<u8, 4>::try_from_elements(&[1, 1, 0, 2, 2, 2]);
+        assert_eq!(result, Ok(Multiset::from([1, 2, 3, 0])));
+        let result = Multiset::<u8, 4>::try_from_elements(&[1, 1, 0, 2, 2, 2, 4]);
+        assert_eq!(result, Err(Error::ElementOutOfRange { element: 4, len: 4 }));
+        let elements = vec![3; 256];
+        let result = Multiset::<u8, 4>::try_from_elements(&elements);
+        assert_eq!(result, Err(Error::Overflow { element: 3 }));
+        let result = Multiset::<u16, 4>::try_from_elements(&elements);
+        assert_eq!(result, Ok(Multiset::from([0, 0, 0, 256])));
+    }
+
     #[test]
     fn test_clear() {
         let mut set = Multiset::<u8, 4>::repeat(3);
@@ -1821,6 +3810,18 @@ mod tests {
         assert_eq!(set.get(1), Some(&0))
     }
 
+    #[test]
+    fn test_try_insert_remove() {
+        let mut set = Multiset::<u8, 4>::repeat(3);
+        assert_eq!(set.try_insert(1, 5), Ok(()));
+        assert_eq!(set.try_remove(2), Ok(()));
+        assert_eq!(set, Multiset::from([3, 5, 0, 3]));
+        let err = Error::ElementOutOfRange { element: 4, len: 4 };
+        assert_eq!(set.try_insert(4, 5), Err(err));
+        assert_eq!(set.try_remove(4), Err(err));
+        assert_eq!(set, Multiset::from([3, 5, 0, 3]));
+    }
+
     #[test]
     fn test_get() {
         let set = Multiset::<usize, 4>::from([6, 7, 8, 9]);
@@ -2010,6 +4011,72 @@ mod tests {
         assert_eq!(set.total(), 10)
     }
 
+    #[test]
+    #[should_panic(expected = "the total of the multiset overflowed usize")]
+    fn test_total_overflow() {
+        Multiset::from([1u128 << 64, 0, 0, 0]).total();
+    }
+
+    #[test]
+    fn test_total_u128() {
+        let set = Multiset::from([1u8, 2, 3, 4]);
+        assert_eq!(set.total_u128(), 10);
+        assert_eq!(set.checked_total(), Ok(10));
+
+        let set = Multiset::from([u64::MAX; 3]);
+        assert_eq!(set.total_u128(), 3 * u64::MAX as u128);
+        assert_eq!(set.checked_total(), Ok(3 * u64::MAX as u128));
+
+        let set = Multiset::from([1u128, u128::MAX - 1, 1, 0]);
+        assert_eq!(set.total_u128(), u128::MAX);
+        assert_eq!(set.checked_total(), Err(Error::Overflow { element: 2 }));
+    }
+
+    #[test]
+    fn test_cast() {
+        let set = Multiset::from([1u8, 255, 0, 7]);
+        assert_eq!(set.cast::<u16>(), Multiset::from([1u16, 255, 0, 7]));
+        assert_eq!(set.cast::<u128>(), Multiset::from([1u128, 255, 0, 7]));
+        assert_eq!(set.cast::<u8>(), set);
+
+        let set = Multiset::from([1u64, 300, 0, u64::MAX]);
+        assert_eq!(set.try_cast::<u8>(), Err(Error::Overflow { element: 1 }));
+        assert_eq!(set.try_cast::<u128>(), Ok(set.cast::<u128>()));
+        assert_eq!(
+            set.saturating_cast::<u8>(),
+            Multiset::from([1u8, 255, 0, 255])
+        );
+        assert_eq!(
+            set.saturating_cast::<u32>(),
+            Multiset::from([1u32, 300, 0, u32::MAX])
+        );
+
+        let set = Multiset::from([1u32, 300, 0, 2]);
+        assert_eq!(set.try_cast::<u16>(), Ok(Multiset::from([1u16, 300, 0, 2])));
+    }
+
+    #[test]
+    fn test_u128_counter() {
+        let a = Multiset::from([u128::MAX, 2, 0, 1]);
+        let b = Multiset::from([1u128, 3, 0, 0]);
+        assert_eq!(a.union(&b), Multiset::from([u128::MAX, 3, 0, 1]));
+        assert_eq!(a.intersection(&b), Multiset::from([1, 2, 0, 0]));
+        assert_eq!(a.count_non_zero(), 3);
+        assert_eq!(a.elem_count_max(), (0, &u128::MAX));
+        assert_eq!(a.checked_add(&b), Err(Error::Overflow { element: 0 }));
+        assert!(b.is_subset(&Multiset::from([1, 3, 5, 0])));
+        assert_eq!(a.partial_cmp(&b), None);
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_entropy_large_counts() {
+        // The usize total of these counts overflows, the u128 total does not.
+        let set = Multiset::from([u64::MAX, u64::MAX, 0]);
+        assert_relative_eq!(set.shannon_entropy(), -(0.5f64.ln()));
+        assert_relative_eq!(set.collision_entropy(), 1.0);
+    }
+
     #[test]
     fn test_argmax() {
         let set = Multiset::from([1u8, 0, 3, 1]);
@@ -2074,6 +4141,15 @@ mod tests {
         assert!(result2.is_singleton() && result2.is_subset(&Multiset::from([1u8, 2, 3, 4, 5])));
     }
 
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_choose_random_large_counts() {
+        let rng = &mut StdRng::seed_from_u64(thread_rng().next_u64());
+        let mut set = Multiset::from([u64::MAX, 0, u64::MAX, 1]);
+        set.choose_random(rng);
+        assert!(set.is_singleton());
+    }
+
     #[cfg(feature = "rand")]
     #[test]
     fn test_choose_random_empty() {
@@ -2084,6 +4160,7 @@ mod tests {
         assert_eq!(result, expected);
     }
 
+    #[cfg(any(feature = "std", feature = "libm"))]
     #[test]
     fn test_collision_entropy() {
         let simple: Multiset<u8, 4> = Multiset::from([200, 0, 0, 0]);
@@ -2097,6 +4174,7 @@ mod tests {
         );
     }
 
+    #[cfg(any(feature = "std", feature = "libm"))]
     #[test]
     fn test_shannon_entropy() {
         let a: Multiset<u8, 4> = Multiset::from([200, 0, 0, 0]);
@@ -2110,9 +4188,299 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_distances() {
+        let a: Multiset<u16, 4> = Multiset::from([1, 2, 0, 0]);
+        let b: Multiset<u16, 4> = Multiset::from([0, 1, 3, 0]);
+        assert_eq!(a.l1_distance(&b), 5.0);
+        assert_eq!(a.linf_distance(&b), 3.0);
+        assert_eq!(a.jaccard_index(&b), 1.0 / 6.0);
+        assert_eq!(a.bray_curtis_dissimilarity(&b), 5.0 / 7.0);
+        assert_eq!(a.l1_distance(&a), 0.0);
+        assert_eq!(a.jaccard_index(&a), 1.0);
+        assert_eq!(a.bray_curtis_dissimilarity(&a), 0.0);
+        assert_eq!(
+            Multiset::<u16, 0>::empty().linf_distance(&Multiset::empty()),
+            0.0
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_float_distances() {
+        let a: Multiset<u16, 4> = Multiset::from([1, 2, 0, 0]);
+        let b: Multiset<u16, 4> = Multiset::from([0, 1, 3, 0]);
+        assert_relative_eq!(a.l2_distance(&b), 11f64.sqrt(), epsilon = f64::EPSILON);
+        assert_relative_eq!(
+            a.cosine_similarity(&b),
+            2.0 / (5f64.sqrt() * 10f64.sqrt()),
+            epsilon = f64::EPSILON
+        );
+        // sqrt(1 - sqrt(2 / 12)) for the distributions [1/3, 2/3] and [1/4, 3/4].
+        assert_relative_eq!(
+            a.hellinger_distance(&b),
+            (1.0 - (2.0f64 / 12.0).sqrt()).sqrt(),
+            epsilon = 1e-12
+        );
+        assert_eq!(a.l2_distance(&a), 0.0);
+        assert_relative_eq!(a.cosine_similarity(&a), 1.0, epsilon = 1e-12);
+        assert!(a.hellinger_distance(&a) < 1e-7);
+    }
+
+    #[test]
+    fn test_distances_empty() {
+        let empty: Multiset<u8, 4> = Multiset::empty();
+        let a: Multiset<u8, 4> = Multiset::from([1, 2, 0, 0]);
+        assert_eq!(empty.l1_distance(&empty), 0.0);
+        assert_eq!(empty.linf_distance(&empty), 0.0);
+        assert_eq!(empty.jaccard_index(&empty), 1.0);
+        assert_eq!(empty.jaccard_index(&a), 0.0);
+        assert_eq!(empty.bray_curtis_dissimilarity(&empty), 0.0);
+        assert_eq!(empty.bray_curtis_dissimilarity(&a), 1.0);
+        #[cfg(any(feature = "std", feature = "libm"))]
+        {
+            assert_eq!(empty.l2_distance(&empty), 0.0);
+            assert_eq!(empty.cosine_similarity(&empty), 1.0);
+            assert_eq!(empty.cosine_similarity(&a), 0.0);
+            assert_eq!(empty.hellinger_distance(&empty), 0.0);
+            assert_eq!(a.hellinger_distance(&empty), 1.0);
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_divergences() {
+        let a: Multiset<u16, 4> = Multiset::from([2, 1, 1, 0]);
+        let b: Multiset<u16, 4> = Multiset::from([1, 1, 1, 1]);
+        let ln2 = 2f64.ln();
+        assert_relative_eq!(a.kl_divergence(&b), 0.5 * ln2, epsilon = 1e-12);
+        assert_eq!(b.kl_divergence(&a), f64::INFINITY);
+        assert_eq!(a.kl_divergence(&a), 0.0);
+        assert_relative_eq!(a.cross_entropy(&b), 4f64.ln(), epsilon = 1e-12);
+        assert_relative_eq!(
+            a.cross_entropy(&b),
+            a.shannon_entropy() + a.kl_divergence(&b),
+            epsilon = 1e-12
+        );
+        assert_eq!(b.cross_entropy(&a), f64::INFINITY);
+        // The average distribution is [3/8, 1/4, 1/4, 1/8].
+        let js = 0.5 * (0.5 * (4f64 / 3.0).ln() + 0.25 * (2f64 / 3.0).ln() + 0.25 * ln2);
+        assert_relative_eq!(a.jensen_shannon_divergence(&b), js, epsilon = 1e-12);
+        assert_relative_eq!(b.jensen_shannon_divergence(&a), js, epsilon = 1e-12);
+        assert_eq!(a.total_variation(&b), 0.25);
+        assert_eq!(b.total_variation(&a), 0.25);
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_divergences_pseudocount() {
+        let a: Multiset<u16, 4> = Multiset::from([2, 1, 1, 0]);
+        let b: Multiset<u16, 4> = Multiset::from([1, 1, 1, 1]);
+        // The smoothed distributions are [3, 2, 2, 1] / 8 and [2, 2, 2, 2] / 8.
+        let kl = 0.25 * (4f64 / 3.0).ln();
+        assert_relative_eq!(
+            b.kl_divergence_with_pseudocount(&a, 1.0),
+            kl,
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            b.cross_entropy_with_pseudocount(&a, 1.0),
+            4f64.ln() + kl,
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            b.kl_divergence_with_pseudocount(&a, 0.0),
+            b.kl_divergence(&a)
+        );
+        let empty = Multiset::<u16, 4>::empty();
+        assert_relative_eq!(
+            a.kl_divergence_with_pseudocount(&empty, 1.0),
+            0.375 * 1.5f64.ln() + 0.125 * 0.5f64.ln(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    #[should_panic]
+    fn test_divergences_negative_pseudocount() {
+        let a: Multiset<u16, 4> = Multiset::from([2, 1, 1, 0]);
+        a.kl_divergence_with_pseudocount(&a, -1.0);
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_divergences_empty() {
+        let empty: Multiset<u8, 4> = Multiset::empty();
+        let a: Multiset<u8, 4> = Multiset::from([1, 2, 0, 0]);
+        assert_eq!(empty.kl_divergence(&empty), 0.0);
+        assert_eq!(empty.kl_divergence(&a), 0.0);
+        assert_eq!(a.kl_divergence(&empty), f64::INFINITY);
+        assert_eq!(empty.cross_entropy(&empty), 0.0);
+        assert_eq!(a.cross_entropy(&empty), f64::INFINITY);
+        assert_eq!(empty.jensen_shannon_divergence(&empty), 0.0);
+        assert_eq!(empty.jensen_shannon_divergence(&a), 2f64.ln());
+        assert_eq!(a.jensen_shannon_divergence(&empty), 2f64.ln());
+        assert_eq!(empty.total_variation(&empty), 0.0);
+        assert_eq!(a.total_variation(&empty), 1.0);
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_renyi_entropy() {
+        let a: Multiset<u8, 4> = Multiset::from([2, 1, 1, 0]);
+        for &base in [LogBase::E, LogBase::Two, LogBase::Ten].iter() {
+            // The entropies decrease as the order increases.
+            let orders = [0.0, 0.5, 1.0, 2.0, 3.0, f64::INFINITY];
+            for pair in orders.windows(2) {
+                assert!(a.renyi_entropy(pair[0], base) > a.renyi_entropy(pair[1], base));
+            }
+        }
+        assert_eq!(a.renyi_entropy(1.0, LogBase::E), a.shannon_entropy());
+        assert_eq!(a.renyi_entropy(2.0, LogBase::Two), a.collision_entropy());
+        assert_relative_eq!(a.renyi_entropy(1.0, LogBase::Two), 1.5, epsilon = 1e-12);
+        assert_relative_eq!(
+            a.renyi_entropy(2.0, LogBase::E),
+            -(0.375f64).ln(),
+            epsilon = 1e-12
+        );
+        assert_relative_eq!(
+            a.renyi_entropy(0.0, LogBase::Ten),
+            3f64.log10(),
+            epsilon = 1e-12
+        );
+        // The sum of the cubes of the probabilities is 5 / 32.
+        assert_relative_eq!(
+            a.renyi_entropy(3.0, LogBase::E),
+            (5f64 / 32.0).ln() / -2.0,
+            epsilon = 1e-12
+        );
+        assert_eq!(a.min_entropy(), 1.0);
+
+        let uniform: Multiset<u8, 4> = Multiset::from([3, 3, 3, 3]);
+        for &alpha in [0.0, 0.5, 1.0, 2.0, 3.0, f64::INFINITY].iter() {
+            assert_relative_eq!(
+                uniform.renyi_entropy(alpha, LogBase::Two),
+                2.0,
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_renyi_entropy_empty() {
+        let empty: Multiset<u8, 4> = Multiset::empty();
+        assert!(empty.collision_entropy().is_nan());
+        assert_eq!(empty.shannon_entropy(), 0.0);
+        for &alpha in [0.0, 0.5, 1.0, 2.0, 3.0, f64::INFINITY].iter() {
+            assert_eq!(empty.renyi_entropy(alpha, LogBase::E), 0.0);
+            assert_eq!(empty.hill_number(alpha), 0.0);
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    #[should_panic]
+    fn test_renyi_entropy_negative_order() {
+        let a: Multiset<u8, 4> = Multiset::from([2, 1, 1, 0]);
+        a.renyi_entropy(-1.0, LogBase::E);
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_hill_number() {
+        let a: Multiset<u8, 4> = Multiset::from([2, 1, 1, 0]);
+        assert_eq!(a.hill_number(0.0), 3.0);
+        assert_relative_eq!(a.hill_number(1.0), 2f64.powf(1.5), epsilon = 1e-12);
+        assert_relative_eq!(a.hill_number(2.0), 8.0 / 3.0, epsilon = 1e-12);
+        assert_eq!(a.hill_number(f64::INFINITY), 2.0);
+        for &q in [0.5, 1.0, 2.0, 3.0].iter() {
+            assert_relative_eq!(
+                a.hill_number(q),
+                a.renyi_entropy(q, LogBase::E).exp(),
+                epsilon = 1e-12
+            );
+        }
+    }
+
+    // The counts `y` of the examples of the R entropy package (Hausser and
+    // Strimmer, `?entropy`). PlugIn, MillerMadow, ChaoShen and JamesStein are
+    // its "ML", "MM", "CS" and "shrink" methods, and the expected values are
+    // those of `entropy.empirical`, `entropy.MillerMadow`, `entropy.ChaoShen`
+    // and `entropy.shrink` for these counts, including the package's coverage
+    // adjustment when every count is one and its shrinkage target over all 11
+    // bins.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_entropy_with() {
+        let a: Multiset<u8, 11> = Multiset::from([4, 2, 3, 0, 2, 4, 0, 0, 2, 1, 1]);
+        let expected = [
+            (EntropyEstimator::PlugIn, 1.968382408728381),
+            (EntropyEstimator::MillerMadow, 2.152592935044171),
+            (EntropyEstimator::ChaoShen, 2.201137101279585),
+            (EntropyEstimator::JamesStein, 2.379602895309956),
+        ];
+        for &(estimator, value) in expected.iter() {
+            assert_relative_eq!(a.entropy_with(estimator), value, epsilon = 1e-12);
+        }
+        assert_eq!(
+            a.entropy_with(EntropyEstimator::PlugIn),
+            a.shannon_entropy()
+        );
+    }
+
+    // There is no published reference value of the Grassberger (2003)
+    // estimator for these counts, so the expected value is its closed form,
+    // ln N - 1/N sum(n G(n)) with G(n) = digamma(n) + (-1)^n / 2 *
+    // (digamma((n + 1) / 2) - digamma(n / 2)), evaluated to 30 digits with
+    // mpmath. This checks the digamma approximation, not the definition.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_entropy_with_grassberger() {
+        let a: Multiset<u8, 11> = Multiset::from([4, 2, 3, 0, 2, 4, 0, 0, 2, 1, 1]);
+        assert_relative_eq!(
+            a.entropy_with(EntropyEstimator::Grassberger),
+            2.144626386031427,
+            epsilon = 1e-12
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_entropy_with_small() {
+        let estimators = [
+            EntropyEstimator::PlugIn,
+            EntropyEstimator::MillerMadow,
+            EntropyEstimator::ChaoShen,
+            EntropyEstimator::Grassberger,
+            EntropyEstimator::JamesStein,
+        ];
+        let empty: Multiset<u8, 4> = Multiset::empty();
+        for &estimator in estimators.iter() {
+            assert_eq!(empty.entropy_with(estimator), 0.0);
+        }
+        // A single observation fully shrinks to the uniform distribution.
+        let single: Multiset<u8, 4> = Multiset::from([0, 1, 0, 0]);
+        assert_eq!(single.entropy_with(EntropyEstimator::ChaoShen), 0.0);
+        assert_eq!(single.entropy_with(EntropyEstimator::MillerMadow), 0.0);
+        assert_relative_eq!(
+            single.entropy_with(EntropyEstimator::JamesStein),
+            4f64.ln(),
+            epsilon = 1e-12
+        );
+        // Only singletons, where the Chao-Shen coverage is adjusted.
+        let singletons: Multiset<u8, 4> = Multiset::from([1, 1, 1, 0]);
+        assert_relative_eq!(
+            singletons.entropy_with(EntropyEstimator::ChaoShen),
+            2.460486508261296,
+            epsilon = 1e-12
+        );
+    }
+
     #[test]
     fn test_generic() {
-        #[cfg(feature = "simd")]
+        #[cfg(any(feature = "simd", feature = "portable_simd"))]
         fn generic_total<T: Counter, const SIZE: usize>(ms: Multiset<T, SIZE>) -> usize
         where
             [(); T::L128 * T::L256 * T::LF]: Sized,
@@ -2120,14 +4488,14 @@ mod tests {
             ms.total()
         }
 
-        #[cfg(not(feature = "simd"))]
+        #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
         fn generic_total<T: Counter, const SIZE: usize>(ms: Multiset<T, SIZE>) -> usize {
             ms.total()
         }
 
         assert_eq!(generic_total(Multiset::from([1u8, 2, 3])), 6);
 
-        #[cfg(feature = "simd")]
+        #[cfg(any(feature = "simd", feature = "portable_simd"))]
         fn generic_union<T: Counter, const SIZE: usize>(
             ms1: Multiset<T, SIZE>,
             ms2: Multiset<T, SIZE>,
@@ -2138,7 +4506,7 @@ mod tests {
             ms1.union(&ms2)
         }
 
-        #[cfg(not(feature = "simd"))]
+        #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
         fn generic_union<T: Counter, const SIZE: usize>(
             ms1: Multiset<T, SIZE>,
             ms2: Multiset<T, SIZE>,
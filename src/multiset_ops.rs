@@ -0,0 +1,992 @@
+use crate::iter::{Elements, IterByCount, IterNonZero, Support};
+use crate::kernels;
+use crate::{Counter, Error, Multiset, MultisetVec};
+use alloc::vec;
+use core::slice::{Iter, IterMut};
+use num_traits::AsPrimitive;
+#[cfg(feature = "rand")]
+use rand::{Rng, RngCore};
+
+/// Storage agnostic multiset operations.
+///
+/// `MultisetOps` is implemented by every multiset flavour in Utote, as well as
+/// by plain slices of counters (`[N]`), so that algorithms can be written once
+/// and used with any of them. Each method has the same meaning as the
+/// inherent method of the same name on [`Multiset`].
+///
+/// Since the number of elements is not always known at compile time, methods
+/// which combine two multisets return a `Result`, with
+/// [`Error::LengthMismatch`] when the lengths differ. For [`Multiset`] these
+/// methods never fail.
+///
+/// Note that slices have inherent `len`, `is_empty` and `contains` methods
+/// which take precedence over the methods of this trait when using method
+/// call syntax. Use the fully qualified form, e.g.
+/// `MultisetOps::is_empty(slice)`, to call the multiset versions on a slice.
+///
+/// # Examples
+///
+/// ```
+/// use utote::{Counter, Error, Multiset, MultisetOps, MultisetVec};
+///
+/// // Fraction of the counts in `a` which are also in `b`.
+/// fn overlap<N: Counter, M: MultisetOps<N> + ?Sized>(a: &M, b: &M) -> Result<f64, Error> {
+///     let common = a.intersection(b)?;
+///     Ok(common.total() as f64 / a.total() as f64)
+/// }
+///
+/// let a = Multiset::from([2u8, 2, 0, 0]);
+/// let b = Multiset::from([1u8, 2, 5, 0]);
+/// assert_eq!(overlap(&a, &b), Ok(0.75));
+///
+/// let c = MultisetVec::from(vec![2u8, 2, 0, 0]);
+/// let d = MultisetVec::from(vec![1u8, 2, 5, 0]);
+/// assert_eq!(overlap(&c, &d), Ok(0.75));
+///
+/// let e: &[u8] = &[2, 2, 0, 0];
+/// let f: &[u8] = &[1, 2, 5];
+/// assert!(overlap(e, f).is_err());
+/// ```
+pub trait MultisetOps<N: Counter> {
+    /// The owned multiset type returned by the set operations.
+    type Owned: MultisetOps<N>;
+
+    /// Returns the number of elements in the multiset.
+    fn len(&self) -> usize;
+
+    /// Return an [Iter](`std::slice::Iter`) of the element counts in the
+    /// multiset.
+    fn iter(&self) -> Iter<'_, N>;
+
+    /// Return a [IterMut](`std::slice::IterMut`) of the element counts in the
+    /// multiset.
+    fn iter_mut(&mut self) -> IterMut<'_, N>;
+
+    /// Returns an iterator over the `(element, count)` pairs with a non-zero
+    /// count.
+    ///
+    /// See [`Multiset::iter_nonzero`].
+    #[inline]
+    fn iter_nonzero(&self) -> IterNonZero<'_, N> {
+        IterNonZero::new(self.iter().as_slice())
+    }
+
+    /// Returns an iterator over the elements with a non-zero count.
+    ///
+    /// See [`Multiset::support`].
+    #[inline]
+    fn support(&self) -> Support<'_, N> {
+        Support::new(self.iter().as_slice())
+    }
+
+    /// Returns an iterator yielding each element as many times as its count.
+    ///
+    /// See [`Multiset::elements`].
+    #[inline]
+    fn elements(&self) -> Elements<'_, N> {
+        Elements::new(self.iter().as_slice())
+    }
+
+    /// Returns an iterator over the `(element, count)` pairs, largest count
+    /// first.
+    ///
+    /// See [`Multiset::iter_by_count_desc`].
+    #[inline]
+    fn iter_by_count_desc(&self) -> IterByCount<'_, N> {
+        IterByCount::descending(self.iter().as_slice())
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// largest counts, largest first.
+    ///
+    /// See [`Multiset::top_k`].
+    #[inline]
+    fn top_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::top_k(self.iter().as_slice(), k)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// smallest counts, smallest first.
+    ///
+    /// See [`Multiset::bottom_k`].
+    #[inline]
+    fn bottom_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::bottom_k(self.iter().as_slice(), k)
+    }
+
+    /// Sets all element counts in the multiset to zero.
+    fn clear(&mut self);
+
+    /// Returns `true` if `elem` has count > 0 in the multiset.
+    fn contains(&self, elem: usize) -> bool;
+
+    /// Set the count of `elem` in the multiset to `amount`.
+    fn insert(&mut self, elem: usize, amount: N);
+
+    /// Set the count of `elem` in the multiset to zero.
+    fn remove(&mut self, elem: usize);
+
+    /// Returns a multiset which is the intersection of `self` and `other`.
+    ///
+    /// See [`Multiset::intersection`].
+    fn intersection(&self, other: &Self) -> Result<Self::Owned, Error>;
+
+    /// Returns a multiset which is the union of `self` and `other`.
+    ///
+    /// See [`Multiset::union`].
+    fn union(&self, other: &Self) -> Result<Self::Owned, Error>;
+
+    /// Returns a multiset which is the difference of `self` and `other`.
+    ///
+    /// See [`Multiset::difference`].
+    fn difference(&self, other: &Self) -> Result<Self::Owned, Error>;
+
+    /// Returns a multiset which is the symmetric_difference of `self` and
+    /// `other`.
+    ///
+    /// See [`Multiset::symmetric_difference`].
+    fn symmetric_difference(&self, other: &Self) -> Result<Self::Owned, Error>;
+
+    /// Returns the number of elements whose count is non-zero.
+    fn count_non_zero(&self) -> usize;
+
+    /// Returns the number of elements whose count is zero.
+    fn count_zero(&self) -> usize;
+
+    /// Returns `true` if only one element in the multiset has a non-zero
+    /// count.
+    fn is_singleton(&self) -> bool;
+
+    /// Returns `true` if `self` is disjoint to `other`.
+    ///
+    /// See [`Multiset::is_disjoint`].
+    fn is_disjoint(&self, other: &Self) -> Result<bool, Error>;
+
+    /// Returns `true` if `self` is a subset of `other`.
+    ///
+    /// See [`Multiset::is_subset`].
+    fn is_subset(&self, other: &Self) -> Result<bool, Error>;
+
+    /// Returns `true` if `self` is a superset of `other`.
+    ///
+    /// See [`Multiset::is_superset`].
+    fn is_superset(&self, other: &Self) -> Result<bool, Error>;
+
+    /// Returns `true` if `self` is a proper subset of `other`.
+    ///
+    /// See [`Multiset::is_proper_subset`].
+    fn is_proper_subset(&self, other: &Self) -> Result<bool, Error>;
+
+    /// Returns `true` if `self` is a proper superset of `other`.
+    ///
+    /// See [`Multiset::is_proper_superset`].
+    fn is_proper_superset(&self, other: &Self) -> Result<bool, Error>;
+
+    /// Returns `true` if any element count in `self` is less than that element
+    /// count in `other`.
+    fn is_any_lesser(&self, other: &Self) -> Result<bool, Error>;
+
+    /// Returns `true` if any element count in `self` is greater than that
+    /// element count in `other`.
+    fn is_any_greater(&self, other: &Self) -> Result<bool, Error>;
+
+    /// Returns `true` if all elements have a count of zero.
+    fn is_empty(&self) -> bool;
+
+    /// The total or cardinality of a multiset is the sum of all element
+    /// counts.
+    fn total(&self) -> usize;
+
+    /// The total of the multiset, accumulated as `u128`. Saturates at
+    /// `u128::MAX`, which is only reachable with `u128` counters.
+    ///
+    /// See [`Multiset::total_u128`].
+    #[inline]
+    fn total_u128(&self) -> u128 {
+        self.iter().fold(0u128, |acc, e| {
+            acc.saturating_add(<N as AsPrimitive<u128>>::as_(*e))
+        })
+    }
+
+    /// The total of the multiset, accumulated as `u128`, or
+    /// [`Error::Overflow`] naming the element at which the total overflowed.
+    ///
+    /// See [`Multiset::checked_total`].
+    #[inline]
+    fn checked_total(&self) -> Result<u128, Error> {
+        self.iter().enumerate().try_fold(0u128, |acc, (elem, e)| {
+            acc.checked_add(<N as AsPrimitive<u128>>::as_(*e))
+                .ok_or(Error::Overflow { element: elem })
+        })
+    }
+
+    /// Returns a tuple containing the element and a reference to the largest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    fn elem_count_max(&self) -> (usize, &N);
+
+    /// Returns the element with the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    fn elem_max(&self) -> usize {
+        self.elem_count_max().0
+    }
+
+    /// Returns a reference to the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    fn count_max(&self) -> &N {
+        self.elem_count_max().1
+    }
+
+    /// Returns a tuple containing the element and a reference to the smallest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    fn elem_count_min(&self) -> (usize, &N);
+
+    /// Returns the element with the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    fn elem_min(&self) -> usize {
+        self.elem_count_min().0
+    }
+
+    /// Returns a reference to the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    fn count_min(&self) -> &N {
+        self.elem_count_min().1
+    }
+
+    /// Set all element counts, except for the given `elem`, to zero.
+    fn choose(&mut self, elem: usize);
+
+    /// Set all element counts, except for a random choice, to zero.
+    ///
+    /// See [`Multiset::choose_random`].
+    #[cfg(feature = "rand")]
+    fn choose_random<T: RngCore>(&mut self, rng: &mut T);
+
+    /// Calculate the collision entropy of the multiset.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    fn collision_entropy(&self) -> f64;
+
+    /// Calculate the shannon entropy of the multiset. Uses ln rather than log2.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    fn shannon_entropy(&self) -> f64;
+}
+
+// Returns an error if `a` and `b` do not have the same number of elements.
+#[inline]
+pub(crate) fn check_same_len<A, B>(a: &[A], b: &[B]) -> Result<(), Error> {
+    if a.len() == b.len() {
+        Ok(())
+    } else {
+        Err(Error::LengthMismatch {
+            expected: a.len(),
+            found: b.len(),
+        })
+    }
+}
+
+#[inline]
+fn zip_map_slice<N, F>(a: &[N], b: &[N], mut f: F) -> Result<MultisetVec<N>, Error>
+where
+    N: Counter,
+    F: FnMut(N, N) -> N,
+{
+    check_same_len(a, b)?;
+    Ok(a.iter().zip(b.iter()).map(|(a, b)| f(*a, *b)).collect())
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// MultisetOps for slices
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter> MultisetOps<N> for [N] {
+    type Owned = MultisetVec<N>;
+
+    #[inline]
+    fn len(&self) -> usize {
+        <[N]>::len(self)
+    }
+
+    #[inline]
+    fn iter(&self) -> Iter<'_, N> {
+        <[N]>::iter(self)
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> IterMut<'_, N> {
+        <[N]>::iter_mut(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        <[N]>::iter_mut(self).for_each(|e| *e = N::zero())
+    }
+
+    #[inline]
+    fn contains(&self, elem: usize) -> bool {
+        matches!(self.get(elem), Some(count) if count > &N::zero())
+    }
+
+    #[inline]
+    fn insert(&mut self, elem: usize, amount: N) {
+        if let Some(count) = self.get_mut(elem) {
+            *count = amount;
+        }
+    }
+
+    #[inline]
+    fn remove(&mut self, elem: usize) {
+        if let Some(count) = self.get_mut(elem) {
+            *count = N::zero();
+        }
+    }
+
+    #[inline]
+    fn intersection(&self, other: &Self) -> Result<Self::Owned, Error> {
+        check_same_len(self, other)?;
+        let mut res = vec![N::zero(); <[N]>::len(self)];
+        kernels::intersection(self, other, &mut res);
+        Ok(MultisetVec::from(res))
+    }
+
+    #[inline]
+    fn union(&self, other: &Self) -> Result<Self::Owned, Error> {
+        check_same_len(self, other)?;
+        let mut res = vec![N::zero(); <[N]>::len(self)];
+        kernels::union(self, other, &mut res);
+        Ok(MultisetVec::from(res))
+    }
+
+    #[inline]
+    fn difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+        zip_map_slice(self, other, |e1, e2| {
+            if e1 > N::zero() && e2 > N::zero() {
+                e1.min(e2)
+            } else {
+                e1
+            }
+        })
+    }
+
+    #[inline]
+    fn symmetric_difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+        zip_map_slice(self, other, |e1, e2| {
+            if e1 > N::zero() && e2 > N::zero() {
+                e1.min(e2)
+            } else {
+                e1.max(e2)
+            }
+        })
+    }
+
+    #[inline]
+    fn count_non_zero(&self) -> usize {
+        <[N]>::iter(self).filter(|elem| !elem.is_zero()).count()
+    }
+
+    #[inline]
+    fn count_zero(&self) -> usize {
+        <[N]>::len(self) - MultisetOps::count_non_zero(self)
+    }
+
+    #[inline]
+    fn is_singleton(&self) -> bool {
+        MultisetOps::count_non_zero(self) == 1
+    }
+
+    #[inline]
+    fn is_disjoint(&self, other: &Self) -> Result<bool, Error> {
+        check_same_len(self, other)?;
+        Ok(kernels::is_disjoint(self, other))
+    }
+
+    #[inline]
+    fn is_subset(&self, other: &Self) -> Result<bool, Error> {
+        check_same_len(self, other)?;
+        Ok(kernels::is_subset(self, other))
+    }
+
+    #[inline]
+    fn is_superset(&self, other: &Self) -> Result<bool, Error> {
+        check_same_len(self, other)?;
+        Ok(kernels::is_superset(self, other))
+    }
+
+    #[inline]
+    fn is_proper_subset(&self, other: &Self) -> Result<bool, Error> {
+        Ok(MultisetOps::is_subset(self, other)? && self != other)
+    }
+
+    #[inline]
+    fn is_proper_superset(&self, other: &Self) -> Result<bool, Error> {
+        Ok(MultisetOps::is_superset(self, other)? && self != other)
+    }
+
+    #[inline]
+    fn is_any_lesser(&self, other: &Self) -> Result<bool, Error> {
+        check_same_len(self, other)?;
+        Ok(kernels::is_any_lesser(self, other))
+    }
+
+    #[inline]
+    fn is_any_greater(&self, other: &Self) -> Result<bool, Error> {
+        check_same_len(self, other)?;
+        Ok(kernels::is_any_greater(self, other))
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        <[N]>::iter(self).all(|elem| elem.is_zero())
+    }
+
+    #[inline]
+    fn total(&self) -> usize {
+        kernels::total(self)
+    }
+
+    #[inline]
+    fn elem_count_max(&self) -> (usize, &N) {
+        <[N]>::iter(self)
+            .enumerate()
+            .max_by_key(|(_, count)| *count)
+            .expect("elem_count_max called on a multiset with no elements")
+    }
+
+    #[inline]
+    fn elem_count_min(&self) -> (usize, &N) {
+        <[N]>::iter(self)
+            .enumerate()
+            .min_by_key(|(_, count)| *count)
+            .expect("elem_count_min called on a multiset with no elements")
+    }
+
+    #[inline]
+    fn choose(&mut self, elem: usize) {
+        <[N]>::iter_mut(self)
+            .enumerate()
+            .filter(|(i, _)| *i != elem)
+            .for_each(|(_, count)| *count = N::zero());
+    }
+
+    #[cfg(feature = "rand")]
+    #[inline]
+    fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
+        let total = MultisetOps::total_u128(self);
+        if total == 0 {
+            return;
+        }
+        let choice_value = rng.gen_range(1..=total);
+        let mut acc = 0u128;
+        let mut chosen = false;
+        for elem in <[N]>::iter_mut(self) {
+            if chosen {
+                *elem = N::zero();
+            } else {
+                acc = acc.saturating_add(<N as AsPrimitive<u128>>::as_(*elem));
+                if acc >= choice_value {
+                    chosen = true;
+                } else {
+                    *elem = N::zero();
+                }
+            }
+        }
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn collision_entropy(&self) -> f64 {
+        kernels::collision_entropy(self, MultisetOps::total_u128(self).as_())
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn shannon_entropy(&self) -> f64 {
+        kernels::shannon_entropy(self, MultisetOps::total_u128(self).as_())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// MultisetOps for Multiset
+////////////////////////////////////////////////////////////////////////////////
+
+// The simd implementations of the inherent methods on Multiset require an
+// extra bound, so the impl is generated for both cases.
+macro_rules! impl_multiset_ops_for_multiset {
+    ($($bound:tt)*) => {
+        impl<N: Counter, const SIZE: usize> MultisetOps<N> for Multiset<N, SIZE>
+        where
+            $($bound)*
+        {
+            type Owned = Multiset<N, SIZE>;
+
+            #[inline]
+            fn len(&self) -> usize {
+                SIZE
+            }
+
+            #[inline]
+            fn iter(&self) -> Iter<'_, N> {
+                Multiset::iter(self)
+            }
+
+            #[inline]
+            fn iter_mut(&mut self) -> IterMut<'_, N> {
+                Multiset::iter_mut(self)
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                Multiset::clear(self)
+            }
+
+            #[inline]
+            fn contains(&self, elem: usize) -> bool {
+                Multiset::contains(self, elem)
+            }
+
+            #[inline]
+            fn insert(&mut self, elem: usize, amount: N) {
+                Multiset::insert(self, elem, amount)
+            }
+
+            #[inline]
+            fn remove(&mut self, elem: usize) {
+                Multiset::remove(self, elem)
+            }
+
+            #[inline]
+            fn intersection(&self, other: &Self) -> Result<Self::Owned, Error> {
+                Ok(Multiset::intersection(self, other))
+            }
+
+            #[inline]
+            fn union(&self, other: &Self) -> Result<Self::Owned, Error> {
+                Ok(Multiset::union(self, other))
+            }
+
+            #[inline]
+            fn difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+                Ok(Multiset::difference(self, other))
+            }
+
+            #[inline]
+            fn symmetric_difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+                Ok(Multiset::symmetric_difference(self, other))
+            }
+
+            #[inline]
+            fn count_non_zero(&self) -> usize {
+                Multiset::count_non_zero(self)
+            }
+
+            #[inline]
+            fn count_zero(&self) -> usize {
+                Multiset::count_zero(self)
+            }
+
+            #[inline]
+            fn is_singleton(&self) -> bool {
+                Multiset::is_singleton(self)
+            }
+
+            #[inline]
+            fn is_disjoint(&self, other: &Self) -> Result<bool, Error> {
+                Ok(Multiset::is_disjoint(self, other))
+            }
+
+            #[inline]
+            fn is_subset(&self, other: &Self) -> Result<bool, Error> {
+                Ok(Multiset::is_subset(self, other))
+            }
+
+            #[inline]
+            fn is_superset(&self, other: &Self) -> Result<bool, Error> {
+                Ok(Multiset::is_superset(self, other))
+            }
+
+            #[inline]
+            fn is_proper_subset(&self, other: &Self) -> Result<bool, Error> {
+                Ok(Multiset::is_proper_subset(self, other))
+            }
+
+            #[inline]
+            fn is_proper_superset(&self, other: &Self) -> Result<bool, Error> {
+                Ok(Multiset::is_proper_superset(self, other))
+            }
+
+            #[inline]
+            fn is_any_lesser(&self, other: &Self) -> Result<bool, Error> {
+                Ok(Multiset::is_any_lesser(self, other))
+            }
+
+            #[inline]
+            fn is_any_greater(&self, other: &Self) -> Result<bool, Error> {
+                Ok(Multiset::is_any_greater(self, other))
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                Multiset::is_empty(self)
+            }
+
+            #[inline]
+            fn total(&self) -> usize {
+                Multiset::total(self)
+            }
+
+            #[inline]
+            fn elem_count_max(&self) -> (usize, &N) {
+                Multiset::elem_count_max(self)
+            }
+
+            #[inline]
+            fn elem_count_min(&self) -> (usize, &N) {
+                Multiset::elem_count_min(self)
+            }
+
+            #[inline]
+            fn choose(&mut self, elem: usize) {
+                Multiset::choose(self, elem)
+            }
+
+            #[cfg(feature = "rand")]
+            #[inline]
+            fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
+                Multiset::choose_random(self, rng)
+            }
+
+            #[cfg(any(feature = "std", feature = "libm"))]
+            #[inline]
+            fn collision_entropy(&self) -> f64 {
+                Multiset::collision_entropy(self)
+            }
+
+            #[cfg(any(feature = "std", feature = "libm"))]
+            #[inline]
+            fn shannon_entropy(&self) -> f64 {
+                Multiset::shannon_entropy(self)
+            }
+        }
+    };
+}
+
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+impl_multiset_ops_for_multiset!();
+
+#[cfg(any(feature = "simd", feature = "portable_simd"))]
+impl_multiset_ops_for_multiset!([(); N::L128 * N::L256 * N::LF]: Sized);
+
+////////////////////////////////////////////////////////////////////////////////
+// MultisetOps for MultisetVec
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter> MultisetOps<N> for MultisetVec<N> {
+    type Owned = MultisetVec<N>;
+
+    #[inline]
+    fn len(&self) -> usize {
+        MultisetVec::len(self)
+    }
+
+    #[inline]
+    fn iter(&self) -> Iter<'_, N> {
+        MultisetVec::iter(self)
+    }
+
+    #[inline]
+    fn iter_mut(&mut self) -> IterMut<'_, N> {
+        MultisetVec::iter_mut(self)
+    }
+
+    #[inline]
+    fn clear(&mut self) {
+        MultisetVec::clear(self)
+    }
+
+    #[inline]
+    fn contains(&self, elem: usize) -> bool {
+        MultisetVec::contains(self, elem)
+    }
+
+    #[inline]
+    fn insert(&mut self, elem: usize, amount: N) {
+        MultisetVec::insert(self, elem, amount)
+    }
+
+    #[inline]
+    fn remove(&mut self, elem: usize) {
+        MultisetVec::remove(self, elem)
+    }
+
+    #[inline]
+    fn intersection(&self, other: &Self) -> Result<Self::Owned, Error> {
+        MultisetVec::intersection(self, other)
+    }
+
+    #[inline]
+    fn union(&self, other: &Self) -> Result<Self::Owned, Error> {
+        MultisetVec::union(self, other)
+    }
+
+    #[inline]
+    fn difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+        MultisetVec::difference(self, other)
+    }
+
+    #[inline]
+    fn symmetric_difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+        MultisetVec::symmetric_difference(self, other)
+    }
+
+    #[inline]
+    fn count_non_zero(&self) -> usize {
+        MultisetVec::count_non_zero(self)
+    }
+
+    #[inline]
+    fn count_zero(&self) -> usize {
+        MultisetVec::count_zero(self)
+    }
+
+    #[inline]
+    fn is_singleton(&self) -> bool {
+        MultisetVec::is_singleton(self)
+    }
+
+    #[inline]
+    fn is_disjoint(&self, other: &Self) -> Result<bool, Error> {
+        MultisetVec::is_disjoint(self, other)
+    }
+
+    #[inline]
+    fn is_subset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetVec::is_subset(self, other)
+    }
+
+    #[inline]
+    fn is_superset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetVec::is_superset(self, other)
+    }
+
+    #[inline]
+    fn is_proper_subset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetVec::is_proper_subset(self, other)
+    }
+
+    #[inline]
+    fn is_proper_superset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetVec::is_proper_superset(self, other)
+    }
+
+    #[inline]
+    fn is_any_lesser(&self, other: &Self) -> Result<bool, Error> {
+        MultisetVec::is_any_lesser(self, other)
+    }
+
+    #[inline]
+    fn is_any_greater(&self, other: &Self) -> Result<bool, Error> {
+        MultisetVec::is_any_greater(self, other)
+    }
+
+    #[inline]
+    fn is_empty(&self) -> bool {
+        MultisetVec::is_empty(self)
+    }
+
+    #[inline]
+    fn total(&self) -> usize {
+        MultisetVec::total(self)
+    }
+
+    #[inline]
+    fn elem_count_max(&self) -> (usize, &N) {
+        MultisetVec::elem_count_max(self)
+    }
+
+    #[inline]
+    fn elem_count_min(&self) -> (usize, &N) {
+        MultisetVec::elem_count_min(self)
+    }
+
+    #[inline]
+    fn choose(&mut self, elem: usize) {
+        MultisetVec::choose(self, elem)
+    }
+
+    #[cfg(feature = "rand")]
+    #[inline]
+    fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
+        MultisetVec::choose_random(self, rng)
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn collision_entropy(&self) -> f64 {
+        MultisetVec::collision_entropy(self)
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn shannon_entropy(&self) -> f64 {
+        MultisetVec::shannon_entropy(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MultisetSliceMut;
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use approx::assert_relative_eq;
+    #[cfg(feature = "rand")]
+    use rand::prelude::*;
+
+    // Exercises the trait through generic code only, so that each impl is
+    // checked against the same expectations.
+    fn check_ops<M: MultisetOps<u8> + ?Sized>(a: &M, b: &M, c: &M) {
+        assert_eq!(a.len(), 4);
+        assert!(a.contains(1));
+        assert!(!a.contains(2));
+        assert!(!a.contains(9));
+
+        let inter = a.intersection(b).unwrap();
+        assert_eq!(inter.iter().copied().collect::<Vec<_>>(), vec![0, 1, 0, 0]);
+        let union = a.union(b).unwrap();
+        assert_eq!(union.iter().copied().collect::<Vec<_>>(), vec![1, 2, 3, 0]);
+        let diff = a.difference(b).unwrap();
+        assert_eq!(diff.iter().copied().collect::<Vec<_>>(), vec![1, 1, 0, 0]);
+        let sym_diff = a.symmetric_difference(b).unwrap();
+        assert_eq!(
+            sym_diff.iter().copied().collect::<Vec<_>>(),
+            vec![1, 1, 3, 0]
+        );
+
+        assert_eq!(a.count_non_zero(), 2);
+        assert_eq!(a.count_zero(), 2);
+        assert!(!a.is_singleton());
+        assert!(!a.is_empty());
+        assert_eq!(a.total(), 3);
+
+        assert_eq!(a.iter_nonzero().collect::<Vec<_>>(), vec![(0, &1), (1, &2)]);
+        assert_eq!(a.support().collect::<Vec<_>>(), vec![0, 1]);
+        assert_eq!(a.elements().rev().collect::<Vec<_>>(), vec![1, 1, 0]);
+        assert_eq!(a.iter_by_count_desc().next(), Some((1, &2)));
+        assert_eq!(a.top_k(1).collect::<Vec<_>>(), vec![(1, &2)]);
+        assert_eq!(a.bottom_k(2).collect::<Vec<_>>(), vec![(2, &0), (3, &0)]);
+
+        assert_eq!(a.is_disjoint(b), Ok(false));
+        assert_eq!(a.is_subset(c), Ok(true));
+        assert_eq!(c.is_superset(a), Ok(true));
+        assert_eq!(a.is_proper_subset(c), Ok(true));
+        assert_eq!(a.is_proper_superset(a), Ok(false));
+        assert_eq!(a.is_any_lesser(b), Ok(true));
+        assert_eq!(a.is_any_greater(b), Ok(true));
+
+        assert_eq!(a.elem_count_max(), (1, &2));
+        assert_eq!(a.elem_max(), 1);
+        assert_eq!(a.count_max(), &2);
+        assert_eq!(a.elem_count_min(), (2, &0));
+        assert_eq!(a.elem_min(), 2);
+        assert_eq!(a.count_min(), &0);
+
+        #[cfg(any(feature = "std", feature = "libm"))]
+        assert_relative_eq!(
+            a.shannon_entropy(),
+            0.6365141682948128,
+            epsilon = f64::EPSILON
+        );
+        #[cfg(any(feature = "std", feature = "libm"))]
+        assert_relative_eq!(
+            a.collision_entropy(),
+            0.8479969065549501,
+            epsilon = f64::EPSILON
+        );
+    }
+
+    fn check_mutators<M: MultisetOps<u8> + ?Sized>(a: &mut M) {
+        a.insert(3, 7);
+        assert!(a.contains(3));
+        a.choose(3);
+        assert_eq!(a.count_non_zero(), 1);
+        a.remove(3);
+        assert!(a.is_empty());
+        a.insert(0, 1);
+        a.clear();
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    fn test_multiset() {
+        let a = Multiset::from([1u8, 2, 0, 0]);
+        let b = Multiset::from([0u8, 1, 3, 0]);
+        let c = Multiset::from([1u8, 2, 1, 0]);
+        check_ops(&a, &b, &c);
+        check_mutators(&mut a.clone());
+    }
+
+    #[test]
+    fn test_multiset_vec() {
+        let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+        let b = MultisetVec::from(vec![0u8, 1, 3, 0]);
+        let c = MultisetVec::from(vec![1u8, 2, 1, 0]);
+        check_ops(&a, &b, &c);
+        check_mutators(&mut a.clone());
+    }
+
+    #[test]
+    fn test_slice() {
+        let a: &[u8] = &[1, 2, 0, 0];
+        let b: &[u8] = &[0, 1, 3, 0];
+        let c: &[u8] = &[1, 2, 1, 0];
+        check_ops(a, b, c);
+        check_mutators(&mut a.to_vec()[..]);
+    }
+
+    #[test]
+    fn test_multiset_slice_mut() {
+        let (mut a, mut b, mut c) = ([1u8, 2, 0, 0], [0u8, 1, 3, 0], [1u8, 2, 1, 0]);
+        let a = MultisetSliceMut::from(&mut a[..]);
+        let b = MultisetSliceMut::from(&mut b[..]);
+        let c = MultisetSliceMut::from(&mut c[..]);
+        check_ops(&a, &b, &c);
+        check_mutators(&mut MultisetSliceMut::from(&mut [1u8, 2, 0, 0][..]));
+    }
+
+    #[test]
+    fn test_slice_length_mismatch() {
+        let a: &[u8] = &[1, 2, 0, 0];
+        let b: &[u8] = &[0, 1, 3];
+        let err = Error::LengthMismatch {
+            expected: 4,
+            found: 3,
+        };
+        assert_eq!(a.union(b), Err(err));
+        assert_eq!(MultisetOps::is_subset(a, b), Err(err));
+        assert_eq!(MultisetOps::is_any_greater(a, b), Err(err));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_slice_choose_random() {
+        let mut result = [1u8, 2, 3, 4, 5];
+        let test_rng = &mut StdRng::seed_from_u64(thread_rng().next_u64());
+        MultisetOps::choose_random(&mut result[..], test_rng);
+        assert!(MultisetOps::is_singleton(&result[..]));
+        assert_eq!(
+            MultisetOps::is_subset(&result[..], &[1u8, 2, 3, 4, 5][..]),
+            Ok(true)
+        );
+    }
+}
@@ -0,0 +1,2065 @@
+use crate::iter::{Elements, IterByCount, IterNonZero, Support};
+use crate::{Counter, Error, Multiset, MultisetOps, MultisetVec};
+use alloc::vec::Vec;
+use core::convert::TryFrom;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
+};
+use core::slice::{Iter, IterMut, SliceIndex};
+#[cfg(feature = "rand")]
+use rand::RngCore;
+
+/// A borrowed view of a slice of counters as a multiset.
+///
+/// `MultisetSlice` gives the multiset API to counters which live in some
+/// other buffer, such as a row of a matrix or a field of another struct,
+/// without copying them. Operations which produce a new multiset return an
+/// owned [`MultisetVec`].
+///
+/// As with [`MultisetVec`], methods which combine two multisets return
+/// [`Error::LengthMismatch`] when the lengths differ.
+///
+/// # Examples
+///
+/// ```
+/// use utote::{MultisetSlice, MultisetVec};
+///
+/// let matrix = [[1u8, 2, 0, 0], [0, 1, 3, 0]];
+/// let a = MultisetSlice::from(&matrix[0][..]);
+/// let b = MultisetSlice::from(&matrix[1][..]);
+///
+/// assert_eq!(a.total(), 3);
+/// assert_eq!(a.is_subset(&b), Ok(false));
+/// assert_eq!(a.union(&b), Ok(MultisetVec::from(vec![1, 2, 3, 0])));
+/// ```
+///
+/// Conversions to and from [`Multiset`] do not copy when the lengths match:
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use utote::{Multiset, MultisetSlice};
+///
+/// let multiset = Multiset::from([1u8, 2, 3]);
+/// let view = MultisetSlice::from(&multiset);
+/// let back = <&Multiset<u8, 3>>::try_from(view).unwrap();
+/// assert_eq!(back, &multiset);
+///
+/// assert!(<&Multiset<u8, 4>>::try_from(view).is_err());
+/// ```
+pub struct MultisetSlice<'a, N: Counter> {
+    pub(crate) data: &'a [N],
+}
+
+/// A mutable borrowed view of a slice of counters as a multiset.
+///
+/// `MultisetSliceMut` is the mutable counterpart of [`MultisetSlice`], which
+/// also provides the mutating methods of the multiset API along with the
+/// assigning arithmetic operators.
+///
+/// # Examples
+///
+/// ```
+/// use utote::MultisetSliceMut;
+///
+/// let mut buffer = [0u8, 1, 2, 3, 4, 5];
+/// let mut view = MultisetSliceMut::from(&mut buffer[2..]);
+///
+/// view.insert(0, 7);
+/// view += 1;
+/// assert_eq!(view.total(), 23);
+/// assert_eq!(buffer, [0, 1, 8, 4, 5, 6]);
+/// ```
+pub struct MultisetSliceMut<'a, N: Counter> {
+    pub(crate) data: &'a mut [N],
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common trait implementations for MultisetSlice & MultisetSliceMut
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a, N: Counter> Debug for MultisetSlice<'a, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultisetSlice")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<'a, N: Counter> Debug for MultisetSliceMut<'a, N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultisetSliceMut")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<'a, N: Counter> Hash for MultisetSlice<'a, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
+}
+
+impl<'a, N: Counter> Hash for MultisetSliceMut<'a, N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
+}
+
+impl<'a, N: Counter> Clone for MultisetSlice<'a, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, N: Counter> Copy for MultisetSlice<'a, N> {}
+
+impl<'a, 'b, N: Counter> PartialEq<MultisetSlice<'b, N>> for MultisetSlice<'a, N> {
+    #[inline]
+    fn eq(&self, other: &MultisetSlice<'b, N>) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<'a, N: Counter> Eq for MultisetSlice<'a, N> {}
+
+impl<'a, 'b, N: Counter> PartialEq<MultisetSliceMut<'b, N>> for MultisetSliceMut<'a, N> {
+    #[inline]
+    fn eq(&self, other: &MultisetSliceMut<'b, N>) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<'a, N: Counter> Eq for MultisetSliceMut<'a, N> {}
+
+impl<'a, N: Counter, I: SliceIndex<[N]>> Index<I> for MultisetSlice<'a, N> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(self.data, index)
+    }
+}
+
+impl<'a, N: Counter, I: SliceIndex<[N]>> Index<I> for MultisetSliceMut<'a, N> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(&*self.data, index)
+    }
+}
+
+impl<'a, N: Counter, I: SliceIndex<[N]>> IndexMut<I> for MultisetSliceMut<'a, N> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        IndexMut::index_mut(&mut *self.data, index)
+    }
+}
+
+impl<'a, N: Counter> IntoIterator for MultisetSlice<'a, N> {
+    type Item = &'a N;
+    type IntoIter = Iter<'a, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl<'a, N: Counter> IntoIterator for &MultisetSlice<'a, N> {
+    type Item = &'a N;
+    type IntoIter = Iter<'a, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter()
+    }
+}
+
+impl<'a, N: Counter> IntoIterator for MultisetSliceMut<'a, N> {
+    type Item = &'a mut N;
+    type IntoIter = IterMut<'a, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.iter_mut()
+    }
+}
+
+impl<'a, 'b, N: Counter> IntoIterator for &'b MultisetSliceMut<'a, N> {
+    type Item = &'b N;
+    type IntoIter = Iter<'b, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, 'b, N: Counter> IntoIterator for &'b mut MultisetSliceMut<'a, N> {
+    type Item = &'b mut N;
+    type IntoIter = IterMut<'b, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<'a, N: Counter> From<&'a [N]> for MultisetSlice<'a, N> {
+    #[inline]
+    fn from(data: &'a [N]) -> Self {
+        MultisetSlice { data }
+    }
+}
+
+impl<'a, N: Counter> From<MultisetSlice<'a, N>> for &'a [N] {
+    #[inline]
+    fn from(set: MultisetSlice<'a, N>) -> Self {
+        set.data
+    }
+}
+
+impl<'a, N: Counter> From<&'a mut [N]> for MultisetSliceMut<'a, N> {
+    #[inline]
+    fn from(data: &'a mut [N]) -> Self {
+        MultisetSliceMut { data }
+    }
+}
+
+impl<'a, N: Counter> From<MultisetSliceMut<'a, N>> for &'a mut [N] {
+    #[inline]
+    fn from(set: MultisetSliceMut<'a, N>) -> Self {
+        set.data
+    }
+}
+
+impl<'a, N: Counter> From<MultisetSliceMut<'a, N>> for MultisetSlice<'a, N> {
+    #[inline]
+    fn from(set: MultisetSliceMut<'a, N>) -> Self {
+        MultisetSlice { data: set.data }
+    }
+}
+
+impl<'a, N: Counter, const SIZE: usize> From<&'a Multiset<N, SIZE>> for MultisetSlice<'a, N> {
+    #[inline]
+    fn from(set: &'a Multiset<N, SIZE>) -> Self {
+        MultisetSlice { data: &set.data }
+    }
+}
+
+impl<'a, N: Counter, const SIZE: usize> From<&'a mut Multiset<N, SIZE>>
+    for MultisetSliceMut<'a, N>
+{
+    #[inline]
+    fn from(set: &'a mut Multiset<N, SIZE>) -> Self {
+        MultisetSliceMut {
+            data: &mut set.data,
+        }
+    }
+}
+
+impl<'a, N: Counter> From<&'a MultisetVec<N>> for MultisetSlice<'a, N> {
+    #[inline]
+    fn from(set: &'a MultisetVec<N>) -> Self {
+        MultisetSlice { data: &set.data }
+    }
+}
+
+impl<'a, N: Counter> From<&'a mut MultisetVec<N>> for MultisetSliceMut<'a, N> {
+    #[inline]
+    fn from(set: &'a mut MultisetVec<N>) -> Self {
+        MultisetSliceMut {
+            data: &mut set.data,
+        }
+    }
+}
+
+impl<'a, N: Counter> From<MultisetSlice<'a, N>> for MultisetVec<N> {
+    #[inline]
+    fn from(set: MultisetSlice<'a, N>) -> Self {
+        MultisetVec::from(set.data)
+    }
+}
+
+impl<'a, N: Counter, const SIZE: usize> TryFrom<MultisetSlice<'a, N>> for &'a Multiset<N, SIZE> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(set: MultisetSlice<'a, N>) -> Result<Self, Self::Error> {
+        let array = <&[N; SIZE]>::try_from(set.data).map_err(|_| Error::LengthMismatch {
+            expected: SIZE,
+            found: set.len(),
+        })?;
+        // Safety: Multiset is a transparent wrapper of [N; SIZE].
+        Ok(unsafe { &*(array as *const [N; SIZE] as *const Multiset<N, SIZE>) })
+    }
+}
+
+impl<'a, N: Counter, const SIZE: usize> TryFrom<MultisetSliceMut<'a, N>>
+    for &'a mut Multiset<N, SIZE>
+{
+    type Error = Error;
+
+    #[inline]
+    fn try_from(set: MultisetSliceMut<'a, N>) -> Result<Self, Self::Error> {
+        let found = set.len();
+        let array = <&mut [N; SIZE]>::try_from(set.data).map_err(|_| Error::LengthMismatch {
+            expected: SIZE,
+            found,
+        })?;
+        // Safety: Multiset is a transparent wrapper of [N; SIZE].
+        Ok(unsafe { &mut *(array as *mut [N; SIZE] as *mut Multiset<N, SIZE>) })
+    }
+}
+
+impl<'a, N: Counter, const SIZE: usize> TryFrom<MultisetSlice<'a, N>> for Multiset<N, SIZE> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(set: MultisetSlice<'a, N>) -> Result<Self, Self::Error> {
+        Ok(*<&Multiset<N, SIZE>>::try_from(set)?)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common ops implementations for MultisetSlice & MultisetSliceMut
+////////////////////////////////////////////////////////////////////////////////
+
+// Elementwise ops on views produce an owned MultisetVec, while the assigning
+// ops write through a MultisetSliceMut. As for MultisetVec, the ops panic if
+// the lengths of the operands differ.
+macro_rules! impl_slice_ops {
+    ($op:ident, $op_fn:ident, $op_assign:ident, $op_assign_fn:ident) => {
+        impl<'a, 'b, N: Counter> $op<MultisetSlice<'b, N>> for MultisetSlice<'a, N> {
+            type Output = MultisetVec<N>;
+
+            #[inline]
+            fn $op_fn(self, rhs: MultisetSlice<'b, N>) -> Self::Output {
+                if let Err(e) = crate::multiset_ops::check_same_len(self.data, rhs.data) {
+                    panic!("{}", e)
+                }
+                MultisetVec::from(
+                    self.data
+                        .iter()
+                        .zip(rhs.data.iter())
+                        .map(|(l, r)| (*l).$op_fn(*r))
+                        .collect::<Vec<N>>(),
+                )
+            }
+        }
+
+        impl<'a, N: Counter> $op<N> for MultisetSlice<'a, N> {
+            type Output = MultisetVec<N>;
+
+            #[inline]
+            fn $op_fn(self, rhs: N) -> Self::Output {
+                MultisetVec::from(
+                    self.data
+                        .iter()
+                        .map(|l| (*l).$op_fn(rhs))
+                        .collect::<Vec<N>>(),
+                )
+            }
+        }
+
+        impl<'a, 'b, N: Counter> $op_assign<MultisetSlice<'b, N>> for MultisetSliceMut<'a, N> {
+            #[inline]
+            fn $op_assign_fn(&mut self, rhs: MultisetSlice<'b, N>) {
+                if let Err(e) = crate::multiset_ops::check_same_len(self.data, rhs.data) {
+                    panic!("{}", e)
+                }
+                self.data
+                    .iter_mut()
+                    .zip(rhs.data.iter())
+                    .for_each(|(l, r)| l.$op_assign_fn(*r));
+            }
+        }
+
+        impl<'a, N: Counter> $op_assign<N> for MultisetSliceMut<'a, N> {
+            #[inline]
+            fn $op_assign_fn(&mut self, rhs: N) {
+                self.data.iter_mut().for_each(|l| l.$op_assign_fn(rhs));
+            }
+        }
+    };
+}
+
+impl_slice_ops!(Add, add, AddAssign, add_assign);
+impl_slice_ops!(Div, div, DivAssign, div_assign);
+impl_slice_ops!(Mul, mul, MulAssign, mul_assign);
+impl_slice_ops!(Rem, rem, RemAssign, rem_assign);
+impl_slice_ops!(Sub, sub, SubAssign, sub_assign);
+
+////////////////////////////////////////////////////////////////////////////////
+// Inherent methods for MultisetSlice
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a, N: Counter> MultisetSlice<'a, N> {
+    /// Constructs a new MultisetSlice viewing `data`.
+    ///
+    /// This method is equivalent to calling [`MultisetSlice::from`] with a
+    /// slice of the correct type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let data = [1u8, 2, 3, 4];
+    /// assert_eq!(MultisetSlice::new(&data), MultisetSlice::from(&data[..]));
+    /// ```
+    #[inline]
+    pub fn new(data: &'a [N]) -> MultisetSlice<'a, N> {
+        MultisetSlice { data }
+    }
+
+    /// Returns the number of elements in the multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[1u8, 0, 3]);
+    /// assert_eq!(multiset.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns the underlying slice of counters.
+    #[inline]
+    pub fn as_slice(&self) -> &'a [N] {
+        self.data
+    }
+
+    /// Return an [Iter](`std::slice::Iter`) of the element counts in the
+    /// MultisetSlice.
+    #[inline]
+    pub fn iter(&self) -> Iter<'a, N> {
+        self.data.iter()
+    }
+
+    /// Returns an iterator over the `(element, count)` pairs with a non-zero
+    /// count, in element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[0u8, 3, 0, 1]);
+    /// let pairs: Vec<_> = multiset.iter_nonzero().collect();
+    /// assert_eq!(pairs, vec![(1, &3), (3, &1)]);
+    /// ```
+    #[inline]
+    pub fn iter_nonzero(&self) -> IterNonZero<'a, N> {
+        IterNonZero::new(self.data)
+    }
+
+    /// Returns an iterator over the elements with a non-zero count, in
+    /// element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[0u8, 3, 0, 1]);
+    /// assert_eq!(multiset.support().collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    #[inline]
+    pub fn support(&self) -> Support<'a, N> {
+        Support::new(self.data)
+    }
+
+    /// Returns an iterator yielding each element as many times as its count,
+    /// in element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[0u8, 3, 0, 1]);
+    /// let elements: Vec<_> = multiset.elements().collect();
+    /// assert_eq!(elements, vec![1, 1, 1, 3]);
+    /// ```
+    #[inline]
+    pub fn elements(&self) -> Elements<'a, N> {
+        Elements::new(self.data)
+    }
+
+    /// Returns an iterator over all the `(element, count)` pairs, largest
+    /// count first. Elements with equal counts are in element order.
+    ///
+    /// The multiset itself is not reordered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[2u8, 0, 5, 2]);
+    /// let sorted: Vec<_> = multiset.iter_by_count_desc().collect();
+    /// assert_eq!(sorted, vec![(2, &5), (0, &2), (3, &2), (1, &0)]);
+    /// ```
+    #[inline]
+    pub fn iter_by_count_desc(&self) -> IterByCount<'a, N> {
+        IterByCount::descending(self.data)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// largest counts, largest first, or over all of them if there are fewer
+    /// than `k` elements. Elements with equal counts are in element order.
+    ///
+    /// The `k` pairs are selected in linear time, so only they are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[2u8, 0, 5, 2]);
+    /// let top: Vec<_> = multiset.top_k(2).collect();
+    /// assert_eq!(top, vec![(2, &5), (0, &2)]);
+    /// ```
+    #[inline]
+    pub fn top_k(&self, k: usize) -> IterByCount<'a, N> {
+        IterByCount::top_k(self.data, k)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// smallest counts, smallest first, or over all of them if there are fewer
+    /// than `k` elements. Elements with equal counts are in element order.
+    ///
+    /// The `k` pairs are selected in linear time, so only they are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[2u8, 0, 5, 2]);
+    /// let bottom: Vec<_> = multiset.bottom_k(2).collect();
+    /// assert_eq!(bottom, vec![(1, &0), (0, &2)]);
+    /// ```
+    #[inline]
+    pub fn bottom_k(&self, k: usize) -> IterByCount<'a, N> {
+        IterByCount::bottom_k(self.data, k)
+    }
+
+    /// Returns `true` if `elem` has count > 0 in the multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.contains(1), true);
+    /// assert_eq!(multiset.contains(3), false);
+    /// assert_eq!(multiset.contains(5), false);
+    /// ```
+    #[inline]
+    pub fn contains(&self, elem: usize) -> bool {
+        MultisetOps::contains(self.data, elem)
+    }
+
+    /// Returns `true` if `elem` has count > 0 in the multiset, without doing
+    /// bounds checking.
+    ///
+    /// For a safe alternative see [`contains`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]* even if the resulting boolean is not used.
+    ///
+    /// [`contains`]: MultisetSlice::contains
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    #[inline]
+    pub unsafe fn contains_unchecked(&self, elem: usize) -> bool {
+        self.get_unchecked(elem) > &N::zero()
+    }
+
+    /// Returns a reference to a count or subslice of counts depending on the
+    /// type of index, or `None` if the index is out of bounds.
+    ///
+    /// See [`Multiset::get`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.get(1), Some(&2));
+    /// assert_eq!(multiset.get(5), None);
+    /// ```
+    #[inline]
+    pub fn get<I>(&self, index: I) -> Option<&'a I::Output>
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get(index)
+    }
+
+    /// Returns a reference to a count or subslice of counts, without doing
+    /// bounds checking.
+    ///
+    /// For a safe alternative see [`get`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]* even if the resulting reference is not used.
+    ///
+    /// [`get`]: MultisetSlice::get
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    #[inline]
+    pub unsafe fn get_unchecked<I>(&self, index: I) -> &'a I::Output
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get_unchecked(index)
+    }
+
+    /// Returns a multiset which is the intersection of `self` and `other`, or
+    /// an error if their lengths differ.
+    ///
+    /// See [`Multiset::intersection`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{MultisetSlice, MultisetVec};
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[0, 1, 3, 0]);
+    /// assert_eq!(a.intersection(&b), Ok(MultisetVec::from(vec![0, 1, 0, 0])));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn intersection(&self, other: &MultisetSlice<'_, N>) -> Result<MultisetVec<N>, Error> {
+        MultisetOps::intersection(self.data, other.data)
+    }
+
+    /// Returns a multiset which is the union of `self` and `other`, or an
+    /// error if their lengths differ.
+    ///
+    /// See [`Multiset::union`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{MultisetSlice, MultisetVec};
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[0, 1, 3, 0]);
+    /// assert_eq!(a.union(&b), Ok(MultisetVec::from(vec![1, 2, 3, 0])));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn union(&self, other: &MultisetSlice<'_, N>) -> Result<MultisetVec<N>, Error> {
+        MultisetOps::union(self.data, other.data)
+    }
+
+    /// Returns a multiset which is the difference of `self` and `other`, or
+    /// an error if their lengths differ.
+    ///
+    /// See [`Multiset::difference`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{MultisetSlice, MultisetVec};
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[0, 1, 3, 0]);
+    /// assert_eq!(a.difference(&b), Ok(MultisetVec::from(vec![1, 1, 0, 0])));
+    /// ```
+    #[inline]
+    pub fn difference(&self, other: &MultisetSlice<'_, N>) -> Result<MultisetVec<N>, Error> {
+        MultisetOps::difference(self.data, other.data)
+    }
+
+    /// Returns a multiset which is the symmetric_difference of `self` and
+    /// `other`, or an error if their lengths differ.
+    ///
+    /// See [`Multiset::symmetric_difference`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{MultisetSlice, MultisetVec};
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[0, 1, 3, 0]);
+    /// let c = MultisetVec::from(vec![1, 1, 3, 0]);
+    /// assert_eq!(a.symmetric_difference(&b), Ok(c));
+    /// ```
+    #[inline]
+    pub fn symmetric_difference(
+        &self,
+        other: &MultisetSlice<'_, N>,
+    ) -> Result<MultisetVec<N>, Error> {
+        MultisetOps::symmetric_difference(self.data, other.data)
+    }
+
+    /// Returns the number of elements whose count is non-zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[1u8, 0, 0, 0]);
+    /// assert_eq!(multiset.count_non_zero(), 1);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn count_non_zero(&self) -> usize {
+        MultisetOps::count_non_zero(self.data)
+    }
+
+    /// Returns the number of elements whose count is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[1u8, 0, 0, 0]);
+    /// assert_eq!(multiset.count_zero(), 3);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn count_zero(&self) -> usize {
+        self.len() - self.count_non_zero()
+    }
+
+    /// Returns `true` if only one element in the multiset has a non-zero
+    /// count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[0u8, 5, 0, 0]);
+    /// assert_eq!(multiset.is_singleton(), true);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_singleton(&self) -> bool {
+        self.count_non_zero() == 1
+    }
+
+    /// Returns `true` if `self` is disjoint to `other`, or an error if their
+    /// lengths differ.
+    ///
+    /// See [`Multiset::is_disjoint`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[0, 0, 3, 4]);
+    /// assert_eq!(a.is_disjoint(&b), Ok(true));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_disjoint(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        MultisetOps::is_disjoint(self.data, other.data)
+    }
+
+    /// Returns `true` if `self` is a subset of `other`, or an error if their
+    /// lengths differ.
+    ///
+    /// See [`Multiset::is_subset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[1, 3, 0, 0]);
+    /// assert_eq!(a.is_subset(&b), Ok(true));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_subset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        MultisetOps::is_subset(self.data, other.data)
+    }
+
+    /// Returns `true` if `self` is a superset of `other`, or an error if
+    /// their lengths differ.
+    ///
+    /// See [`Multiset::is_superset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[1, 1, 0, 0]);
+    /// assert_eq!(a.is_superset(&b), Ok(true));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_superset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        MultisetOps::is_superset(self.data, other.data)
+    }
+
+    /// Returns `true` if `self` is a proper subset of `other`, or an error if
+    /// their lengths differ.
+    ///
+    /// See [`Multiset::is_proper_subset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// assert_eq!(a.is_proper_subset(&a), Ok(false));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_proper_subset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        Ok(self.is_subset(other)? && self.data != other.data)
+    }
+
+    /// Returns `true` if `self` is a proper superset of `other`, or an error
+    /// if their lengths differ.
+    ///
+    /// See [`Multiset::is_proper_superset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[1, 1, 0, 0]);
+    /// assert_eq!(a.is_proper_superset(&b), Ok(true));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_proper_superset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        Ok(self.is_superset(other)? && self.data != other.data)
+    }
+
+    /// Returns `true` if any element count in `self` is less than that element
+    /// count in `other`, or an error if their lengths differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 4, 0]);
+    /// let b = MultisetSlice::new(&[1, 3, 0, 0]);
+    /// assert_eq!(a.is_any_lesser(&b), Ok(true));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_any_lesser(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        MultisetOps::is_any_lesser(self.data, other.data)
+    }
+
+    /// Returns `true` if any element count in `self` is greater than that
+    /// element count in `other`, or an error if their lengths differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+    /// let b = MultisetSlice::new(&[1, 1, 4, 0]);
+    /// assert_eq!(a.is_any_greater(&b), Ok(true));
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn is_any_greater(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        MultisetOps::is_any_greater(self.data, other.data)
+    }
+
+    /// Returns `true` if all elements have a count of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[0u8, 0, 0, 0]);
+    /// assert_eq!(multiset.is_empty(), true);
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        MultisetOps::is_empty(self.data)
+    }
+
+    /// The total or cardinality of a multiset is the sum of all element
+    /// counts.
+    ///
+    /// See [`Multiset::total`] for details.
+    ///
+    /// # Panics
+    /// If the counter type is wider than `usize`, such as `u128`, and the total
+    /// does not fit in a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[1u8, 2, 3, 4]);
+    /// assert_eq!(multiset.total(), 10);
+    /// ```
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[inline]
+    pub fn total(&self) -> usize {
+        MultisetOps::total(self.data)
+    }
+
+    /// The total of the multiset, accumulated as `u128`.
+    ///
+    /// See [`Multiset::total_u128`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[u64::MAX, u64::MAX]);
+    /// assert_eq!(multiset.total_u128(), 2 * u64::MAX as u128);
+    /// ```
+    #[inline]
+    pub fn total_u128(&self) -> u128 {
+        MultisetOps::total_u128(self.data)
+    }
+
+    /// The total of the multiset, accumulated as `u128`, or
+    /// [`Error::Overflow`] naming the element at which the total overflowed.
+    ///
+    /// See [`Multiset::checked_total`] for details.
+    #[inline]
+    pub fn checked_total(&self) -> Result<u128, Error> {
+        MultisetOps::checked_total(self.data)
+    }
+
+    /// Returns a tuple containing the element and a reference to the largest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[2u16, 0, 5, 3]);
+    /// assert_eq!(multiset.elem_count_max(), (2, &5));
+    /// ```
+    #[inline]
+    pub fn elem_count_max(&self) -> (usize, &'a N) {
+        MultisetOps::elem_count_max(self.data)
+    }
+
+    /// Returns the element with the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn elem_max(&self) -> usize {
+        self.elem_count_max().0
+    }
+
+    /// Returns a reference to the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn count_max(&self) -> &'a N {
+        self.elem_count_max().1
+    }
+
+    /// Returns a tuple containing the element and a reference to the smallest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[2u8, 0, 5, 3]);
+    /// assert_eq!(multiset.elem_count_min(), (1, &0));
+    /// ```
+    #[inline]
+    pub fn elem_count_min(&self) -> (usize, &'a N) {
+        MultisetOps::elem_count_min(self.data)
+    }
+
+    /// Returns the element with the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn elem_min(&self) -> usize {
+        self.elem_count_min().0
+    }
+
+    /// Returns a reference to the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn count_min(&self) -> &'a N {
+        self.elem_count_min().1
+    }
+
+    /// Calculate the collision entropy of the multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[2u8, 1, 1, 0]);
+    /// let result = multiset.collision_entropy();
+    /// // approximate: result == 1.415037499278844
+    /// ```
+    ///
+    /// # Warning
+    /// The total is accumulated with [`MultisetSlice::total_u128`], so it cannot
+    /// overflow, but it and the counts are converted to `f64` and so lose
+    /// precision above 2^53. The conversions are handled by
+    /// [`AsPrimitive<f64>`].
+    ///
+    /// [`AsPrimitive<f64>`]: num_traits::AsPrimitive
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn collision_entropy(&self) -> f64 {
+        MultisetOps::collision_entropy(self.data)
+    }
+
+    /// Calculate the shannon entropy of the multiset. Uses ln rather than log2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSlice;
+    ///
+    /// let multiset = MultisetSlice::new(&[2u8, 1, 1, 0]);
+    /// let result = multiset.shannon_entropy();
+    /// // approximate: result == 1.0397207708399179
+    /// ```
+    ///
+    /// # Warning
+    /// The total is accumulated with [`MultisetSlice::total_u128`], so it cannot
+    /// overflow, but it and the counts are converted to `f64` and so lose
+    /// precision above 2^53. The conversions are handled by
+    /// [`AsPrimitive<f64>`].
+    ///
+    /// [`AsPrimitive<f64>`]: num_traits::AsPrimitive
+    #[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn shannon_entropy(&self) -> f64 {
+        MultisetOps::shannon_entropy(self.data)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Inherent methods for MultisetSliceMut
+////////////////////////////////////////////////////////////////////////////////
+
+impl<'a, N: Counter> MultisetSliceMut<'a, N> {
+    /// Constructs a new MultisetSliceMut viewing `data`.
+    ///
+    /// This method is equivalent to calling [`MultisetSliceMut::from`] with a
+    /// slice of the correct type.
+    #[inline]
+    pub fn new(data: &'a mut [N]) -> MultisetSliceMut<'a, N> {
+        MultisetSliceMut { data }
+    }
+
+    /// Returns the number of elements in the multiset.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Returns a read only [`MultisetSlice`] view of this multiset.
+    ///
+    /// All the non-mutating methods of `MultisetSliceMut` are available
+    /// directly, so this is mostly useful for passing the counters to
+    /// functions which expect a `MultisetSlice`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{MultisetSlice, MultisetSliceMut};
+    ///
+    /// let mut data = [1u8, 2, 0];
+    /// let view = MultisetSliceMut::new(&mut data);
+    /// assert_eq!(view.as_multiset_slice(), MultisetSlice::new(&[1, 2, 0]));
+    /// ```
+    #[inline]
+    pub fn as_multiset_slice(&self) -> MultisetSlice<'_, N> {
+        MultisetSlice { data: self.data }
+    }
+
+    /// Returns the underlying slice of counters.
+    #[inline]
+    pub fn as_slice(&self) -> &[N] {
+        self.data
+    }
+
+    /// Returns the underlying mutable slice of counters.
+    #[inline]
+    pub fn as_mut_slice(&mut self) -> &mut [N] {
+        self.data
+    }
+
+    /// Return an [Iter](`std::slice::Iter`) of the element counts in the
+    /// MultisetSliceMut.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, N> {
+        self.data.iter()
+    }
+
+    /// Return a [IterMut](`std::slice::IterMut`) of the element counts in the
+    /// MultisetSliceMut.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, N> {
+        self.data.iter_mut()
+    }
+
+    /// Returns an iterator over the `(element, count)` pairs with a non-zero
+    /// count, in element order.
+    ///
+    /// See [`Multiset::iter_nonzero`] for details.
+    #[inline]
+    pub fn iter_nonzero(&self) -> IterNonZero<'_, N> {
+        IterNonZero::new(self.data)
+    }
+
+    /// Returns an iterator over the elements with a non-zero count, in
+    /// element order.
+    ///
+    /// See [`Multiset::support`] for details.
+    #[inline]
+    pub fn support(&self) -> Support<'_, N> {
+        Support::new(self.data)
+    }
+
+    /// Returns an iterator yielding each element as many times as its count,
+    /// in element order.
+    ///
+    /// See [`Multiset::elements`] for details.
+    #[inline]
+    pub fn elements(&self) -> Elements<'_, N> {
+        Elements::new(self.data)
+    }
+
+    /// Returns an iterator over all the `(element, count)` pairs, largest
+    /// count first.
+    ///
+    /// See [`Multiset::iter_by_count_desc`] for details.
+    #[inline]
+    pub fn iter_by_count_desc(&self) -> IterByCount<'_, N> {
+        IterByCount::descending(self.data)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// largest counts, largest first.
+    ///
+    /// See [`Multiset::top_k`] for details.
+    #[inline]
+    pub fn top_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::top_k(self.data, k)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// smallest counts, smallest first.
+    ///
+    /// See [`Multiset::bottom_k`] for details.
+    #[inline]
+    pub fn bottom_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::bottom_k(self.data, k)
+    }
+
+    /// Sets all element counts in the multiset to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSliceMut;
+    ///
+    /// let mut data = [1u8, 2, 3, 4];
+    /// MultisetSliceMut::new(&mut data).clear();
+    /// assert_eq!(data, [0, 0, 0, 0]);
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        MultisetOps::clear(&mut *self.data)
+    }
+
+    /// Returns `true` if `elem` has count > 0 in the multiset.
+    #[inline]
+    pub fn contains(&self, elem: usize) -> bool {
+        MultisetOps::contains(&*self.data, elem)
+    }
+
+    /// Set the count of `elem` in the multiset to `amount`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSliceMut;
+    ///
+    /// let mut data = [1u8, 2, 0, 0];
+    /// let mut multiset = MultisetSliceMut::new(&mut data);
+    /// multiset.insert(2, 5);
+    /// assert_eq!(multiset.get(2), Some(&5));
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, elem: usize, amount: N) {
+        MultisetOps::insert(&mut *self.data, elem, amount)
+    }
+
+    /// Set the count of `elem` in the multiset to `amount`, without doing
+    /// bounds checking.
+    ///
+    /// For a safe alternative see [`insert`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]*.
+    ///
+    /// [`insert`]: MultisetSliceMut::insert
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    #[inline]
+    pub unsafe fn insert_unchecked(&mut self, elem: usize, amount: N) {
+        *self.get_unchecked_mut(elem) = amount
+    }
+
+    /// Set the count of `elem` in the multiset to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSliceMut;
+    ///
+    /// let mut data = [1u8, 2, 0, 0];
+    /// let mut multiset = MultisetSliceMut::new(&mut data);
+    /// multiset.remove(1);
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, elem: usize) {
+        MultisetOps::remove(&mut *self.data, elem)
+    }
+
+    /// Set the count of `elem` in the multiset to zero, without doing bounds
+    /// checking.
+    ///
+    /// For a safe alternative see [`remove`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]*.
+    ///
+    /// [`remove`]: MultisetSliceMut::remove
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    #[inline]
+    pub unsafe fn remove_unchecked(&mut self, elem: usize) {
+        *self.get_unchecked_mut(elem) = N::zero()
+    }
+
+    /// Returns a reference to a count or subslice of counts depending on the
+    /// type of index, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get<I>(&self, index: I) -> Option<&I::Output>
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get(index)
+    }
+
+    /// Returns a mutable reference to a count or subslice of counts depending
+    /// on the type of index, or `None` if the index is out of bounds.
+    #[inline]
+    pub fn get_mut<I>(&mut self, index: I) -> Option<&mut I::Output>
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get_mut(index)
+    }
+
+    /// Returns a reference to a count or subslice of counts, without doing
+    /// bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]* even if the resulting reference is not used.
+    ///
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    #[inline]
+    pub unsafe fn get_unchecked<I>(&self, index: I) -> &I::Output
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get_unchecked(index)
+    }
+
+    /// Returns a mutable reference to a count or subslice of counts, without
+    /// doing bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]* even if the resulting reference is not used.
+    ///
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    #[inline]
+    pub unsafe fn get_unchecked_mut<I>(&mut self, index: I) -> &mut I::Output
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get_unchecked_mut(index)
+    }
+
+    /// Returns `true` if all elements have a count of zero.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        MultisetOps::is_empty(&*self.data)
+    }
+
+    /// The total of the multiset, accumulated as `u128`.
+    ///
+    /// See [`Multiset::total_u128`] for details.
+    #[inline]
+    pub fn total_u128(&self) -> u128 {
+        MultisetOps::total_u128(&*self.data)
+    }
+
+    /// The total of the multiset, accumulated as `u128`, or
+    /// [`Error::Overflow`] naming the element at which the total overflowed.
+    ///
+    /// See [`Multiset::checked_total`] for details.
+    #[inline]
+    pub fn checked_total(&self) -> Result<u128, Error> {
+        MultisetOps::checked_total(&*self.data)
+    }
+
+    /// Returns a tuple containing the element and a reference to the largest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn elem_count_max(&self) -> (usize, &N) {
+        MultisetOps::elem_count_max(&*self.data)
+    }
+
+    /// Returns the element with the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn elem_max(&self) -> usize {
+        self.elem_count_max().0
+    }
+
+    /// Returns a reference to the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn count_max(&self) -> &N {
+        self.elem_count_max().1
+    }
+
+    /// Returns a tuple containing the element and a reference to the smallest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn elem_count_min(&self) -> (usize, &N) {
+        MultisetOps::elem_count_min(&*self.data)
+    }
+
+    /// Returns the element with the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn elem_min(&self) -> usize {
+        self.elem_count_min().0
+    }
+
+    /// Returns a reference to the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    #[inline]
+    pub fn count_min(&self) -> &N {
+        self.elem_count_min().1
+    }
+
+    /// Set all element counts, except for the given `elem`, to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSliceMut;
+    ///
+    /// let mut data = [2u8, 0, 5, 3];
+    /// MultisetSliceMut::new(&mut data).choose(2);
+    /// assert_eq!(data, [0, 0, 5, 0]);
+    /// ```
+    #[inline]
+    pub fn choose(&mut self, elem: usize) {
+        MultisetOps::choose(&mut *self.data, elem)
+    }
+
+    /// Set all element counts, except for a random choice, to zero.
+    ///
+    /// The choice is weighted by the counts of the elements, and unless the
+    /// multiset is empty an element with non-zero count will always be chosen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetSliceMut;
+    /// use rand::prelude::*;
+    ///
+    /// let rng = &mut StdRng::seed_from_u64(thread_rng().next_u64());
+    /// let mut data = [2u8, 0, 5, 3];
+    /// let mut multiset = MultisetSliceMut::new(&mut data);
+    /// multiset.choose_random(rng);
+    /// assert_eq!(multiset.is_singleton(), true);
+    /// ```
+    #[cfg(feature = "rand")]
+    #[inline]
+    pub fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
+        MultisetOps::choose_random(&mut *self.data, rng)
+    }
+}
+
+// The simd implementations on MultisetSlice require an extra bound, so the
+// methods of MultisetSliceMut which use them are generated for both cases.
+macro_rules! impl_slice_mut_delegates {
+    ($($bound:tt)*) => {
+        impl<'a, N: Counter> MultisetSliceMut<'a, N>
+        where
+            $($bound)*
+        {
+            /// Returns a multiset which is the intersection of `self` and
+            /// `other`, or an error if their lengths differ.
+            ///
+            /// See [`Multiset::intersection`] for details.
+            #[inline]
+            pub fn intersection(
+                &self,
+                other: &MultisetSlice<'_, N>,
+            ) -> Result<MultisetVec<N>, Error> {
+                self.as_multiset_slice().intersection(other)
+            }
+
+            /// Returns a multiset which is the union of `self` and `other`,
+            /// or an error if their lengths differ.
+            ///
+            /// See [`Multiset::union`] for details.
+            #[inline]
+            pub fn union(&self, other: &MultisetSlice<'_, N>) -> Result<MultisetVec<N>, Error> {
+                self.as_multiset_slice().union(other)
+            }
+
+            /// Returns a multiset which is the difference of `self` and
+            /// `other`, or an error if their lengths differ.
+            ///
+            /// See [`Multiset::difference`] for details.
+            #[inline]
+            pub fn difference(
+                &self,
+                other: &MultisetSlice<'_, N>,
+            ) -> Result<MultisetVec<N>, Error> {
+                self.as_multiset_slice().difference(other)
+            }
+
+            /// Returns a multiset which is the symmetric_difference of `self`
+            /// and `other`, or an error if their lengths differ.
+            ///
+            /// See [`Multiset::symmetric_difference`] for details.
+            #[inline]
+            pub fn symmetric_difference(
+                &self,
+                other: &MultisetSlice<'_, N>,
+            ) -> Result<MultisetVec<N>, Error> {
+                self.as_multiset_slice().symmetric_difference(other)
+            }
+
+            /// Returns the number of elements whose count is non-zero.
+            #[inline]
+            pub fn count_non_zero(&self) -> usize {
+                self.as_multiset_slice().count_non_zero()
+            }
+
+            /// Returns the number of elements whose count is zero.
+            #[inline]
+            pub fn count_zero(&self) -> usize {
+                self.as_multiset_slice().count_zero()
+            }
+
+            /// Returns `true` if only one element in the multiset has a
+            /// non-zero count.
+            #[inline]
+            pub fn is_singleton(&self) -> bool {
+                self.as_multiset_slice().is_singleton()
+            }
+
+            /// Returns `true` if `self` is disjoint to `other`, or an error
+            /// if their lengths differ.
+            #[inline]
+            pub fn is_disjoint(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+                self.as_multiset_slice().is_disjoint(other)
+            }
+
+            /// Returns `true` if `self` is a subset of `other`, or an error
+            /// if their lengths differ.
+            #[inline]
+            pub fn is_subset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+                self.as_multiset_slice().is_subset(other)
+            }
+
+            /// Returns `true` if `self` is a superset of `other`, or an error
+            /// if their lengths differ.
+            #[inline]
+            pub fn is_superset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+                self.as_multiset_slice().is_superset(other)
+            }
+
+            /// Returns `true` if `self` is a proper subset of `other`, or an
+            /// error if their lengths differ.
+            #[inline]
+            pub fn is_proper_subset(
+                &self,
+                other: &MultisetSlice<'_, N>,
+            ) -> Result<bool, Error> {
+                self.as_multiset_slice().is_proper_subset(other)
+            }
+
+            /// Returns `true` if `self` is a proper superset of `other`, or
+            /// an error if their lengths differ.
+            #[inline]
+            pub fn is_proper_superset(
+                &self,
+                other: &MultisetSlice<'_, N>,
+            ) -> Result<bool, Error> {
+                self.as_multiset_slice().is_proper_superset(other)
+            }
+
+            /// Returns `true` if any element count in `self` is less than
+            /// that element count in `other`, or an error if their lengths
+            /// differ.
+            #[inline]
+            pub fn is_any_lesser(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+                self.as_multiset_slice().is_any_lesser(other)
+            }
+
+            /// Returns `true` if any element count in `self` is greater than
+            /// that element count in `other`, or an error if their lengths
+            /// differ.
+            #[inline]
+            pub fn is_any_greater(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+                self.as_multiset_slice().is_any_greater(other)
+            }
+
+            /// The total or cardinality of a multiset is the sum of all
+            /// element counts.
+            #[inline]
+            pub fn total(&self) -> usize {
+                self.as_multiset_slice().total()
+            }
+
+            /// Calculate the collision entropy of the multiset.
+            #[cfg(any(feature = "std", feature = "libm"))]
+            #[inline]
+            pub fn collision_entropy(&self) -> f64 {
+                self.as_multiset_slice().collision_entropy()
+            }
+
+            /// Calculate the shannon entropy of the multiset. Uses ln rather
+            /// than log2.
+            #[cfg(any(feature = "std", feature = "libm"))]
+            #[inline]
+            pub fn shannon_entropy(&self) -> f64 {
+                self.as_multiset_slice().shannon_entropy()
+            }
+        }
+    };
+}
+
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+impl_slice_mut_delegates!();
+
+#[cfg(any(feature = "simd", feature = "portable_simd"))]
+impl_slice_mut_delegates!([(); N::L128 * N::L256 * N::LF]: Sized);
+
+////////////////////////////////////////////////////////////////////////////////
+// MultisetOps for MultisetSliceMut
+////////////////////////////////////////////////////////////////////////////////
+
+// A shared MultisetSlice cannot provide the mutating half of MultisetOps, so
+// generic code over read only views should use the impl for `[N]` through
+// `MultisetSlice::as_slice`.
+macro_rules! impl_multiset_ops_for_slice_mut {
+    ($($bound:tt)*) => {
+        impl<'a, N: Counter> MultisetOps<N> for MultisetSliceMut<'a, N>
+        where
+            $($bound)*
+        {
+            type Owned = MultisetVec<N>;
+
+            #[inline]
+            fn len(&self) -> usize {
+                MultisetSliceMut::len(self)
+            }
+
+            #[inline]
+            fn iter(&self) -> Iter<'_, N> {
+                MultisetSliceMut::iter(self)
+            }
+
+            #[inline]
+            fn iter_mut(&mut self) -> IterMut<'_, N> {
+                MultisetSliceMut::iter_mut(self)
+            }
+
+            #[inline]
+            fn clear(&mut self) {
+                MultisetSliceMut::clear(self)
+            }
+
+            #[inline]
+            fn contains(&self, elem: usize) -> bool {
+                MultisetSliceMut::contains(self, elem)
+            }
+
+            #[inline]
+            fn insert(&mut self, elem: usize, amount: N) {
+                MultisetSliceMut::insert(self, elem, amount)
+            }
+
+            #[inline]
+            fn remove(&mut self, elem: usize) {
+                MultisetSliceMut::remove(self, elem)
+            }
+
+            #[inline]
+            fn intersection(&self, other: &Self) -> Result<Self::Owned, Error> {
+                MultisetSliceMut::intersection(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn union(&self, other: &Self) -> Result<Self::Owned, Error> {
+                MultisetSliceMut::union(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+                MultisetSliceMut::difference(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn symmetric_difference(&self, other: &Self) -> Result<Self::Owned, Error> {
+                MultisetSliceMut::symmetric_difference(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn count_non_zero(&self) -> usize {
+                MultisetSliceMut::count_non_zero(self)
+            }
+
+            #[inline]
+            fn count_zero(&self) -> usize {
+                MultisetSliceMut::count_zero(self)
+            }
+
+            #[inline]
+            fn is_singleton(&self) -> bool {
+                MultisetSliceMut::is_singleton(self)
+            }
+
+            #[inline]
+            fn is_disjoint(&self, other: &Self) -> Result<bool, Error> {
+                MultisetSliceMut::is_disjoint(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn is_subset(&self, other: &Self) -> Result<bool, Error> {
+                MultisetSliceMut::is_subset(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn is_superset(&self, other: &Self) -> Result<bool, Error> {
+                MultisetSliceMut::is_superset(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn is_proper_subset(&self, other: &Self) -> Result<bool, Error> {
+                MultisetSliceMut::is_proper_subset(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn is_proper_superset(&self, other: &Self) -> Result<bool, Error> {
+                MultisetSliceMut::is_proper_superset(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn is_any_lesser(&self, other: &Self) -> Result<bool, Error> {
+                MultisetSliceMut::is_any_lesser(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn is_any_greater(&self, other: &Self) -> Result<bool, Error> {
+                MultisetSliceMut::is_any_greater(self, &other.as_multiset_slice())
+            }
+
+            #[inline]
+            fn is_empty(&self) -> bool {
+                MultisetSliceMut::is_empty(self)
+            }
+
+            #[inline]
+            fn total(&self) -> usize {
+                MultisetSliceMut::total(self)
+            }
+
+            #[inline]
+            fn elem_count_max(&self) -> (usize, &N) {
+                MultisetSliceMut::elem_count_max(self)
+            }
+
+            #[inline]
+            fn elem_count_min(&self) -> (usize, &N) {
+                MultisetSliceMut::elem_count_min(self)
+            }
+
+            #[inline]
+            fn choose(&mut self, elem: usize) {
+                MultisetSliceMut::choose(self, elem)
+            }
+
+            #[cfg(feature = "rand")]
+            #[inline]
+            fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
+                MultisetSliceMut::choose_random(self, rng)
+            }
+
+            #[cfg(any(feature = "std", feature = "libm"))]
+            #[inline]
+            fn collision_entropy(&self) -> f64 {
+                MultisetSliceMut::collision_entropy(self)
+            }
+
+            #[cfg(any(feature = "std", feature = "libm"))]
+            #[inline]
+            fn shannon_entropy(&self) -> f64 {
+                MultisetSliceMut::shannon_entropy(self)
+            }
+        }
+    };
+}
+
+#[cfg(not(any(feature = "simd", feature = "portable_simd")))]
+impl_multiset_ops_for_slice_mut!();
+
+#[cfg(any(feature = "simd", feature = "portable_simd"))]
+impl_multiset_ops_for_slice_mut!([(); N::L128 * N::L256 * N::LF]: Sized);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use approx::assert_relative_eq;
+    #[cfg(feature = "rand")]
+    use rand::prelude::*;
+    use std::panic;
+
+    fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(
+        f: F,
+    ) -> std::thread::Result<R> {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(f);
+        panic::set_hook(prev_hook);
+        result
+    }
+
+    fn mismatch(expected: usize, found: usize) -> Error {
+        Error::LengthMismatch { expected, found }
+    }
+
+    #[test]
+    fn test_index() {
+        let data = [1u8, 2, 3, 4];
+        let set = MultisetSlice::new(&data);
+        assert_eq!(set[1], 2);
+        assert_eq!(&set[2..], &[3, 4]);
+
+        let mut data = [1u8, 2, 3, 4];
+        let mut set = MultisetSliceMut::new(&mut data);
+        set[1] = 5;
+        assert_eq!(set[1], 5);
+        assert_eq!(data, [1, 5, 3, 4]);
+    }
+
+    #[test]
+    fn test_conversions() {
+        let mut multiset = Multiset::from([1u8, 2, 3]);
+        let view = MultisetSlice::from(&multiset);
+        assert_eq!(view.as_slice(), &[1, 2, 3]);
+        assert_eq!(MultisetVec::from(view), MultisetVec::from(vec![1u8, 2, 3]));
+        assert_eq!(Multiset::<u8, 3>::try_from(view), Ok(multiset));
+        assert_eq!(Multiset::<u8, 2>::try_from(view), Err(mismatch(2, 3)));
+
+        let ptr = <&Multiset<u8, 3>>::try_from(view).unwrap() as *const Multiset<u8, 3>;
+        assert_eq!(ptr, &multiset as *const Multiset<u8, 3>);
+
+        let view = MultisetSliceMut::from(&mut multiset);
+        let back = <&mut Multiset<u8, 3>>::try_from(view).unwrap();
+        back.insert(0, 9);
+        assert_eq!(multiset, Multiset::from([9, 2, 3]));
+
+        let view = MultisetSliceMut::from(&mut multiset);
+        assert_eq!(
+            <&mut Multiset<u8, 4>>::try_from(view).map(|ms| *ms),
+            Err(mismatch(4, 3))
+        );
+
+        let mut vec = MultisetVec::from(vec![1u8, 0]);
+        MultisetSliceMut::from(&mut vec).insert(1, 4);
+        assert_eq!(MultisetSlice::from(&vec), MultisetSlice::new(&[1, 4]));
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut data = [1u8, 2, 3];
+        let view = MultisetSlice::new(&data);
+        assert_eq!(view.into_iter().sum::<u8>(), 6);
+
+        let mut view = MultisetSliceMut::new(&mut data);
+        for elem in &mut view {
+            *elem *= 2;
+        }
+        assert_eq!(
+            (&view).into_iter().copied().collect::<Vec<_>>(),
+            vec![2, 4, 6]
+        );
+        view.into_iter().for_each(|elem| *elem = 0);
+        assert_eq!(data, [0, 0, 0]);
+    }
+
+    #[test]
+    fn test_ops() {
+        let a = MultisetSlice::new(&[1u8, 2, 3]);
+        let b = MultisetSlice::new(&[2u8, 2, 1]);
+        assert_eq!(a + b, MultisetVec::from(vec![3, 4, 4]));
+        assert_eq!(
+            a - MultisetSlice::new(&[1, 1, 1]),
+            MultisetVec::from(vec![0, 1, 2])
+        );
+        assert_eq!(a * 2, MultisetVec::from(vec![2, 4, 6]));
+
+        let mut data = [1u8, 2, 3];
+        let mut c = MultisetSliceMut::new(&mut data);
+        c += b;
+        c *= 2;
+        c %= 5;
+        assert_eq!(c.as_slice(), &[1, 3, 3]);
+        c -= 1;
+        c /= MultisetSlice::new(&[1, 2, 1]);
+        assert_eq!(data, [0, 1, 2]);
+    }
+
+    #[test]
+    fn test_ops_length_mismatch() {
+        let result = catch_unwind_silent(|| {
+            let mut data = [1u8, 2, 3];
+            let mut a = MultisetSliceMut::new(&mut data);
+            a += MultisetSlice::new(&[1, 2]);
+        });
+        assert!(result.is_err());
+        let result = catch_unwind_silent(|| {
+            let _ = MultisetSlice::new(&[1u8, 2, 3]) + MultisetSlice::new(&[1, 2]);
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_contains_get() {
+        let set = MultisetSlice::new(&[1u8, 0, 3]);
+        assert!(set.contains(0));
+        assert!(!set.contains(1));
+        assert!(!set.contains(3));
+        assert_eq!(set.get(2), Some(&3));
+        assert_eq!(set.get(3), None);
+        unsafe {
+            assert!(set.contains_unchecked(2));
+            assert_eq!(set.get_unchecked(0), &1);
+        }
+    }
+
+    #[test]
+    fn test_mutators() {
+        let mut data = [1u8, 2, 0, 0];
+        let mut set = MultisetSliceMut::new(&mut data);
+        set.insert(3, 7);
+        assert!(set.contains(3));
+        set.remove(0);
+        assert_eq!(set.get(0), Some(&0));
+        *set.get_mut(1).unwrap() = 4;
+        unsafe {
+            set.insert_unchecked(2, 1);
+            set.remove_unchecked(2);
+            assert_eq!(set.get_unchecked(2), &0);
+            *set.get_unchecked_mut(2) = 2;
+        }
+        set.iter_mut().for_each(|elem| *elem += 1);
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), vec![1, 5, 3, 8]);
+        set.choose(1);
+        assert!(set.is_singleton());
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(data, [0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_set_ops() {
+        let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+        let b = MultisetSlice::new(&[0u8, 1, 3, 0]);
+        let c = MultisetSlice::new(&[0u8, 1]);
+        assert_eq!(a.intersection(&b), Ok(MultisetVec::from(vec![0, 1, 0, 0])));
+        assert_eq!(a.union(&b), Ok(MultisetVec::from(vec![1, 2, 3, 0])));
+        assert_eq!(a.difference(&b), Ok(MultisetVec::from(vec![1, 1, 0, 0])));
+        assert_eq!(
+            a.symmetric_difference(&b),
+            Ok(MultisetVec::from(vec![1, 1, 3, 0]))
+        );
+        assert_eq!(a.intersection(&c), Err(mismatch(4, 2)));
+        assert_eq!(a.union(&c), Err(mismatch(4, 2)));
+        assert_eq!(a.difference(&c), Err(mismatch(4, 2)));
+        assert_eq!(a.symmetric_difference(&c), Err(mismatch(4, 2)));
+
+        let mut data = [1u8, 2, 0, 0];
+        let d = MultisetSliceMut::new(&mut data);
+        assert_eq!(d.union(&b), a.union(&b));
+        assert_eq!(d.intersection(&c), Err(mismatch(4, 2)));
+    }
+
+    #[test]
+    fn test_predicates() {
+        let a = MultisetSlice::new(&[1u8, 2, 0, 0]);
+        let b = MultisetSlice::new(&[1u8, 3, 0, 0]);
+        let c = MultisetSlice::new(&[0u8, 0, 3, 4]);
+        let short = MultisetSlice::new(&[0u8]);
+        assert_eq!(a.is_disjoint(&c), Ok(true));
+        assert_eq!(a.is_disjoint(&b), Ok(false));
+        assert_eq!(a.is_subset(&b), Ok(true));
+        assert_eq!(a.is_superset(&b), Ok(false));
+        assert_eq!(b.is_proper_superset(&a), Ok(true));
+        assert_eq!(a.is_proper_subset(&a), Ok(false));
+        assert_eq!(a.is_any_lesser(&b), Ok(true));
+        assert_eq!(a.is_any_greater(&b), Ok(false));
+        assert_eq!(a.is_subset(&short), Err(mismatch(4, 1)));
+        assert_eq!(a.is_disjoint(&short), Err(mismatch(4, 1)));
+        assert_eq!(a.is_any_greater(&short), Err(mismatch(4, 1)));
+
+        let mut data = [1u8, 3, 0, 0];
+        let d = MultisetSliceMut::new(&mut data);
+        assert_eq!(d.is_superset(&a), Ok(true));
+        assert_eq!(d.is_proper_subset(&short), Err(mismatch(4, 1)));
+    }
+
+    #[test]
+    fn test_stats() {
+        let set = MultisetSlice::new(&[2u8, 0, 5, 3]);
+        assert_eq!(set.len(), 4);
+        assert_eq!(set.total(), 10);
+        assert_eq!(set.count_non_zero(), 3);
+        assert_eq!(set.count_zero(), 1);
+        assert!(!set.is_singleton());
+        assert!(!set.is_empty());
+        assert_eq!(set.elem_count_max(), (2, &5));
+        assert_eq!(set.elem_max(), 2);
+        assert_eq!(set.count_max(), &5);
+        assert_eq!(set.elem_count_min(), (1, &0));
+        assert_eq!(set.elem_min(), 1);
+        assert_eq!(set.count_min(), &0);
+
+        let mut data = [2u8, 0, 5, 3];
+        let set = MultisetSliceMut::new(&mut data);
+        assert_eq!(set.total(), 10);
+        assert_eq!(set.count_zero(), 1);
+        assert_eq!(set.elem_max(), 2);
+        assert_eq!(set.elem_min(), 1);
+    }
+
+    #[test]
+    fn test_empty_view_panics() {
+        let result = catch_unwind_silent(|| MultisetSlice::<u8>::new(&[]).elem_max());
+        assert!(result.is_err());
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_entropy() {
+        let set = MultisetSlice::new(&[2u8, 1, 1, 0]);
+        assert_relative_eq!(set.collision_entropy(), 1.415037499278844);
+        assert_relative_eq!(set.shannon_entropy(), 1.0397207708399179);
+
+        let mut data = [2u8, 1, 1, 0];
+        let set = MultisetSliceMut::new(&mut data);
+        assert_relative_eq!(set.collision_entropy(), 1.415037499278844);
+        assert_relative_eq!(set.shannon_entropy(), 1.0397207708399179);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_choose_random() {
+        let rng = &mut StdRng::seed_from_u64(thread_rng().next_u64());
+        let mut data = [2u8, 0, 5, 3];
+        let mut set = MultisetSliceMut::new(&mut data);
+        set.choose_random(rng);
+        assert!(set.is_singleton());
+        assert_eq!(set.count_zero(), 3);
+        assert_eq!(set.get(1), Some(&0));
+    }
+}
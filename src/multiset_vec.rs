@@ -0,0 +1,2954 @@
+use crate::iter::{Elements, IterByCount, IterNonZero, Support};
+use crate::kernels;
+use crate::multiset_ops::check_same_len;
+use crate::{Counter, Error, Multiset, MultisetOps};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt::{self, Debug, Formatter};
+use core::hash::{Hash, Hasher};
+use core::iter::FromIterator;
+use core::ops::{
+    Add, AddAssign, Div, DivAssign, Index, IndexMut, Mul, MulAssign, Rem, RemAssign, Sub, SubAssign,
+};
+use core::slice::{Iter, IterMut, SliceIndex};
+#[cfg(feature = "rand")]
+use rand::RngCore;
+
+/// A heap allocated multiset of unsigned integers.
+///
+/// `MultisetVec` is the runtime sized counterpart to [`Multiset`]: the number
+/// of elements is chosen when the multiset is constructed rather than at
+/// compile time. As with `Multiset`, each index corresponds to an element in
+/// the multiset and the value at that index is its count.
+///
+/// Since two `MultisetVec`s are not guaranteed to have the same number of
+/// elements, methods which combine two multisets return a `Result`, with
+/// [`Error::LengthMismatch`] when the lengths differ.
+///
+/// # Examples
+///
+/// ```
+/// use utote::MultisetVec;
+///
+/// // A multiset of 5 elements, which can be counted up to u8::MAX
+/// let mut multiset = MultisetVec::from(vec![0u8, 3, 4, 0, 5]);
+/// assert_eq!(multiset.total(), 12);
+///
+/// multiset.insert(2, 6);
+/// assert_eq!(multiset, MultisetVec::from(vec![0, 3, 6, 0, 5]));
+///
+/// assert_eq!(multiset.contains(0), false);
+/// assert_eq!(multiset.contains(1), true);
+/// ```
+///
+/// Some common set-like operations:
+///
+/// ```
+/// use utote::MultisetVec;
+///
+/// let ms_sub = MultisetVec::from(vec![0u32, 1, 1]);
+/// let ms_super = MultisetVec::from(vec![1, 1, 2]);
+///
+/// assert_eq!(ms_sub.is_subset(&ms_super), Ok(true));
+///
+/// assert_eq!(ms_sub.union(&ms_super), Ok(MultisetVec::from(vec![1, 1, 2])));
+///
+/// // Multisets of different lengths cannot be combined.
+/// let short = MultisetVec::from(vec![1u32, 1]);
+/// assert!(ms_sub.union(&short).is_err());
+/// ```
+///
+/// # Arithmetic
+///
+/// The arithmetic operators are implemented elementwise, as they are for
+/// `Multiset`. Since operators cannot return an error, they panic if the
+/// lengths of the operands differ.
+///
+/// ```
+/// use utote::MultisetVec;
+///
+/// let a = MultisetVec::from(vec![1u8, 2, 3]);
+/// let b = MultisetVec::from(vec![3u8, 2, 1]);
+/// assert_eq!(a + b, MultisetVec::from(vec![4, 4, 4]));
+/// ```
+#[derive(Default)]
+pub struct MultisetVec<N: Counter> {
+    pub(crate) data: Vec<N>,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common trait implementations for MultisetVec
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter> Debug for MultisetVec<N> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultisetVec")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+impl<N: Counter> Hash for MultisetVec<N> {
+    #[inline]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.data.hash(state)
+    }
+}
+
+impl<N: Counter> Clone for MultisetVec<N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        MultisetVec {
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl<N: Counter> PartialEq for MultisetVec<N> {
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.data == other.data
+    }
+}
+
+impl<N: Counter> Eq for MultisetVec<N> {}
+
+impl<N: Counter, I: SliceIndex<[N]>> Index<I> for MultisetVec<N> {
+    type Output = I::Output;
+
+    #[inline]
+    fn index(&self, index: I) -> &Self::Output {
+        Index::index(&self.data, index)
+    }
+}
+
+impl<N: Counter, I: SliceIndex<[N]>> IndexMut<I> for MultisetVec<N> {
+    #[inline]
+    fn index_mut(&mut self, index: I) -> &mut Self::Output {
+        IndexMut::index_mut(&mut self.data, index)
+    }
+}
+
+impl<N: Counter> FromIterator<N> for MultisetVec<N> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = N>>(iter: T) -> Self {
+        MultisetVec {
+            data: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<'a, N: 'a + Counter> FromIterator<&'a N> for MultisetVec<N> {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = &'a N>>(iter: T) -> Self {
+        iter.into_iter().copied().collect()
+    }
+}
+
+// Panics like `from_elements` when an element is out of bounds, and when a
+// count would overflow.
+impl<N: Counter> Extend<usize> for MultisetVec<N> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        self.extend_elements(iter)
+    }
+}
+
+impl<'a, N: Counter> Extend<&'a usize> for MultisetVec<N> {
+    #[inline]
+    fn extend<I: IntoIterator<Item = &'a usize>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied())
+    }
+}
+
+impl<N: Counter> IntoIterator for MultisetVec<N> {
+    type Item = N;
+    type IntoIter = alloc::vec::IntoIter<N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<'a, N: Counter> IntoIterator for &'a MultisetVec<N> {
+    type Item = &'a N;
+    type IntoIter = Iter<'a, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, N: Counter> IntoIterator for &'a mut MultisetVec<N> {
+    type Item = &'a mut N;
+    type IntoIter = IterMut<'a, N>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<N: Counter> From<Vec<N>> for MultisetVec<N> {
+    #[inline]
+    fn from(data: Vec<N>) -> Self {
+        MultisetVec { data }
+    }
+}
+
+impl<N: Counter> From<MultisetVec<N>> for Vec<N> {
+    #[inline]
+    fn from(set: MultisetVec<N>) -> Self {
+        set.data
+    }
+}
+
+impl<N: Counter> From<&[N]> for MultisetVec<N> {
+    #[inline]
+    fn from(slice: &[N]) -> Self {
+        MultisetVec {
+            data: slice.to_vec(),
+        }
+    }
+}
+
+impl<N: Counter, const SIZE: usize> From<[N; SIZE]> for MultisetVec<N> {
+    #[inline]
+    fn from(data: [N; SIZE]) -> Self {
+        MultisetVec {
+            data: data.to_vec(),
+        }
+    }
+}
+
+impl<N: Counter, const SIZE: usize> From<Multiset<N, SIZE>> for MultisetVec<N> {
+    #[inline]
+    fn from(set: Multiset<N, SIZE>) -> Self {
+        MultisetVec {
+            data: set.data.to_vec(),
+        }
+    }
+}
+
+impl<N: Counter, const SIZE: usize> TryFrom<MultisetVec<N>> for Multiset<N, SIZE> {
+    type Error = Error;
+
+    #[inline]
+    fn try_from(set: MultisetVec<N>) -> Result<Self, Self::Error> {
+        Multiset::try_from(set.data.as_slice())
+    }
+}
+
+impl<'a, N: Counter> From<&'a MultisetVec<N>> for &'a [N] {
+    #[inline]
+    fn from(set: &'a MultisetVec<N>) -> Self {
+        &set.data
+    }
+}
+
+impl<'a, N: Counter> From<&'a mut MultisetVec<N>> for &'a mut [N] {
+    #[inline]
+    fn from(set: &'a mut MultisetVec<N>) -> Self {
+        &mut set.data
+    }
+}
+
+/// Partial order based on proper sub/super sets. Multisets of different
+/// lengths are not comparable.
+impl<N: Counter> PartialOrd for MultisetVec<N> {
+    #[inline]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        if self.len() != other.len() {
+            return None;
+        }
+        let mut order: Ordering = Ordering::Equal;
+        for (e_self, e_other) in self.iter().zip(other.iter()) {
+            match order {
+                Ordering::Equal if e_self < e_other => order = Ordering::Less,
+                Ordering::Equal if e_self > e_other => order = Ordering::Greater,
+                Ordering::Less if e_self > e_other => return None,
+                Ordering::Greater if e_self < e_other => return None,
+                _ => (),
+            }
+        }
+        Some(order)
+    }
+
+    #[inline]
+    fn lt(&self, other: &Self) -> bool {
+        self.is_proper_subset(other).unwrap_or(false)
+    }
+
+    #[inline]
+    fn le(&self, other: &Self) -> bool {
+        self.is_subset(other).unwrap_or(false)
+    }
+
+    #[inline]
+    fn gt(&self, other: &Self) -> bool {
+        self.is_proper_superset(other).unwrap_or(false)
+    }
+
+    #[inline]
+    fn ge(&self, other: &Self) -> bool {
+        self.is_superset(other).unwrap_or(false)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Common ops implementations for MultisetVec
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter> Add for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn add(mut self, rhs: Self) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<N: Counter> Add<N> for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn add(mut self, rhs: N) -> Self::Output {
+        self += rhs;
+        self
+    }
+}
+
+impl<N: Counter> AddAssign for MultisetVec<N> {
+    #[inline]
+    fn add_assign(&mut self, rhs: Self) {
+        self.assert_same_len(&rhs);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l += r);
+    }
+}
+
+impl<N: Counter> AddAssign<N> for MultisetVec<N> {
+    #[inline]
+    fn add_assign(&mut self, rhs: N) {
+        self.iter_mut().for_each(|l| *l += rhs);
+    }
+}
+
+impl<N: Counter> Div for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn div(mut self, rhs: Self) -> Self::Output {
+        self /= rhs;
+        self
+    }
+}
+
+impl<N: Counter> Div<N> for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn div(mut self, rhs: N) -> Self::Output {
+        self /= rhs;
+        self
+    }
+}
+
+impl<N: Counter> DivAssign for MultisetVec<N> {
+    #[inline]
+    fn div_assign(&mut self, rhs: Self) {
+        self.assert_same_len(&rhs);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l /= r);
+    }
+}
+
+impl<N: Counter> DivAssign<N> for MultisetVec<N> {
+    #[inline]
+    fn div_assign(&mut self, rhs: N) {
+        self.iter_mut().for_each(|l| *l /= rhs);
+    }
+}
+
+impl<N: Counter> Mul for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn mul(mut self, rhs: Self) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<N: Counter> Mul<N> for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn mul(mut self, rhs: N) -> Self::Output {
+        self *= rhs;
+        self
+    }
+}
+
+impl<N: Counter> MulAssign for MultisetVec<N> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: Self) {
+        self.assert_same_len(&rhs);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l *= r);
+    }
+}
+
+impl<N: Counter> MulAssign<N> for MultisetVec<N> {
+    #[inline]
+    fn mul_assign(&mut self, rhs: N) {
+        self.iter_mut().for_each(|l| *l *= rhs);
+    }
+}
+
+impl<N: Counter> Rem for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn rem(mut self, rhs: Self) -> Self::Output {
+        self %= rhs;
+        self
+    }
+}
+
+impl<N: Counter> Rem<N> for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn rem(mut self, rhs: N) -> Self::Output {
+        self %= rhs;
+        self
+    }
+}
+
+impl<N: Counter> RemAssign for MultisetVec<N> {
+    #[inline]
+    fn rem_assign(&mut self, rhs: Self) {
+        self.assert_same_len(&rhs);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l %= r);
+    }
+}
+
+impl<N: Counter> RemAssign<N> for MultisetVec<N> {
+    #[inline]
+    fn rem_assign(&mut self, rhs: N) {
+        self.iter_mut().for_each(|l| *l %= rhs);
+    }
+}
+
+impl<N: Counter> Sub for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn sub(mut self, rhs: Self) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<N: Counter> Sub<N> for MultisetVec<N> {
+    type Output = MultisetVec<N>;
+
+    #[inline]
+    fn sub(mut self, rhs: N) -> Self::Output {
+        self -= rhs;
+        self
+    }
+}
+
+impl<N: Counter> SubAssign for MultisetVec<N> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: Self) {
+        self.assert_same_len(&rhs);
+        self.iter_mut().zip(rhs).for_each(|(l, r)| *l -= r);
+    }
+}
+
+impl<N: Counter> SubAssign<N> for MultisetVec<N> {
+    #[inline]
+    fn sub_assign(&mut self, rhs: N) {
+        self.iter_mut().for_each(|l| *l -= rhs);
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Inherent methods
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter> MultisetVec<N> {
+    /// Constructs a new MultisetVec.
+    ///
+    /// This method is equivalent to calling [`MultisetVec::from`] with a
+    /// `Vec` of the correct type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::new(vec![1u8, 2, 3, 4]);
+    /// let multiset_from = MultisetVec::from(vec![1, 2, 3, 4]);
+    /// assert_eq!(multiset, multiset_from);
+    /// ```
+    #[inline]
+    pub fn new(data: Vec<N>) -> MultisetVec<N> {
+        MultisetVec { data }
+    }
+
+    /// Returns the number of elements in the multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 0, 3]);
+    /// assert_eq!(multiset.len(), 3);
+    /// ```
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    // The ops traits have no way to return an error, so panic with the
+    // length mismatch instead.
+    #[inline]
+    fn assert_same_len(&self, other: &Self) {
+        if let Err(e) = check_same_len(&self.data, &other.data) {
+            panic!("{}", e)
+        }
+    }
+
+    /// Returns a MultisetVec of `len` elements with all element counts set to
+    /// zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let empty_multiset = MultisetVec::<u8>::empty(4);
+    /// assert_eq!(empty_multiset, MultisetVec::from(vec![0, 0, 0, 0]));
+    /// ```
+    #[inline]
+    pub fn empty(len: usize) -> Self {
+        MultisetVec {
+            data: vec![N::zero(); len],
+        }
+    }
+
+    /// Returns a MultisetVec of `len` elements with all elements set to
+    /// `count`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::<u8>::repeat(5, 4);
+    /// assert_eq!(multiset, MultisetVec::from(vec![5, 5, 5, 5]))
+    /// ```
+    #[inline]
+    pub fn repeat(count: N, len: usize) -> Self {
+        MultisetVec {
+            data: vec![count; len],
+        }
+    }
+
+    /// Constructs a MultisetVec of `len` elements from an iterator of elements
+    /// in the multiset, incrementing the count of each element as it occurs
+    /// in the iterator.
+    ///
+    /// # Panics
+    /// If any item in the iterator is out of bounds of the MultisetVec. See
+    /// [`try_from_elements`] for a fallible alternative.
+    ///
+    /// [`try_from_elements`]: MultisetVec::try_from_elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::<u8>::from_elements(&[1, 1, 0, 2, 2, 2], 4);
+    /// assert_eq!(multiset, MultisetVec::from(vec![1, 2, 3, 0]))
+    /// ```
+    #[inline]
+    pub fn from_elements<'a, I>(elements: I, len: usize) -> Self
+    where
+        I: IntoIterator<Item = &'a usize>,
+    {
+        elements
+            .into_iter()
+            .fold(MultisetVec::empty(len), |mut acc, &e| {
+                if e >= len {
+                    panic!("element: {} not in MultisetVec (element >= len)", e)
+                }
+                // Safety: Above condition ensures `e` is not out of bounds.
+                unsafe { *acc.get_unchecked_mut(e) += N::one() };
+                acc
+            })
+    }
+
+    /// Constructs a MultisetVec of `len` elements from an iterator of elements
+    /// in the multiset, incrementing the count of each element as it occurs
+    /// in the iterator.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if any item in the iterator is
+    /// out of bounds of the MultisetVec, or [`Error::Overflow`] if the count
+    /// of an element would overflow the counter type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let multiset = MultisetVec::<u8>::try_from_elements(&[1, 1, 0, 2, 2, 2], 4);
+    /// assert_eq!(multiset, Ok(MultisetVec::from(vec![1, 2, 3, 0])));
+    ///
+    /// let multiset = MultisetVec::<u8>::try_from_elements(&[1, 4], 4);
+    /// assert_eq!(multiset, Err(Error::ElementOutOfRange { element: 4, len: 4 }));
+    /// ```
+    #[inline]
+    pub fn try_from_elements<'a, I>(elements: I, len: usize) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = &'a usize>,
+    {
+        let mut res = MultisetVec::empty(len);
+        for &elem in elements {
+            let count: &mut N = res
+                .get_mut(elem)
+                .ok_or(Error::ElementOutOfRange { element: elem, len })?;
+            *count = count
+                .checked_add(&N::one())
+                .ok_or(Error::Overflow { element: elem })?;
+        }
+        Ok(res)
+    }
+
+    /// Return an [Iter](`std::slice::Iter`) of the element counts in the
+    /// MultisetVec.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, N> {
+        self.data.iter()
+    }
+
+    /// Return a [IterMut](`std::slice::IterMut`) of the element counts in the
+    /// MultisetVec.
+    #[inline]
+    pub fn iter_mut(&mut self) -> IterMut<'_, N> {
+        self.data.iter_mut()
+    }
+
+    /// Returns an iterator over the `(element, count)` pairs with a non-zero
+    /// count, in element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![0u8, 3, 0, 1]);
+    /// let pairs: Vec<_> = multiset.iter_nonzero().collect();
+    /// assert_eq!(pairs, vec![(1, &3), (3, &1)]);
+    /// ```
+    #[inline]
+    pub fn iter_nonzero(&self) -> IterNonZero<'_, N> {
+        IterNonZero::new(&self.data)
+    }
+
+    /// Returns an iterator over the elements with a non-zero count, in
+    /// element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![0u8, 3, 0, 1]);
+    /// assert_eq!(multiset.support().collect::<Vec<_>>(), vec![1, 3]);
+    /// ```
+    #[inline]
+    pub fn support(&self) -> Support<'_, N> {
+        Support::new(&self.data)
+    }
+
+    /// Returns an iterator yielding each element as many times as its count,
+    /// in element order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![0u8, 3, 0, 1]);
+    /// let elements: Vec<_> = multiset.elements().collect();
+    /// assert_eq!(elements, vec![1, 1, 1, 3]);
+    /// ```
+    #[inline]
+    pub fn elements(&self) -> Elements<'_, N> {
+        Elements::new(&self.data)
+    }
+
+    /// Returns an iterator over all the `(element, count)` pairs, largest
+    /// count first. Elements with equal counts are in element order.
+    ///
+    /// The multiset itself is not reordered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 2]);
+    /// let sorted: Vec<_> = multiset.iter_by_count_desc().collect();
+    /// assert_eq!(sorted, vec![(2, &5), (0, &2), (3, &2), (1, &0)]);
+    /// ```
+    #[inline]
+    pub fn iter_by_count_desc(&self) -> IterByCount<'_, N> {
+        IterByCount::descending(&self.data)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// largest counts, largest first, or over all of them if there are fewer
+    /// than `k` elements. Elements with equal counts are in element order.
+    ///
+    /// The `k` pairs are selected in linear time, so only they are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 2]);
+    /// let top: Vec<_> = multiset.top_k(2).collect();
+    /// assert_eq!(top, vec![(2, &5), (0, &2)]);
+    /// ```
+    #[inline]
+    pub fn top_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::top_k(&self.data, k)
+    }
+
+    /// Returns an iterator over the `k` `(element, count)` pairs with the
+    /// smallest counts, smallest first, or over all of them if there are fewer
+    /// than `k` elements. Elements with equal counts are in element order.
+    ///
+    /// The `k` pairs are selected in linear time, so only they are sorted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 2]);
+    /// let bottom: Vec<_> = multiset.bottom_k(2).collect();
+    /// assert_eq!(bottom, vec![(1, &0), (0, &2)]);
+    /// ```
+    #[inline]
+    pub fn bottom_k(&self, k: usize) -> IterByCount<'_, N> {
+        IterByCount::bottom_k(&self.data, k)
+    }
+
+    /// Sets all element counts in the multiset to zero.
+    ///
+    /// The number of elements in the multiset is unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 3, 4]);
+    /// multiset.clear();
+    /// assert_eq!(multiset.is_empty(), true);
+    /// assert_eq!(multiset.len(), 4);
+    /// ```
+    #[inline]
+    pub fn clear(&mut self) {
+        MultisetOps::clear(self.data.as_mut_slice())
+    }
+
+    /// Returns `true` if `elem` has count > 0 in the multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.contains(1), true);
+    /// assert_eq!(multiset.contains(3), false);
+    /// assert_eq!(multiset.contains(5), false);
+    /// ```
+    #[inline]
+    pub fn contains(&self, elem: usize) -> bool {
+        // Safety: Guaranteed by bounds check on `elem`.
+        elem < self.len() && unsafe { self.get_unchecked(elem) > &N::zero() }
+    }
+
+    /// Returns `true` if `elem` has count > 0 in the multiset, without doing
+    /// bounds checking.
+    ///
+    /// For a safe alternative see [`contains`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]* even if the resulting boolean is not used.
+    ///
+    /// [`contains`]: MultisetVec::contains
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    ///
+    /// unsafe {
+    ///     assert_eq!(multiset.contains_unchecked(1), true);
+    ///     assert_eq!(multiset.contains_unchecked(3), false);
+    /// }
+    /// ```
+    #[inline]
+    pub unsafe fn contains_unchecked(&self, elem: usize) -> bool {
+        self.get_unchecked(elem) > &N::zero()
+    }
+
+    /// Set the count of `elem` in the multiset to `amount`.
+    ///
+    /// Does nothing if `elem` is out of bounds of the MultisetVec, see
+    /// [`try_insert`] for an alternative which reports this.
+    ///
+    /// [`try_insert`]: MultisetVec::try_insert
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// multiset.insert(2, 5);
+    /// assert_eq!(multiset.get(2), Some(&5));
+    /// ```
+    #[inline]
+    pub fn insert(&mut self, elem: usize, amount: N) {
+        if let Some(count) = self.get_mut(elem) {
+            *count = amount;
+        }
+    }
+
+    /// Set the count of `elem` in the multiset to `amount`, or return
+    /// [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// MultisetVec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.try_insert(2, 5), Ok(()));
+    /// assert_eq!(multiset.get(2), Some(&5));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.try_insert(4, 5), Err(err));
+    /// ```
+    #[inline]
+    pub fn try_insert(&mut self, elem: usize, amount: N) -> Result<(), Error> {
+        *self.count_mut(elem)? = amount;
+        Ok(())
+    }
+
+    /// Set the count of `elem` in the multiset to `amount`, without doing
+    /// bounds checking.
+    ///
+    /// For a safe alternative see [`insert`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]*.
+    ///
+    /// [`insert`]: MultisetVec::insert
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    ///
+    /// unsafe {
+    ///     multiset.insert_unchecked(2, 5);
+    ///     assert_eq!(multiset.get(2), Some(&5));
+    /// }
+    /// ```
+    #[inline]
+    pub unsafe fn insert_unchecked(&mut self, elem: usize, amount: N) {
+        *self.get_unchecked_mut(elem) = amount
+    }
+
+    /// Set the count of `elem` in the multiset to zero.
+    ///
+    /// Does nothing if `elem` is out of bounds of the MultisetVec, see
+    /// [`try_remove`] for an alternative which reports this.
+    ///
+    /// [`try_remove`]: MultisetVec::try_remove
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// multiset.remove(1);
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// ```
+    #[inline]
+    pub fn remove(&mut self, elem: usize) {
+        if let Some(count) = self.get_mut(elem) {
+            *count = N::zero();
+        }
+    }
+
+    /// Set the count of `elem` in the multiset to zero, or return
+    /// [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// MultisetVec.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.try_remove(1), Ok(()));
+    /// assert_eq!(multiset.get(1), Some(&0));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.try_remove(4), Err(err));
+    /// ```
+    #[inline]
+    pub fn try_remove(&mut self, elem: usize) -> Result<(), Error> {
+        self.try_insert(elem, N::zero())
+    }
+
+    /// Set the count of `elem` in the multiset to zero, without doing bounds
+    /// checking.
+    ///
+    /// For a safe alternative see [`remove`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]*.
+    ///
+    /// [`remove`]: MultisetVec::remove
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// unsafe {
+    ///     multiset.remove_unchecked(1);
+    ///     assert_eq!(multiset.get(1), Some(&0));
+    /// }
+    /// ```
+    #[inline]
+    pub unsafe fn remove_unchecked(&mut self, elem: usize) {
+        *self.get_unchecked_mut(elem) = N::zero()
+    }
+
+    /// Returns a reference to a count or subslice of counts depending on the
+    /// type of index, or `None` if the index is out of bounds.
+    ///
+    /// See [`Multiset::get`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.get(1), Some(&2));
+    /// assert_eq!(multiset.get(2..), Some(&[0, 0][..]));
+    /// assert_eq!(multiset.get(5), None);
+    /// ```
+    #[inline]
+    pub fn get<I>(&self, index: I) -> Option<&I::Output>
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get(index)
+    }
+
+    /// Returns a mutable reference to a count or subslice of counts depending
+    /// on the type of index (see [`get`]) or `None` if the index is out of
+    /// bounds.
+    ///
+    /// [`get`]: MultisetVec::get
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 4]);
+    ///
+    /// if let Some(elem) = multiset.get_mut(1) {
+    ///     *elem = 42;
+    /// }
+    /// assert_eq!(multiset, MultisetVec::from(vec![1u8, 42, 4]));
+    /// ```
+    #[inline]
+    pub fn get_mut<I>(&mut self, index: I) -> Option<&mut I::Output>
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get_mut(index)
+    }
+
+    /// Returns a reference to a count or subslice of counts, without doing
+    /// bounds checking.
+    ///
+    /// For a safe alternative see [`get`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]* even if the resulting reference is not used.
+    ///
+    /// [`get`]: MultisetVec::get
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u32, 2, 0, 0]);
+    ///
+    /// unsafe {
+    ///     assert_eq!(multiset.get_unchecked(1), &2);
+    /// }
+    /// ```
+    #[inline]
+    pub unsafe fn get_unchecked<I>(&self, index: I) -> &I::Output
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get_unchecked(index)
+    }
+
+    /// Returns a mutable reference to a count or subslice of counts, without
+    /// doing bounds checking.
+    ///
+    /// For a safe alternative see [`get_mut`].
+    ///
+    /// # Safety
+    ///
+    /// Calling this method with an out-of-bounds index is
+    /// *[undefined behavior]* even if the resulting reference is not used.
+    ///
+    /// [`get_mut`]: MultisetVec::get_mut
+    /// [undefined behavior]: https://doc.rust-lang.org/reference/behavior-considered-undefined.html
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 4]);
+    ///
+    /// unsafe {
+    ///     let elem = multiset.get_unchecked_mut(1);
+    ///     *elem = 13;
+    /// }
+    /// assert_eq!(multiset, MultisetVec::from(vec![1u8, 13, 4]));
+    /// ```
+    #[inline]
+    pub unsafe fn get_unchecked_mut<I>(&mut self, index: I) -> &mut I::Output
+    where
+        I: SliceIndex<[N]>,
+    {
+        self.data.get_unchecked_mut(index)
+    }
+
+    /// Returns a multiset which is the intersection of `self` and `other`, or
+    /// an error if their lengths differ.
+    ///
+    /// See [`Multiset::intersection`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// let b = MultisetVec::from(vec![0, 1, 3, 0]);
+    /// let c = MultisetVec::from(vec![0, 1, 0, 0]);
+    /// assert_eq!(a.intersection(&b), Ok(c));
+    /// ```
+    #[inline]
+    pub fn intersection(&self, other: &Self) -> Result<Self, Error> {
+        MultisetOps::intersection(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns a multiset which is the union of `self` and `other`, or an
+    /// error if their lengths differ.
+    ///
+    /// See [`Multiset::union`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// let b = MultisetVec::from(vec![0, 1, 3, 0]);
+    /// let c = MultisetVec::from(vec![1, 2, 3, 0]);
+    /// assert_eq!(a.union(&b), Ok(c));
+    /// ```
+    #[inline]
+    pub fn union(&self, other: &Self) -> Result<Self, Error> {
+        MultisetOps::union(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns a multiset which is the difference of `self` and `other`, or
+    /// an error if their lengths differ.
+    ///
+    /// See [`Multiset::difference`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// let b = MultisetVec::from(vec![0, 1, 3, 0]);
+    /// let c = MultisetVec::from(vec![1, 1, 0, 0]);
+    /// assert_eq!(a.difference(&b), Ok(c));
+    /// ```
+    #[inline]
+    pub fn difference(&self, other: &Self) -> Result<Self, Error> {
+        MultisetOps::difference(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns a multiset which is the symmetric_difference of `self` and
+    /// `other`, or an error if their lengths differ.
+    ///
+    /// See [`Multiset::symmetric_difference`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// let b = MultisetVec::from(vec![0, 1, 3, 0]);
+    /// let c = MultisetVec::from(vec![1, 1, 3, 0]);
+    /// assert_eq!(a.symmetric_difference(&b), Ok(c));
+    /// ```
+    #[inline]
+    pub fn symmetric_difference(&self, other: &Self) -> Result<Self, Error> {
+        MultisetOps::symmetric_difference(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns the number of elements whose count is non-zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 0, 0, 0]);
+    /// assert_eq!(multiset.count_non_zero(), 1);
+    /// ```
+    #[inline]
+    pub fn count_non_zero(&self) -> usize {
+        MultisetOps::count_non_zero(self.data.as_slice())
+    }
+
+    /// Returns the number of elements whose count is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 0, 0, 0]);
+    /// assert_eq!(multiset.count_zero(), 3);
+    /// ```
+    #[inline]
+    pub fn count_zero(&self) -> usize {
+        self.len() - self.count_non_zero()
+    }
+
+    /// Returns `true` if only one element in the multiset has a non-zero
+    /// count.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![0u8, 5, 0, 0]);
+    /// assert_eq!(multiset.is_singleton(), true);
+    /// ```
+    #[inline]
+    pub fn is_singleton(&self) -> bool {
+        self.count_non_zero() == 1
+    }
+
+    /// Returns `true` if `self` is disjoint to `other`, or an error if their
+    /// lengths differ.
+    ///
+    /// See [`Multiset::is_disjoint`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(a.is_disjoint(&a), Ok(false));
+    ///
+    /// let b = MultisetVec::from(vec![0, 0, 3, 4]);
+    /// assert_eq!(a.is_disjoint(&b), Ok(true));
+    /// ```
+    #[inline]
+    pub fn is_disjoint(&self, other: &Self) -> Result<bool, Error> {
+        MultisetOps::is_disjoint(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns `true` if `self` is a subset of `other`, or an error if their
+    /// lengths differ.
+    ///
+    /// See [`Multiset::is_subset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u64, 2, 0, 0]);
+    /// assert_eq!(a.is_subset(&a), Ok(true));
+    ///
+    /// let b = MultisetVec::from(vec![1, 3, 0, 0]);
+    /// assert_eq!(a.is_subset(&b), Ok(true));
+    ///
+    /// assert_eq!(a.is_subset(&MultisetVec::empty(4)), Ok(false));
+    /// ```
+    #[inline]
+    pub fn is_subset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetOps::is_subset(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns `true` if `self` is a superset of `other`, or an error if
+    /// their lengths differ.
+    ///
+    /// See [`Multiset::is_superset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(a.is_superset(&a), Ok(true));
+    ///
+    /// let b = MultisetVec::from(vec![1, 1, 0, 0]);
+    /// assert_eq!(a.is_superset(&b), Ok(true));
+    /// ```
+    #[inline]
+    pub fn is_superset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetOps::is_superset(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns `true` if `self` is a proper subset of `other`, or an error if
+    /// their lengths differ.
+    ///
+    /// See [`Multiset::is_proper_subset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(a.is_proper_subset(&a), Ok(false));
+    ///
+    /// let b = MultisetVec::from(vec![1, 3, 0, 0]);
+    /// assert_eq!(a.is_proper_subset(&b), Ok(true));
+    /// ```
+    #[inline]
+    pub fn is_proper_subset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetOps::is_proper_subset(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns `true` if `self` is a proper superset of `other`, or an error
+    /// if their lengths differ.
+    ///
+    /// See [`Multiset::is_proper_superset`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(a.is_proper_superset(&a), Ok(false));
+    ///
+    /// let b = MultisetVec::from(vec![1, 1, 0, 0]);
+    /// assert_eq!(a.is_proper_superset(&b), Ok(true));
+    /// ```
+    #[inline]
+    pub fn is_proper_superset(&self, other: &Self) -> Result<bool, Error> {
+        MultisetOps::is_proper_superset(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns `true` if any element count in `self` is less than that element
+    /// count in `other`, or an error if their lengths differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 4, 0]);
+    /// assert_eq!(a.is_any_lesser(&a), Ok(false));
+    ///
+    /// let b = MultisetVec::from(vec![1, 3, 0, 0]);
+    /// assert_eq!(a.is_any_lesser(&b), Ok(true));
+    /// ```
+    #[inline]
+    pub fn is_any_lesser(&self, other: &Self) -> Result<bool, Error> {
+        MultisetOps::is_any_lesser(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns `true` if any element count in `self` is greater than that
+    /// element count in `other`, or an error if their lengths differ.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(a.is_any_greater(&a), Ok(false));
+    ///
+    /// let b = MultisetVec::from(vec![1, 1, 4, 0]);
+    /// assert_eq!(a.is_any_greater(&b), Ok(true));
+    /// ```
+    #[inline]
+    pub fn is_any_greater(&self, other: &Self) -> Result<bool, Error> {
+        MultisetOps::is_any_greater(self.data.as_slice(), other.data.as_slice())
+    }
+
+    /// Returns `true` if all elements have a count of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![0u8, 0, 0, 0]);
+    /// assert_eq!(multiset.is_empty(), true);
+    /// assert_eq!(MultisetVec::<u8>::empty(4).is_empty(), true);
+    /// ```
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        MultisetOps::is_empty(self.data.as_slice())
+    }
+
+    /// The total or cardinality of a multiset is the sum of all element
+    /// counts.
+    ///
+    /// See [`Multiset::total`] for details.
+    ///
+    /// # Panics
+    /// If the counter type is wider than `usize`, such as `u128`, and the total
+    /// does not fit in a `usize`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 2, 3, 4]);
+    /// assert_eq!(multiset.total(), 10);
+    /// ```
+    #[inline]
+    pub fn total(&self) -> usize {
+        MultisetOps::total(self.data.as_slice())
+    }
+
+    /// The total of the multiset, accumulated as `u128`.
+    ///
+    /// See [`Multiset::total_u128`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![u64::MAX, u64::MAX]);
+    /// assert_eq!(multiset.total_u128(), 2 * u64::MAX as u128);
+    /// ```
+    #[inline]
+    pub fn total_u128(&self) -> u128 {
+        MultisetOps::total_u128(self.data.as_slice())
+    }
+
+    /// The total of the multiset, accumulated as `u128`, or
+    /// [`Error::Overflow`] naming the element at which the total overflowed.
+    ///
+    /// See [`Multiset::checked_total`] for details.
+    #[inline]
+    pub fn checked_total(&self) -> Result<u128, Error> {
+        MultisetOps::checked_total(self.data.as_slice())
+    }
+
+    /// Returns a tuple containing the element and a reference to the largest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u16, 0, 5, 3]);
+    /// assert_eq!(multiset.elem_count_max(), (2, &5));
+    /// ```
+    #[inline]
+    pub fn elem_count_max(&self) -> (usize, &N) {
+        MultisetOps::elem_count_max(self.data.as_slice())
+    }
+
+    /// Returns the element with the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 3]);
+    /// assert_eq!(multiset.elem_max(), 2);
+    /// ```
+    #[inline]
+    pub fn elem_max(&self) -> usize {
+        self.elem_count_max().0
+    }
+
+    /// Returns a reference to the largest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 3]);
+    /// assert_eq!(multiset.count_max(), &5);
+    /// ```
+    #[inline]
+    pub fn count_max(&self) -> &N {
+        self.elem_count_max().1
+    }
+
+    /// Returns a tuple containing the element and a reference to the smallest
+    /// count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 3]);
+    /// assert_eq!(multiset.elem_count_min(), (1, &0));
+    /// ```
+    #[inline]
+    pub fn elem_count_min(&self) -> (usize, &N) {
+        MultisetOps::elem_count_min(self.data.as_slice())
+    }
+
+    /// Returns the element with the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 3]);
+    /// assert_eq!(multiset.elem_min(), 1);
+    /// ```
+    #[inline]
+    pub fn elem_min(&self) -> usize {
+        self.elem_count_min().0
+    }
+
+    /// Returns a reference to the smallest count in the multiset.
+    ///
+    /// # Panics
+    /// If the multiset has no elements.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 0, 5, 3]);
+    /// assert_eq!(multiset.count_min(), &0);
+    /// ```
+    #[inline]
+    pub fn count_min(&self) -> &N {
+        self.elem_count_min().1
+    }
+
+    /// Set all element counts, except for the given `elem`, to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![2u8, 0, 5, 3]);
+    /// multiset.choose(2);
+    /// let result = MultisetVec::from(vec![0, 0, 5, 0]);
+    /// assert_eq!(multiset, result);
+    /// ```
+    #[inline]
+    pub fn choose(&mut self, elem: usize) {
+        MultisetOps::choose(self.data.as_mut_slice(), elem)
+    }
+
+    /// Set all element counts, except for a random choice, to zero.
+    ///
+    /// The choice is weighted by the counts of the elements, and unless the
+    /// multiset is empty an element with non-zero count will always be chosen.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    /// use rand::prelude::*;
+    ///
+    /// let rng = &mut StdRng::seed_from_u64(thread_rng().next_u64());
+    /// let mut multiset = MultisetVec::from(vec![2u8, 0, 5, 3]);
+    /// multiset.choose_random(rng);
+    /// assert_eq!(multiset.is_singleton(), true);
+    /// ```
+    #[cfg(feature = "rand")]
+    #[inline]
+    pub fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
+        MultisetOps::choose_random(self.data.as_mut_slice(), rng)
+    }
+
+    /// Calculate the collision entropy of the multiset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 1, 1, 0]);
+    /// let result = multiset.collision_entropy();
+    /// // approximate: result == 1.415037499278844
+    /// ```
+    ///
+    /// # Warning
+    /// The total is accumulated with [`MultisetVec::total_u128`], so it cannot
+    /// overflow, but it and the counts are converted to `f64` and so lose
+    /// precision above 2^53. The conversions are handled by
+    /// [`AsPrimitive<f64>`].
+    ///
+    /// [`AsPrimitive<f64>`]: num_traits::AsPrimitive
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn collision_entropy(&self) -> f64 {
+        MultisetOps::collision_entropy(self.data.as_slice())
+    }
+
+    /// Calculate the shannon entropy of the multiset. Uses ln rather than log2.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![2u8, 1, 1, 0]);
+    /// let result = multiset.shannon_entropy();
+    /// // approximate: result == 1.0397207708399179
+    /// ```
+    ///
+    /// # Warning
+    /// The total is accumulated with [`MultisetVec::total_u128`], so it cannot
+    /// overflow, but it and the counts are converted to `f64` and so lose
+    /// precision above 2^53. The conversions are handled by
+    /// [`AsPrimitive<f64>`].
+    ///
+    /// [`AsPrimitive<f64>`]: num_traits::AsPrimitive
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    pub fn shannon_entropy(&self) -> f64 {
+        MultisetOps::shannon_entropy(self.data.as_slice())
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Counter conversions
+////////////////////////////////////////////////////////////////////////////////
+
+// See the counter conversions of `Multiset` for which of these use simd.
+impl<N: Counter> MultisetVec<N> {
+    /// Converts every count to the counter type `N2`, which must be able to
+    /// represent every value of `N` without loss.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u8, 255, 0]);
+    /// let wide: MultisetVec<u64> = multiset.cast();
+    /// assert_eq!(wide, MultisetVec::from(vec![1u64, 255, 0]));
+    /// ```
+    #[inline]
+    pub fn cast<N2>(&self) -> MultisetVec<N2>
+    where
+        N2: Counter + From<N>,
+    {
+        let mut data = vec![N2::zero(); self.data.len()];
+        kernels::cast_counts(&self.data, &mut data);
+        MultisetVec { data }
+    }
+
+    /// Converts every count to the counter type `N2`, or returns
+    /// [`Error::Overflow`] naming the first element whose count does not fit
+    /// in `N2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let multiset = MultisetVec::from(vec![1u32, 255, 0]);
+    /// assert_eq!(multiset.try_cast::<u8>(), Ok(MultisetVec::from(vec![1u8, 255, 0])));
+    ///
+    /// let multiset = MultisetVec::from(vec![1u32, 256, 0]);
+    /// assert_eq!(multiset.try_cast::<u8>(), Err(Error::Overflow { element: 1 }));
+    /// ```
+    #[inline]
+    pub fn try_cast<N2>(&self) -> Result<MultisetVec<N2>, Error>
+    where
+        N2: Counter + TryFrom<N>,
+    {
+        let mut data = vec![N2::zero(); self.data.len()];
+        kernels::try_cast_counts(&self.data, &mut data)?;
+        Ok(MultisetVec { data })
+    }
+
+    /// Converts every count to the counter type `N2`, clamping counts which
+    /// do not fit to the maximum value of `N2`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let multiset = MultisetVec::from(vec![1u32, 256, 0]);
+    /// assert_eq!(multiset.saturating_cast::<u8>(), MultisetVec::from(vec![1u8, 255, 0]));
+    /// ```
+    #[inline]
+    pub fn saturating_cast<N2>(&self) -> MultisetVec<N2>
+    where
+        N2: Counter + TryFrom<N>,
+    {
+        let mut data = vec![N2::zero(); self.data.len()];
+        kernels::saturating_cast_counts(&self.data, &mut data);
+        MultisetVec { data }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Counting
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter> MultisetVec<N> {
+    /// Adds `n` to the count of `elem`, returning the previous count.
+    ///
+    /// Like the arithmetic operators of the counter type this panics rather
+    /// than wrapping. [`checked_add_count`] and [`saturating_add_count`] are
+    /// the non-panicking alternatives.
+    ///
+    /// # Panics
+    /// If `elem` is out of bounds of the MultisetVec, or if the count would
+    /// overflow.
+    ///
+    /// [`checked_add_count`]: MultisetVec::checked_add_count
+    /// [`saturating_add_count`]: MultisetVec::saturating_add_count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.add_count(1, 3), 2);
+    /// assert_eq!(multiset.get(1), Some(&5));
+    /// ```
+    #[inline]
+    pub fn add_count(&mut self, elem: usize, n: N) -> N {
+        self.checked_add_count(elem, n)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Subtracts `n` from the count of `elem`, returning the previous count.
+    ///
+    /// Like the arithmetic operators of the counter type this panics rather
+    /// than wrapping. [`checked_sub_count`] and [`saturating_sub_count`] are
+    /// the non-panicking alternatives.
+    ///
+    /// # Panics
+    /// If `elem` is out of bounds of the MultisetVec, or if `n` is larger than
+    /// the count.
+    ///
+    /// [`checked_sub_count`]: MultisetVec::checked_sub_count
+    /// [`saturating_sub_count`]: MultisetVec::saturating_sub_count
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.sub_count(1, 2), 2);
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// ```
+    #[inline]
+    pub fn sub_count(&mut self, elem: usize, n: N) -> N {
+        self.checked_sub_count(elem, n)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Increments the count of `elem` by one, returning the previous count.
+    ///
+    /// See [`add_count`](MultisetVec::add_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.increment(2), 0);
+    /// assert_eq!(multiset.get(2), Some(&1));
+    /// ```
+    #[inline]
+    pub fn increment(&mut self, elem: usize) -> N {
+        self.add_count(elem, N::one())
+    }
+
+    /// Decrements the count of `elem` by one, returning the previous count.
+    ///
+    /// See [`sub_count`](MultisetVec::sub_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.decrement(1), 2);
+    /// assert_eq!(multiset.get(1), Some(&1));
+    /// ```
+    #[inline]
+    pub fn decrement(&mut self, elem: usize) -> N {
+        self.sub_count(elem, N::one())
+    }
+
+    /// Increments the count of each element yielded by `elements`. This is
+    /// what the [`Extend`] implementation calls.
+    ///
+    /// # Panics
+    /// If an element is out of bounds of the MultisetVec, or if a count would
+    /// overflow. Elements which were counted before the panic are not rolled
+    /// back. [`checked_extend_elements`] and [`saturating_extend_elements`]
+    /// are the non-panicking alternatives.
+    ///
+    /// [`checked_extend_elements`]: MultisetVec::checked_extend_elements
+    /// [`saturating_extend_elements`]: MultisetVec::saturating_extend_elements
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![0u8, 0, 0, 0]);
+    /// multiset.extend_elements(vec![1, 1, 3]);
+    /// assert_eq!(multiset, MultisetVec::from(vec![0, 2, 0, 1]));
+    /// ```
+    #[inline]
+    pub fn extend_elements<I>(&mut self, elements: I)
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        if let Err(e) = self.checked_extend_elements(elements) {
+            panic!("{}", e)
+        }
+    }
+
+    /// Checked addition of `n` to the count of `elem`, returning the previous
+    /// count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// MultisetVec, or [`Error::Overflow`] if the count would overflow, in
+    /// which case the count is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 255]);
+    /// assert_eq!(multiset.checked_add_count(1, 3), Ok(2));
+    /// assert_eq!(multiset.get(1), Some(&5));
+    /// assert_eq!(multiset.checked_add_count(3, 1), Err(Error::Overflow { element: 3 }));
+    /// assert_eq!(multiset.get(3), Some(&255));
+    /// ```
+    #[inline]
+    pub fn checked_add_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev
+            .checked_add(&n)
+            .ok_or(Error::Overflow { element: elem })?;
+        Ok(prev)
+    }
+
+    /// Checked subtraction of `n` from the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// MultisetVec, or [`Error::Overflow`] if `n` is larger than the count, in
+    /// which case the count is left unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.checked_sub_count(1, 2), Ok(2));
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// assert_eq!(multiset.checked_sub_count(0, 2), Err(Error::Overflow { element: 0 }));
+    /// assert_eq!(multiset.get(0), Some(&1));
+    /// ```
+    #[inline]
+    pub fn checked_sub_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev
+            .checked_sub(&n)
+            .ok_or(Error::Overflow { element: elem })?;
+        Ok(prev)
+    }
+
+    /// Checked increment of the count of `elem` by one, returning the previous
+    /// count.
+    ///
+    /// See [`checked_add_count`](MultisetVec::checked_add_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.checked_increment(2), Ok(0));
+    /// assert_eq!(multiset.get(2), Some(&1));
+    /// ```
+    #[inline]
+    pub fn checked_increment(&mut self, elem: usize) -> Result<N, Error> {
+        self.checked_add_count(elem, N::one())
+    }
+
+    /// Checked decrement of the count of `elem` by one, returning the previous
+    /// count.
+    ///
+    /// See [`checked_sub_count`](MultisetVec::checked_sub_count) for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.checked_decrement(1), Ok(2));
+    /// assert_eq!(multiset.checked_decrement(2), Err(Error::Overflow { element: 2 }));
+    /// ```
+    #[inline]
+    pub fn checked_decrement(&mut self, elem: usize) -> Result<N, Error> {
+        self.checked_sub_count(elem, N::one())
+    }
+
+    /// Saturating addition of `n` to the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// MultisetVec. Otherwise the count is clamped at the maximum value of the
+    /// counter type instead of overflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 250]);
+    /// assert_eq!(multiset.saturating_add_count(3, 10), Ok(250));
+    /// assert_eq!(multiset.get(3), Some(&255));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.saturating_add_count(4, 1), Err(err));
+    /// ```
+    #[inline]
+    pub fn saturating_add_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev.saturating_add(&n);
+        Ok(prev)
+    }
+
+    /// Saturating subtraction of `n` from the count of `elem`, returning the
+    /// previous count.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if `elem` is out of bounds of the
+    /// MultisetVec. Otherwise the count is clamped at zero instead of
+    /// underflowing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.saturating_sub_count(1, 5), Ok(2));
+    /// assert_eq!(multiset.get(1), Some(&0));
+    /// ```
+    #[inline]
+    pub fn saturating_sub_count(&mut self, elem: usize, n: N) -> Result<N, Error> {
+        let count = self.count_mut(elem)?;
+        let prev = *count;
+        *count = prev.saturating_sub(&n);
+        Ok(prev)
+    }
+
+    /// Saturating increment of the count of `elem` by one, returning the
+    /// previous count.
+    ///
+    /// See [`saturating_add_count`](MultisetVec::saturating_add_count) for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 255]);
+    /// assert_eq!(multiset.saturating_increment(3), Ok(255));
+    /// assert_eq!(multiset.get(3), Some(&255));
+    /// ```
+    #[inline]
+    pub fn saturating_increment(&mut self, elem: usize) -> Result<N, Error> {
+        self.saturating_add_count(elem, N::one())
+    }
+
+    /// Saturating decrement of the count of `elem` by one, returning the
+    /// previous count.
+    ///
+    /// See [`saturating_sub_count`](MultisetVec::saturating_sub_count) for
+    /// details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![1u8, 2, 0, 0]);
+    /// assert_eq!(multiset.saturating_decrement(2), Ok(0));
+    /// assert_eq!(multiset.get(2), Some(&0));
+    /// ```
+    #[inline]
+    pub fn saturating_decrement(&mut self, elem: usize) -> Result<N, Error> {
+        self.saturating_sub_count(elem, N::one())
+    }
+
+    /// Increment the count of each element yielded by `elements`, stopping
+    /// at the first error.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] if an element is out of bounds of
+    /// the MultisetVec, or [`Error::Overflow`] if a count would overflow.
+    /// Elements which were counted before the error are not rolled back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let mut multiset = MultisetVec::from(vec![0u8, 0, 0, 0]);
+    /// assert_eq!(multiset.checked_extend_elements(vec![1, 1, 3]), Ok(()));
+    /// assert_eq!(multiset, MultisetVec::from(vec![0, 2, 0, 1]));
+    ///
+    /// let err = Error::ElementOutOfRange { element: 4, len: 4 };
+    /// assert_eq!(multiset.checked_extend_elements(vec![0, 4, 0]), Err(err));
+    /// assert_eq!(multiset, MultisetVec::from(vec![1, 2, 0, 1]));
+    /// ```
+    #[inline]
+    pub fn checked_extend_elements<I>(&mut self, elements: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for elem in elements {
+            self.checked_increment(elem)?;
+        }
+        Ok(())
+    }
+
+    /// Increment the count of each element yielded by `elements`, clamping
+    /// counts at the maximum value of the counter type.
+    ///
+    /// Returns [`Error::ElementOutOfRange`] at the first element which is out
+    /// of bounds of the MultisetVec. Elements which were counted before the
+    /// error are not rolled back.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let mut multiset = MultisetVec::from(vec![0u8, 0, 0, 254]);
+    /// assert_eq!(multiset.saturating_extend_elements(vec![1, 3, 3, 3]), Ok(()));
+    /// assert_eq!(multiset, MultisetVec::from(vec![0, 1, 0, 255]));
+    /// ```
+    #[inline]
+    pub fn saturating_extend_elements<I>(&mut self, elements: I) -> Result<(), Error>
+    where
+        I: IntoIterator<Item = usize>,
+    {
+        for elem in elements {
+            self.saturating_increment(elem)?;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn count_mut(&mut self, elem: usize) -> Result<&mut N, Error> {
+        let len = self.len();
+        self.get_mut(elem)
+            .ok_or(Error::ElementOutOfRange { element: elem, len })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Checked, saturating & wrapping arithmetic
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter> MultisetVec<N> {
+    /// Checked elementwise addition. Returns an error if the lengths
+    /// differ, or [`Error::Overflow`] naming the first element whose count
+    /// overflowed.
+    ///
+    /// See [`Multiset::checked_add`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let a = MultisetVec::from(vec![1u8, 2]);
+    /// let b = MultisetVec::from(vec![3, 4]);
+    /// assert_eq!(a.checked_add(&b), Ok(MultisetVec::from(vec![4, 6])));
+    ///
+    /// let a = MultisetVec::from(vec![200u8, 1]);
+    /// let b = MultisetVec::from(vec![100, 1]);
+    /// assert_eq!(a.checked_add(&b), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_add(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        self.try_map_enumerated(|i, a| a.checked_add(&other.data[i]))
+    }
+
+    /// Checked addition of `rhs` to every count. Returns [`Error::Overflow`]
+    /// naming the first element whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let a = MultisetVec::from(vec![250u8, 1]);
+    /// assert_eq!(a.checked_add_scalar(10), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_add_scalar(&self, rhs: N) -> Result<Self, Error> {
+        self.try_map_enumerated(|_, a| a.checked_add(&rhs))
+    }
+
+    /// Checked elementwise subtraction. Returns an error if the lengths
+    /// differ, or [`Error::Overflow`] naming the first element whose count
+    /// overflowed.
+    ///
+    /// See [`Multiset::checked_sub`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let a = MultisetVec::from(vec![3u8, 2]);
+    /// let b = MultisetVec::from(vec![1, 2]);
+    /// assert_eq!(a.checked_sub(&b), Ok(MultisetVec::from(vec![2, 0])));
+    ///
+    /// let a = MultisetVec::from(vec![3u8, 1]);
+    /// let b = MultisetVec::from(vec![1, 2]);
+    /// assert_eq!(a.checked_sub(&b), Err(Error::Overflow { element: 1 }));
+    /// ```
+    #[inline]
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        self.try_map_enumerated(|i, a| a.checked_sub(&other.data[i]))
+    }
+
+    /// Checked subtraction of `rhs` from every count. Returns
+    /// [`Error::Overflow`] naming the first element whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let a = MultisetVec::from(vec![3u8, 1]);
+    /// assert_eq!(a.checked_sub_scalar(2), Err(Error::Overflow { element: 1 }));
+    /// ```
+    #[inline]
+    pub fn checked_sub_scalar(&self, rhs: N) -> Result<Self, Error> {
+        self.try_map_enumerated(|_, a| a.checked_sub(&rhs))
+    }
+
+    /// Checked elementwise multiplication. Returns an error if the lengths
+    /// differ, or [`Error::Overflow`] naming the first element whose count
+    /// overflowed.
+    ///
+    /// See [`Multiset::checked_mul`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let a = MultisetVec::from(vec![2u8, 3]);
+    /// let b = MultisetVec::from(vec![4, 5]);
+    /// assert_eq!(a.checked_mul(&b), Ok(MultisetVec::from(vec![8, 15])));
+    ///
+    /// let a = MultisetVec::from(vec![16u8, 2]);
+    /// let b = MultisetVec::from(vec![16, 2]);
+    /// assert_eq!(a.checked_mul(&b), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_mul(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        self.try_map_enumerated(|i, a| a.checked_mul(&other.data[i]))
+    }
+
+    /// Checked multiplication of every count by `rhs`. Returns
+    /// [`Error::Overflow`] naming the first element whose count overflowed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::{Error, MultisetVec};
+    ///
+    /// let a = MultisetVec::from(vec![128u8, 1]);
+    /// assert_eq!(a.checked_mul_scalar(2), Err(Error::Overflow { element: 0 }));
+    /// ```
+    #[inline]
+    pub fn checked_mul_scalar(&self, rhs: N) -> Result<Self, Error> {
+        self.try_map_enumerated(|_, a| a.checked_mul(&rhs))
+    }
+
+    /// Saturating elementwise addition, or an error if the lengths differ.
+    ///
+    /// See [`Multiset::saturating_add`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![200u8, 1]);
+    /// let b = MultisetVec::from(vec![100, 1]);
+    /// assert_eq!(a.saturating_add(&b), Ok(MultisetVec::from(vec![255, 2])));
+    /// ```
+    #[inline]
+    pub fn saturating_add(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        Ok(self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.saturating_add(b))
+            .collect())
+    }
+
+    /// Saturating addition of `rhs` to every count.
+    ///
+    /// See [`Multiset::saturating_add_scalar`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![250u8, 1]);
+    /// assert_eq!(a.saturating_add_scalar(10), MultisetVec::from(vec![255, 11]));
+    /// ```
+    #[inline]
+    pub fn saturating_add_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.saturating_add(&rhs)).collect()
+    }
+
+    /// Saturating elementwise subtraction, or an error if the lengths differ.
+    ///
+    /// See [`Multiset::saturating_sub`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![3u8, 1]);
+    /// let b = MultisetVec::from(vec![1, 2]);
+    /// assert_eq!(a.saturating_sub(&b), Ok(MultisetVec::from(vec![2, 0])));
+    /// ```
+    #[inline]
+    pub fn saturating_sub(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        Ok(self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.saturating_sub(b))
+            .collect())
+    }
+
+    /// Saturating subtraction of `rhs` from every count.
+    ///
+    /// See [`Multiset::saturating_sub_scalar`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![3u8, 1]);
+    /// assert_eq!(a.saturating_sub_scalar(2), MultisetVec::from(vec![1, 0]));
+    /// ```
+    #[inline]
+    pub fn saturating_sub_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.saturating_sub(&rhs)).collect()
+    }
+
+    /// Saturating elementwise multiplication, or an error if the lengths differ.
+    ///
+    /// See [`Multiset::saturating_mul`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![16u8, 2]);
+    /// let b = MultisetVec::from(vec![16, 2]);
+    /// assert_eq!(a.saturating_mul(&b), Ok(MultisetVec::from(vec![255, 4])));
+    /// ```
+    #[inline]
+    pub fn saturating_mul(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        Ok(self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.saturating_mul(b))
+            .collect())
+    }
+
+    /// Saturating multiplication of every count by `rhs`.
+    ///
+    /// See [`Multiset::saturating_mul_scalar`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![128u8, 1]);
+    /// assert_eq!(a.saturating_mul_scalar(2), MultisetVec::from(vec![255, 2]));
+    /// ```
+    #[inline]
+    pub fn saturating_mul_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.saturating_mul(&rhs)).collect()
+    }
+
+    /// Wrapping elementwise addition, or an error if the lengths differ.
+    ///
+    /// See [`Multiset::wrapping_add`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![200u8, 1]);
+    /// let b = MultisetVec::from(vec![100, 1]);
+    /// assert_eq!(a.wrapping_add(&b), Ok(MultisetVec::from(vec![44, 2])));
+    /// ```
+    #[inline]
+    pub fn wrapping_add(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        Ok(self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.wrapping_add(b))
+            .collect())
+    }
+
+    /// Wrapping addition of `rhs` to every count.
+    ///
+    /// See [`Multiset::wrapping_add_scalar`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![250u8, 1]);
+    /// assert_eq!(a.wrapping_add_scalar(10), MultisetVec::from(vec![4, 11]));
+    /// ```
+    #[inline]
+    pub fn wrapping_add_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.wrapping_add(&rhs)).collect()
+    }
+
+    /// Wrapping elementwise subtraction, or an error if the lengths differ.
+    ///
+    /// See [`Multiset::wrapping_sub`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![3u8, 1]);
+    /// let b = MultisetVec::from(vec![1, 2]);
+    /// assert_eq!(a.wrapping_sub(&b), Ok(MultisetVec::from(vec![2, 255])));
+    /// ```
+    #[inline]
+    pub fn wrapping_sub(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        Ok(self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.wrapping_sub(b))
+            .collect())
+    }
+
+    /// Wrapping subtraction of `rhs` from every count.
+    ///
+    /// See [`Multiset::wrapping_sub_scalar`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![3u8, 1]);
+    /// assert_eq!(a.wrapping_sub_scalar(2), MultisetVec::from(vec![1, 255]));
+    /// ```
+    #[inline]
+    pub fn wrapping_sub_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.wrapping_sub(&rhs)).collect()
+    }
+
+    /// Wrapping elementwise multiplication, or an error if the lengths differ.
+    ///
+    /// See [`Multiset::wrapping_mul`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![16u8, 2]);
+    /// let b = MultisetVec::from(vec![16, 2]);
+    /// assert_eq!(a.wrapping_mul(&b), Ok(MultisetVec::from(vec![0, 4])));
+    /// ```
+    #[inline]
+    pub fn wrapping_mul(&self, other: &Self) -> Result<Self, Error> {
+        check_same_len(&self.data, &other.data)?;
+        Ok(self
+            .iter()
+            .zip(other.iter())
+            .map(|(a, b)| a.wrapping_mul(b))
+            .collect())
+    }
+
+    /// Wrapping multiplication of every count by `rhs`.
+    ///
+    /// See [`Multiset::wrapping_mul_scalar`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use utote::MultisetVec;
+    ///
+    /// let a = MultisetVec::from(vec![128u8, 1]);
+    /// assert_eq!(a.wrapping_mul_scalar(2), MultisetVec::from(vec![0, 2]));
+    /// ```
+    #[inline]
+    pub fn wrapping_mul_scalar(&self, rhs: N) -> Self {
+        self.iter().map(|a| a.wrapping_mul(&rhs)).collect()
+    }
+
+    #[inline]
+    fn try_map_enumerated<F>(&self, f: F) -> Result<Self, Error>
+    where
+        F: Fn(usize, &N) -> Option<N>,
+    {
+        self.iter()
+            .enumerate()
+            .map(|(elem, a)| f(elem, a).ok_or(Error::Overflow { element: elem }))
+            .collect::<Result<Vec<_>, _>>()
+            .map(MultisetVec::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use approx::assert_relative_eq;
+    #[cfg(feature = "rand")]
+    use rand::prelude::*;
+    use std::panic;
+
+    fn catch_unwind_silent<F: FnOnce() -> R + panic::UnwindSafe, R>(
+        f: F,
+    ) -> std::thread::Result<R> {
+        let prev_hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(f);
+        panic::set_hook(prev_hook);
+        result
+    }
+
+    fn mismatch(expected: usize, found: usize) -> Error {
+        Error::LengthMismatch { expected, found }
+    }
+
+    #[test]
+    fn test_counting() {
+        let mut set = MultisetVec::from(vec![1u8, 254, 0]);
+        assert_eq!(set.checked_add_count(0, 4), Ok(1));
+        assert_eq!(set.checked_increment(1), Ok(254));
+        assert_eq!(
+            set.checked_increment(1),
+            Err(Error::Overflow { element: 1 })
+        );
+        assert_eq!(
+            set.checked_decrement(2),
+            Err(Error::Overflow { element: 2 })
+        );
+        assert_eq!(set.saturating_add_count(1, 10), Ok(255));
+        assert_eq!(set.saturating_sub_count(0, 10), Ok(5));
+        assert_eq!(set, MultisetVec::from(vec![0, 255, 0]));
+
+        let err = Error::ElementOutOfRange { element: 3, len: 3 };
+        assert_eq!(set.checked_sub_count(3, 1), Err(err));
+        assert_eq!(set.saturating_increment(3), Err(err));
+
+        assert_eq!(set.add_count(0, 4), 0);
+        assert_eq!(set.decrement(0), 4);
+        assert_eq!(set.sub_count(0, 3), 3);
+        assert_eq!(set.increment(2), 0);
+        set.extend_elements(vec![2]);
+        assert_eq!(set, MultisetVec::from(vec![0, 255, 2]));
+        let res = catch_unwind_silent(|| MultisetVec::from(vec![255u8]).increment(0));
+        assert!(res.is_err());
+        let res = catch_unwind_silent(|| MultisetVec::from(vec![0u8]).sub_count(0, 1));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_extend() {
+        let mut set = MultisetVec::<u8>::empty(3);
+        assert_eq!(set.checked_extend_elements(vec![0, 2, 2]), Ok(()));
+        assert_eq!(set.saturating_extend_elements(vec![1]), Ok(()));
+        set.extend(vec![0]);
+        set.extend(&[1]);
+        assert_eq!(set, MultisetVec::from(vec![2, 2, 2]));
+        assert_eq!(
+            set.checked_extend_elements(vec![3]),
+            Err(Error::ElementOutOfRange { element: 3, len: 3 })
+        );
+
+        let res = catch_unwind_silent(|| MultisetVec::<u8>::empty(1).extend(vec![1]));
+        assert!(res.is_err());
+    }
+
+    #[test]
+    fn test_checked_arithmetic() {
+        let a = MultisetVec::from(vec![250u8, 1, 0, 3]);
+        let b = MultisetVec::from(vec![5u8, 1, 0, 2]);
+        let short = MultisetVec::from(vec![1u8]);
+        assert_eq!(a.checked_add(&b), Ok(MultisetVec::from(vec![255, 2, 0, 5])));
+        assert_eq!(b.checked_sub(&a), Err(Error::Overflow { element: 0 }));
+        assert_eq!(b.checked_mul(&b), Ok(MultisetVec::from(vec![25, 1, 0, 4])));
+        assert_eq!(a.checked_add(&short), Err(mismatch(4, 1)));
+        assert_eq!(a.checked_sub(&short), Err(mismatch(4, 1)));
+        assert_eq!(a.checked_mul(&short), Err(mismatch(4, 1)));
+        assert_eq!(a.checked_sub_scalar(1), Err(Error::Overflow { element: 2 }));
+        assert_eq!(
+            a.checked_add_scalar(5),
+            Ok(MultisetVec::from(vec![255, 6, 5, 8]))
+        );
+        assert_eq!(a.checked_mul_scalar(2), Err(Error::Overflow { element: 0 }));
+    }
+
+    #[test]
+    fn test_saturating_wrapping_arithmetic() {
+        let a = MultisetVec::from(vec![250u8, 1, 0, 3]);
+        let b = MultisetVec::from(vec![10u8, 2, 0, 1]);
+        let short = MultisetVec::from(vec![1u8]);
+        assert_eq!(
+            a.saturating_sub(&b),
+            Ok(MultisetVec::from(vec![240, 0, 0, 2]))
+        );
+        assert_eq!(
+            a.saturating_add(&b),
+            Ok(MultisetVec::from(vec![255, 3, 0, 4]))
+        );
+        assert_eq!(a.saturating_mul(&short), Err(mismatch(4, 1)));
+        assert_eq!(
+            a.saturating_sub_scalar(2),
+            MultisetVec::from(vec![248, 0, 0, 1])
+        );
+        assert_eq!(
+            a.wrapping_sub(&b),
+            Ok(MultisetVec::from(vec![240, 255, 0, 2]))
+        );
+        assert_eq!(a.wrapping_add(&short), Err(mismatch(4, 1)));
+        assert_eq!(
+            a.wrapping_mul_scalar(2),
+            MultisetVec::from(vec![244, 2, 0, 6])
+        );
+    }
+
+    #[test]
+    fn test_index() {
+        let mut set = MultisetVec::from(vec![1u8, 2, 3, 4]);
+        assert_eq!(set[1], 2);
+        assert_eq!(&set[2..], &[3, 4]);
+        set[1] = 5;
+        assert_eq!(set, MultisetVec::from(vec![1u8, 5, 3, 4]));
+    }
+
+    #[test]
+    fn test_add() {
+        let set = MultisetVec::from(vec![2u8, 2, 2, 2]);
+        assert_eq!(set.clone() + set.clone(), MultisetVec::from(vec![4u8; 4]));
+        assert_eq!(set.clone() + 3, MultisetVec::from(vec![5u8; 4]));
+
+        let mut set_assign = MultisetVec::from(vec![3u8; 4]);
+        set_assign += set;
+        assert_eq!(set_assign, MultisetVec::from(vec![5u8; 4]));
+        set_assign += 2;
+        assert_eq!(set_assign, MultisetVec::from(vec![7u8; 4]));
+    }
+
+    #[test]
+    fn test_div() {
+        let set = MultisetVec::from(vec![10u8; 4]);
+        assert_eq!(set.clone() / set.clone(), MultisetVec::from(vec![1u8; 4]));
+        assert_eq!(set.clone() / 2, MultisetVec::from(vec![5u8; 4]));
+
+        let mut set_assign = MultisetVec::from(vec![20u8; 4]);
+        set_assign /= set;
+        assert_eq!(set_assign, MultisetVec::from(vec![2u8; 4]));
+        set_assign /= 2;
+        assert_eq!(set_assign, MultisetVec::from(vec![1u8; 4]));
+    }
+
+    #[test]
+    fn test_mul() {
+        let set = MultisetVec::from(vec![2u8; 4]);
+        assert_eq!(set.clone() * set.clone(), MultisetVec::from(vec![4u8; 4]));
+        assert_eq!(set.clone() * 3, MultisetVec::from(vec![6u8; 4]));
+
+        let mut set_assign = MultisetVec::from(vec![3u8; 4]);
+        set_assign *= set;
+        assert_eq!(set_assign, MultisetVec::from(vec![6u8; 4]));
+        set_assign *= 2;
+        assert_eq!(set_assign, MultisetVec::from(vec![12u8; 4]));
+    }
+
+    #[test]
+    fn test_rem() {
+        let set = MultisetVec::from(vec![10u8; 4]);
+        assert_eq!(set.clone() % set.clone(), MultisetVec::from(vec![0u8; 4]));
+        assert_eq!(set.clone() % 3, MultisetVec::from(vec![1u8; 4]));
+
+        let mut set_assign = MultisetVec::from(vec![3u8; 4]);
+        set_assign %= set;
+        assert_eq!(set_assign, MultisetVec::from(vec![3u8; 4]));
+        set_assign %= 2;
+        assert_eq!(set_assign, MultisetVec::from(vec![1u8; 4]));
+    }
+
+    #[test]
+    fn test_sub() {
+        let set = MultisetVec::from(vec![5u8; 4]);
+        assert_eq!(set.clone() - set.clone(), MultisetVec::from(vec![0u8; 4]));
+        assert_eq!(set.clone() - 3, MultisetVec::from(vec![2u8; 4]));
+
+        let mut set_assign = MultisetVec::from(vec![8u8; 4]);
+        set_assign -= set;
+        assert_eq!(set_assign, MultisetVec::from(vec![3u8; 4]));
+        set_assign -= 2;
+        assert_eq!(set_assign, MultisetVec::from(vec![1u8; 4]));
+    }
+
+    #[test]
+    fn test_ops_length_mismatch_panic() {
+        let a = MultisetVec::from(vec![1u8, 2, 3]);
+        let b = MultisetVec::from(vec![1u8, 2]);
+        let res = catch_unwind_silent(|| a + b);
+        assert!(res.is_err())
+    }
+
+    #[test]
+    fn test_len() {
+        assert_eq!(MultisetVec::<u16>::empty(9).len(), 9)
+    }
+
+    #[test]
+    fn test_empty() {
+        let result = MultisetVec::<u32>::empty(7);
+        assert_eq!(result, MultisetVec::from(vec![0u32; 7]))
+    }
+
+    #[test]
+    fn test_repeat() {
+        let result = MultisetVec::<u8>::repeat(3, 4);
+        assert_eq!(result, MultisetVec::from(vec![3u8; 4]))
+    }
+
+    #[test]
+    fn test_from_elements() {
+        let into_it = &[0, 1, 1, 2, 2, 2];
+        let result = MultisetVec::<u8>::from_elements(into_it, 4);
+        assert_eq!(result, MultisetVec::from(vec![1, 2, 3, 0]))
+    }
+
+    #[test]
+    fn test_try_from_elements() {
+        let result = MultisetVec::<u8>::try_from_elements(&[1, 1, 0, 2, 2, 2], 4);
+        assert_eq!(result, Ok(MultisetVec::from(vec![1, 2, 3, 0])));
+        let result = MultisetVec::<u8>::try_from_elements(&[1, 7], 4);
+        assert_eq!(result, Err(Error::ElementOutOfRange { element: 7, len: 4 }));
+        let result = MultisetVec::<u8>::try_from_elements(&vec![0; 256], 1);
+        assert_eq!(result, Err(Error::Overflow { element: 0 }));
+    }
+
+    #[test]
+    fn test_try_insert_remove() {
+        let mut set = MultisetVec::<u8>::repeat(3, 3);
+        assert_eq!(set.try_insert(1, 5), Ok(()));
+        assert_eq!(set.try_remove(2), Ok(()));
+        assert_eq!(set, MultisetVec::from(vec![3, 5, 0]));
+        let err = Error::ElementOutOfRange { element: 3, len: 3 };
+        assert_eq!(set.try_insert(3, 5), Err(err));
+        assert_eq!(set.try_remove(3), Err(err));
+    }
+
+    #[test]
+    fn test_from_elements_panic() {
+        let into_it = &[9]; // contains a value larger than the multiset len
+        let res = catch_unwind_silent(|| MultisetVec::<u8>::from_elements(into_it, 4));
+        assert!(res.is_err())
+    }
+
+    #[test]
+    fn test_conversions() {
+        let ms = Multiset::from([5u16, 4, 3]);
+        let set = MultisetVec::from(ms);
+        assert_eq!(set, MultisetVec::from([5u16, 4, 3]));
+        assert_eq!(set, MultisetVec::from(&[5u16, 4, 3][..]));
+        assert_eq!(Multiset::<u16, 3>::try_from(set.clone()), Ok(ms));
+        assert_eq!(
+            Multiset::<u16, 4>::try_from(set.clone()),
+            Err(mismatch(4, 3))
+        );
+        let vec: Vec<u16> = set.into();
+        assert_eq!(vec, vec![5, 4, 3]);
+    }
+
+    #[test]
+    fn test_clear() {
+        let mut set = MultisetVec::<u8>::repeat(3, 4);
+        set.clear();
+        assert_eq!(set, MultisetVec::empty(4))
+    }
+
+    #[test]
+    fn test_contains() {
+        let set = MultisetVec::from(vec![1u8, 0, 1, 0, 1]);
+        [0, 2, 4].iter().for_each(|elem| {
+            assert!(set.contains(*elem));
+        });
+
+        [1, 3, 5, 10].iter().for_each(|elem| {
+            assert!(!set.contains(*elem));
+        });
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut set = MultisetVec::from(vec![1u8, 1, 1, 1]);
+        set.insert(2, 5);
+        assert_eq!(set.get(2), Some(&5));
+        set.insert(9, 5);
+        assert_eq!(set, MultisetVec::from(vec![1u8, 1, 5, 1]));
+        set.remove(2);
+        assert_eq!(set.get(2), Some(&0));
+        set.remove(9);
+        assert_eq!(set, MultisetVec::from(vec![1u8, 1, 0, 1]));
+    }
+
+    #[test]
+    fn test_intersection() {
+        let a = MultisetVec::from(vec![1u8, 2, 5, 6]);
+        let b = MultisetVec::from(vec![0, 1, 8, 9]);
+        let c = MultisetVec::from(vec![0, 1, 5, 6]);
+        assert_eq!(a.intersection(&b), Ok(c));
+        assert_eq!(a.intersection(&MultisetVec::empty(2)), Err(mismatch(4, 2)));
+    }
+
+    #[test]
+    fn test_union() {
+        let a = MultisetVec::from(vec![1u8, 2, 5, 6]);
+        let b = MultisetVec::from(vec![0, 1, 8, 9]);
+        let c = MultisetVec::from(vec![1, 2, 8, 9]);
+        assert_eq!(a.union(&b), Ok(c));
+        assert_eq!(a.union(&MultisetVec::empty(5)), Err(mismatch(4, 5)));
+    }
+
+    #[test]
+    fn test_difference() {
+        let a = MultisetVec::from(vec![0u8, 2, 5, 6]);
+        let b = MultisetVec::from(vec![1, 1, 8, 0]);
+        let c = MultisetVec::from(vec![0, 1, 5, 6]);
+        assert_eq!(a.difference(&b), Ok(c))
+    }
+
+    #[test]
+    fn test_symmetric_difference() {
+        let a = MultisetVec::from(vec![0u8, 2, 5, 6]);
+        let b = MultisetVec::from(vec![1, 1, 8, 0]);
+        let c = MultisetVec::from(vec![1, 1, 5, 6]);
+        assert_eq!(a.symmetric_difference(&b), Ok(c))
+    }
+
+    #[test]
+    fn test_count_zero_non_zero() {
+        let set = MultisetVec::from(vec![0u16, 1, 3, 0, 8, 0, 0]);
+        assert_eq!(set.count_zero(), 4);
+        assert_eq!(set.count_non_zero(), 3);
+    }
+
+    #[test]
+    fn test_is_empty() {
+        assert!(!MultisetVec::from(vec![1u8; 3]).is_empty());
+        assert!(MultisetVec::<u8>::empty(3).is_empty());
+        assert!(MultisetVec::<u8>::default().is_empty());
+    }
+
+    #[test]
+    fn test_is_singleton() {
+        assert!(MultisetVec::from(vec![0u16, 0, 8, 0]).is_singleton());
+        assert!(!MultisetVec::from(vec![0u16, 2, 8, 0]).is_singleton());
+        assert!(!MultisetVec::<u16>::empty(4).is_singleton());
+    }
+
+    #[test]
+    fn test_is_disjoint() {
+        let a = MultisetVec::from(vec![1u8, 1, 0, 0]);
+        let b = MultisetVec::from(vec![0, 0, 1, 1]);
+        let c = MultisetVec::from(vec![0, 1, 1, 0]);
+        assert_eq!(a.is_disjoint(&b), Ok(true));
+        assert_eq!(a.is_disjoint(&c), Ok(false));
+        assert_eq!(a.is_disjoint(&MultisetVec::empty(3)), Err(mismatch(4, 3)));
+    }
+
+    #[test]
+    fn test_subset_superset() {
+        let a = MultisetVec::from(vec![1u8; 3]);
+        let b = MultisetVec::from(vec![2u8; 3]);
+        let c = MultisetVec::from(vec![0u8, 1, 2]);
+
+        assert_eq!(a.is_subset(&b), Ok(true));
+        assert_eq!(a.is_subset(&c), Ok(false));
+        assert_eq!(b.is_superset(&a), Ok(true));
+        assert_eq!(c.is_superset(&a), Ok(false));
+        assert_eq!(a.is_proper_subset(&b), Ok(true));
+        assert_eq!(a.is_proper_subset(&a), Ok(false));
+        assert_eq!(b.is_proper_superset(&a), Ok(true));
+        assert_eq!(b.is_proper_superset(&b), Ok(false));
+        assert_eq!(a.is_subset(&MultisetVec::empty(2)), Err(mismatch(3, 2)));
+    }
+
+    #[test]
+    fn test_is_any_lesser_greater() {
+        let a = MultisetVec::from(vec![1u8; 3]);
+        let c = MultisetVec::from(vec![0u8, 1, 2]);
+        assert_eq!(a.is_any_lesser(&c), Ok(true));
+        assert_eq!(a.is_any_greater(&c), Ok(true));
+        assert_eq!(a.is_any_lesser(&a), Ok(false));
+        assert_eq!(a.is_any_greater(&a), Ok(false));
+    }
+
+    #[test]
+    fn test_partial_ord() {
+        let a = MultisetVec::from(vec![1u8; 3]);
+        let b = MultisetVec::from(vec![2u8; 3]);
+        let c = MultisetVec::from(vec![0u8, 1, 2]);
+        assert!(a < b);
+        assert!(b >= a);
+        assert_eq!(a.partial_cmp(&c), None);
+        assert_eq!(a.partial_cmp(&MultisetVec::from(vec![1u8; 4])), None);
+        assert!(!a.le(&MultisetVec::from(vec![1u8; 4])));
+    }
+
+    #[test]
+    fn test_total() {
+        let set = MultisetVec::from(vec![1u8, 2, 3, 4]);
+        assert_eq!(set.total(), 10)
+    }
+
+    #[test]
+    fn test_max_min() {
+        let set = MultisetVec::from(vec![1u8, 0, 3, 1]);
+        assert_eq!(set.elem_count_max(), (2, &3));
+        assert_eq!(set.elem_max(), 2);
+        assert_eq!(set.count_max(), &3);
+        assert_eq!(set.elem_count_min(), (1, &0));
+        assert_eq!(set.elem_min(), 1);
+        assert_eq!(set.count_min(), &0);
+    }
+
+    #[test]
+    fn test_cast() {
+        let set = MultisetVec::from(vec![1u8, 255, 0]);
+        assert_eq!(set.cast::<u64>(), MultisetVec::from(vec![1u64, 255, 0]));
+
+        let set = MultisetVec::from(vec![1u32, 300, 0, 256]);
+        assert_eq!(set.try_cast::<u8>(), Err(Error::Overflow { element: 1 }));
+        assert_eq!(
+            set.saturating_cast::<u8>(),
+            MultisetVec::from(vec![1u8, 255, 0, 255])
+        );
+        assert_eq!(
+            set.try_cast::<u16>(),
+            Ok(MultisetVec::from(vec![1u16, 300, 0, 256]))
+        );
+        assert_eq!(MultisetVec::<u32>::empty(0).cast::<u64>().len(), 0);
+    }
+
+    #[test]
+    fn test_max_no_elements_panic() {
+        let res = catch_unwind_silent(|| *MultisetVec::<u8>::default().count_max());
+        assert!(res.is_err())
+    }
+
+    #[test]
+    fn test_choose() {
+        let mut set = MultisetVec::from(vec![1u8, 2, 3, 4, 5]);
+        set.choose(3);
+        assert_eq!(set, MultisetVec::from(vec![0u8, 0, 0, 4, 0]))
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_choose_random() {
+        let original = MultisetVec::from(vec![1u8, 2, 3, 4, 5]);
+        let mut result = original.clone();
+        let test_rng = &mut StdRng::seed_from_u64(thread_rng().next_u64());
+        result.choose_random(test_rng);
+        assert!(result.is_singleton() && result.is_subset(&original) == Ok(true));
+
+        let mut empty = MultisetVec::<u32>::empty(5);
+        empty.choose_random(test_rng);
+        assert_eq!(empty, MultisetVec::empty(5));
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_collision_entropy() {
+        let simple = MultisetVec::from(vec![200u8, 0, 0, 0]);
+        assert_eq!(simple.collision_entropy(), 0.0);
+
+        let set = MultisetVec::from(vec![2u8, 1, 1, 0]);
+        assert_relative_eq!(
+            set.collision_entropy(),
+            1.415037499278844,
+            epsilon = f64::EPSILON
+        );
+    }
+
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[test]
+    fn test_shannon_entropy() {
+        let a = MultisetVec::from(vec![200u8, 0, 0, 0]);
+        assert_eq!(a.shannon_entropy(), 0.0);
+
+        let b = MultisetVec::from(vec![2u8, 1, 1, 0]);
+        assert_relative_eq!(
+            b.shannon_entropy(),
+            1.0397207708399179,
+            epsilon = f64::EPSILON
+        );
+    }
+}
@@ -0,0 +1,337 @@
+use crate::{Counter, Multiset, ParseError, ParseErrorKind};
+use alloc::vec;
+use core::str::FromStr;
+
+/// Parses a multiset from either of the forms written by its
+/// [`Display`](std::fmt::Display) impl: the sparse `{element: count, ...}`
+/// notation, where missing elements have a count of zero, or the dense
+/// `[count, ...]` list of every count.
+///
+/// Whitespace is allowed between tokens and a trailing comma is accepted. On
+/// failure the returned [`ParseError`] holds the byte offset of the problem.
+///
+/// # Examples
+///
+/// ```
+/// use utote::{Multiset, ParseErrorKind};
+///
+/// let multiset: Multiset<u8, 4> = "{0: 3, 2: 1}".parse().unwrap();
+/// assert_eq!(multiset, Multiset::from([3, 0, 1, 0]));
+///
+/// let multiset: Multiset<u8, 4> = "[3, 0, 1, 0]".parse().unwrap();
+/// assert_eq!(multiset, Multiset::from([3, 0, 1, 0]));
+///
+/// let err = "[3, 0, 1]".parse::<Multiset<u8, 4>>().unwrap_err();
+/// assert_eq!(err.offset(), 8);
+/// assert_eq!(
+///     err.kind(),
+///     &ParseErrorKind::LengthMismatch { expected: 4, found: 3 }
+/// );
+/// ```
+impl<N: Counter, const SIZE: usize> FromStr for Multiset<N, SIZE> {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser { input: s, pos: 0 };
+        let mut res = Multiset::empty();
+        parser.skip_whitespace();
+        match parser.peek() {
+            Some('{') => parser.sparse(&mut res.data)?,
+            Some('[') => parser.dense(&mut res.data)?,
+            _ => return Err(parser.unexpected("'{' or '['")),
+        }
+        parser.skip_whitespace();
+        if parser.peek().is_some() {
+            return Err(parser.unexpected("end of input"));
+        }
+        Ok(res)
+    }
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn peek(&self) -> Option<char> {
+        self.input[self.pos..].chars().next()
+    }
+
+    fn error(&self, offset: usize, kind: ParseErrorKind) -> ParseError {
+        ParseError { offset, kind }
+    }
+
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        let kind = match self.peek() {
+            Some(found) => ParseErrorKind::UnexpectedChar { found, expected },
+            None => ParseErrorKind::UnexpectedEnd,
+        };
+        self.error(self.pos, kind)
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if !c.is_whitespace() {
+                break;
+            }
+            self.pos += c.len_utf8();
+        }
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, c: char, expected: &'static str) -> Result<(), ParseError> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    // Parses a run of ascii digits, returning the value if it fits in a u128
+    // along with the offset at which it starts.
+    fn number(&mut self) -> Result<(Option<u128>, usize), ParseError> {
+        let start = self.pos;
+        let digits = self.input[start..]
+            .bytes()
+            .take_while(u8::is_ascii_digit)
+            .count();
+        if digits == 0 {
+            return Err(self.unexpected("a number"));
+        }
+        self.pos += digits;
+        Ok((self.input[start..self.pos].parse().ok(), start))
+    }
+
+    fn count<N: Counter>(&mut self) -> Result<N, ParseError> {
+        let (value, start) = self.number()?;
+        value
+            .and_then(N::from_u128)
+            .ok_or_else(|| self.error(start, ParseErrorKind::CounterOverflow))
+    }
+
+    fn element(&mut self, len: usize) -> Result<usize, ParseError> {
+        let (value, start) = self.number()?;
+        match value {
+            Some(elem) if elem < len as u128 => Ok(elem as usize),
+            _ => Err(self.error(start, ParseErrorKind::ElementOutOfRange { len })),
+        }
+    }
+
+    // Parses comma separated items after an opening bracket, up to and
+    // including the closing bracket.
+    fn list<F>(
+        &mut self,
+        close: char,
+        expected: &'static str,
+        mut item: F,
+    ) -> Result<(), ParseError>
+    where
+        F: FnMut(&mut Self) -> Result<(), ParseError>,
+    {
+        self.pos += 1;
+        self.skip_whitespace();
+        if self.eat(close) {
+            return Ok(());
+        }
+        loop {
+            item(self)?;
+            self.skip_whitespace();
+            if self.eat(close) {
+                return Ok(());
+            }
+            self.expect(',', expected)?;
+            self.skip_whitespace();
+            if self.eat(close) {
+                return Ok(());
+            }
+        }
+    }
+
+    fn sparse<N: Counter>(&mut self, data: &mut [N]) -> Result<(), ParseError> {
+        let mut seen = vec![false; data.len()];
+        self.list('}', "',' or '}'", |p| {
+            let start = p.pos;
+            let elem = p.element(data.len())?;
+            if seen[elem] {
+                return Err(p.error(start, ParseErrorKind::DuplicateElement));
+            }
+            seen[elem] = true;
+            p.skip_whitespace();
+            p.expect(':', "':'")?;
+            p.skip_whitespace();
+            data[elem] = p.count()?;
+            Ok(())
+        })
+    }
+
+    fn dense<N: Counter>(&mut self, data: &mut [N]) -> Result<(), ParseError> {
+        let mut found = 0;
+        self.list(']', "',' or ']'", |p| {
+            let count = p.count()?;
+            if let Some(c) = data.get_mut(found) {
+                *c = count;
+            }
+            found += 1;
+            Ok(())
+        })?;
+        if found != data.len() {
+            let kind = ParseErrorKind::LengthMismatch {
+                expected: data.len(),
+                found,
+            };
+            return Err(self.error(self.pos - 1, kind));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_err<N: Counter, const SIZE: usize>(s: &str) -> (usize, ParseErrorKind) {
+        let err = s.parse::<Multiset<N, SIZE>>().unwrap_err();
+        (err.offset(), err.kind().clone())
+    }
+
+    #[test]
+    fn test_display_round_trip() {
+        let sets = [
+            Multiset::from([3u32, 0, 1, 0, 0, 0, 0, 12]),
+            Multiset::from([0u32; 8]),
+            Multiset::from([u32::MAX; 8]),
+        ];
+        for set in sets.iter() {
+            assert_eq!(format!("{}", set).parse::<Multiset<u32, 8>>(), Ok(*set));
+            assert_eq!(format!("{:#}", set).parse::<Multiset<u32, 8>>(), Ok(*set));
+        }
+        assert_eq!(format!("{}", sets[0]), "{0: 3, 2: 1, 7: 12}");
+        assert_eq!(format!("{:#}", sets[1]), "[0, 0, 0, 0, 0, 0, 0, 0]");
+
+        let empty = Multiset::<u128, 0>::empty();
+        assert_eq!(format!("{}", empty), "{}");
+        assert_eq!(format!("{:#}", empty), "[]");
+        assert_eq!("{}".parse(), Ok(empty));
+        assert_eq!("[]".parse(), Ok(empty));
+    }
+
+    #[test]
+    fn test_parse_whitespace() {
+        let expected = Multiset::from([3u8, 0, 1]);
+        assert_eq!(" {\n 2 :1,0: 3 ,}\t".parse(), Ok(expected));
+        assert_eq!("[3,0,1]".parse(), Ok(expected));
+        assert_eq!("[ 3 , 0 , 1 , ]".parse(), Ok(expected));
+        assert_eq!("{ }".parse(), Ok(Multiset::<u8, 3>::empty()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        use ParseErrorKind::*;
+
+        assert_eq!(parse_err::<u8, 3>(""), (0, UnexpectedEnd));
+        assert_eq!(parse_err::<u8, 3>("  {0: 1"), (7, UnexpectedEnd));
+        assert_eq!(
+            parse_err::<u8, 3>("(0: 1)"),
+            (
+                0,
+                UnexpectedChar {
+                    found: '(',
+                    expected: "'{' or '['"
+                }
+            )
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("{0 1}"),
+            (
+                3,
+                UnexpectedChar {
+                    found: '1',
+                    expected: "':'"
+                }
+            )
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("{0: 1; 1: 2}"),
+            (
+                5,
+                UnexpectedChar {
+                    found: ';',
+                    expected: "',' or '}'"
+                }
+            )
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("{0: x}"),
+            (
+                4,
+                UnexpectedChar {
+                    found: 'x',
+                    expected: "a number"
+                }
+            )
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("[1, 2, 3] x"),
+            (
+                10,
+                UnexpectedChar {
+                    found: 'x',
+                    expected: "end of input"
+                }
+            )
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("{é: 1}"),
+            (
+                1,
+                UnexpectedChar {
+                    found: 'é',
+                    expected: "a number"
+                }
+            )
+        );
+        assert_eq!(parse_err::<u8, 3>("{0: 256}"), (4, CounterOverflow));
+        assert_eq!(
+            parse_err::<u128, 3>("[0, 0, 340282366920938463463374607431768211456]"),
+            (7, CounterOverflow)
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("{0: 1, 3: 1}"),
+            (7, ElementOutOfRange { len: 3 })
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("{99999999999999999999999999999999999999999: 1}"),
+            (1, ElementOutOfRange { len: 3 })
+        );
+        assert_eq!(parse_err::<u8, 3>("{1: 1, 1: 2}"), (7, DuplicateElement));
+        assert_eq!(
+            parse_err::<u8, 3>("[1, 2]"),
+            (
+                5,
+                LengthMismatch {
+                    expected: 3,
+                    found: 2
+                }
+            )
+        );
+        assert_eq!(
+            parse_err::<u8, 3>("[1, 2, 3, 4, 5]"),
+            (
+                14,
+                LengthMismatch {
+                    expected: 3,
+                    found: 5
+                }
+            )
+        );
+    }
+}
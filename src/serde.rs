@@ -0,0 +1,281 @@
+//! Serde support, enabled with the `serde` feature.
+//!
+//! By default `Multiset` and `MultisetVec` are serialized densely, as a
+//! sequence holding the count of every element. Multisets which mostly hold
+//! zero counts can instead use the [`sparse`] representation, a map from each
+//! element to its count which omits elements with a count of zero.
+//!
+//! Deserializing a `Multiset` checks that the number of counts matches `SIZE`
+//! for the dense representation, and that every element is less than `SIZE`
+//! for the sparse representation.
+//!
+//! # Examples
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use utote::Multiset;
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct Config {
+//!     dense: Multiset<u8, 4>,
+//!     #[serde(with = "utote::serde::sparse")]
+//!     sparse: Multiset<u8, 4>,
+//! }
+//!
+//! let config = Config {
+//!     dense: Multiset::from([1, 0, 0, 2]),
+//!     sparse: Multiset::from([1, 0, 0, 2]),
+//! };
+//! let json = serde_json::to_string(&config).unwrap();
+//! assert_eq!(json, r#"{"dense":[1,0,0,2],"sparse":{"0":1,"3":2}}"#);
+//! ```
+
+use crate::{Counter, Error, Multiset, MultisetVec};
+use ::serde::de::{Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use ::serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::{self, Formatter};
+use core::marker::PhantomData;
+
+////////////////////////////////////////////////////////////////////////////////
+// Dense representation
+////////////////////////////////////////////////////////////////////////////////
+
+impl<N: Counter + Serialize, const SIZE: usize> Serialize for Multiset<N, SIZE> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(SIZE))?;
+        for count in self.data.iter() {
+            seq.serialize_element(count)?;
+        }
+        seq.end()
+    }
+}
+
+struct DenseVisitor<N, const SIZE: usize>(PhantomData<N>);
+
+impl<'de, N: Counter + Deserialize<'de>, const SIZE: usize> Visitor<'de> for DenseVisitor<N, SIZE> {
+    type Value = Multiset<N, SIZE>;
+
+    fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "a sequence of {} counts", SIZE)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut res = Multiset::empty();
+        for (i, count) in res.data.iter_mut().enumerate() {
+            *count = seq
+                .next_element()?
+                .ok_or_else(|| ::serde::de::Error::invalid_length(i, &self))?;
+        }
+        // Drain any extra counts so that the error reports the real length.
+        let mut len = SIZE;
+        while seq.next_element::<IgnoredAny>()?.is_some() {
+            len += 1;
+        }
+        if len != SIZE {
+            return Err(::serde::de::Error::invalid_length(len, &self));
+        }
+        Ok(res)
+    }
+}
+
+impl<'de, N: Counter + Deserialize<'de>, const SIZE: usize> Deserialize<'de> for Multiset<N, SIZE> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_seq(DenseVisitor(PhantomData))
+    }
+}
+
+impl<N: Counter + Serialize> Serialize for MultisetVec<N> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.data.serialize(serializer)
+    }
+}
+
+impl<'de, N: Counter + Deserialize<'de>> Deserialize<'de> for MultisetVec<N> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(MultisetVec::from)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// Sparse representation
+////////////////////////////////////////////////////////////////////////////////
+
+/// Serializes a `Multiset` as a map from element to count, omitting elements
+/// with a count of zero.
+///
+/// Intended for use with `#[serde(with = "utote::serde::sparse")]`. When
+/// deserializing, elements which are not less than `SIZE` and elements which
+/// appear more than once are rejected.
+///
+/// # Examples
+///
+/// ```
+/// use utote::Multiset;
+///
+/// let multiset = Multiset::from([0u16, 3, 0, 0, 1]);
+///
+/// let mut json = Vec::new();
+/// let mut serializer = serde_json::Serializer::new(&mut json);
+/// utote::serde::sparse::serialize(&multiset, &mut serializer).unwrap();
+/// assert_eq!(json, br#"{"1":3,"4":1}"#);
+///
+/// let mut deserializer = serde_json::Deserializer::from_slice(&json);
+/// let decoded: Multiset<u16, 5> = utote::serde::sparse::deserialize(&mut deserializer).unwrap();
+/// assert_eq!(decoded, multiset);
+/// ```
+pub mod sparse {
+    use super::*;
+
+    /// Serializes `multiset` as a map from element to count.
+    pub fn serialize<N, S, const SIZE: usize>(
+        multiset: &Multiset<N, SIZE>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        N: Counter + Serialize,
+        S: Serializer,
+    {
+        let non_zero = multiset
+            .data
+            .iter()
+            .filter(|count| !count.is_zero())
+            .count();
+        let mut map = serializer.serialize_map(Some(non_zero))?;
+        for (elem, count) in multiset.data.iter().enumerate() {
+            if !count.is_zero() {
+                map.serialize_entry(&elem, count)?;
+            }
+        }
+        map.end()
+    }
+
+    /// Deserializes a `Multiset` from a map of element to count.
+    pub fn deserialize<'de, N, D, const SIZE: usize>(
+        deserializer: D,
+    ) -> Result<Multiset<N, SIZE>, D::Error>
+    where
+        N: Counter + Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_map(SparseVisitor(PhantomData))
+    }
+
+    struct SparseVisitor<N, const SIZE: usize>(PhantomData<N>);
+
+    impl<'de, N: Counter + Deserialize<'de>, const SIZE: usize> Visitor<'de>
+        for SparseVisitor<N, SIZE>
+    {
+        type Value = Multiset<N, SIZE>;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> fmt::Result {
+            write!(f, "a map of elements less than {} to counts", SIZE)
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+            let mut res = Multiset::<N, SIZE>::empty();
+            let mut seen = vec![false; SIZE];
+            while let Some(elem) = map.next_key::<usize>()? {
+                if elem >= SIZE {
+                    return Err(::serde::de::Error::custom(Error::ElementOutOfRange {
+                        element: elem,
+                        len: SIZE,
+                    }));
+                }
+                if seen[elem] {
+                    return Err(::serde::de::Error::custom(format_args!(
+                        "duplicate element {}",
+                        elem
+                    )));
+                }
+                seen[elem] = true;
+                res.data[elem] = map.next_value()?;
+            }
+            Ok(res)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+    struct Sparse {
+        #[serde(with = "crate::serde::sparse")]
+        set: Multiset<u32, 4>,
+    }
+
+    #[test]
+    fn test_dense_round_trip() {
+        let set = Multiset::from([1u32, 0, 7, 2]);
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, "[1,0,7,2]");
+        assert_eq!(
+            serde_json::from_str::<Multiset<u32, 4>>(&json).unwrap(),
+            set
+        );
+
+        let set = Multiset::from([u128::MAX, 0]);
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Multiset<u128, 2>>(&json).unwrap(),
+            set
+        );
+
+        let set = MultisetVec::from(vec![3u8, 0, 1]);
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, "[3,0,1]");
+        assert_eq!(serde_json::from_str::<MultisetVec<u8>>(&json).unwrap(), set);
+    }
+
+    #[test]
+    fn test_dense_length() {
+        let err = serde_json::from_str::<Multiset<u32, 4>>("[1,0,7]").unwrap_err();
+        assert!(err.to_string().contains("invalid length 3"));
+
+        let err = serde_json::from_str::<Multiset<u32, 4>>("[1,0,7,2,5]").unwrap_err();
+        assert!(err.to_string().contains("invalid length 5"));
+
+        let err = serde_json::from_str::<Multiset<u32, 4>>("[1,0,7,2,5,6]").unwrap_err();
+        assert!(err.to_string().contains("invalid length 6"));
+
+        assert!(serde_json::from_str::<Multiset<u8, 2>>("[1,256]").is_err());
+    }
+
+    #[test]
+    fn test_sparse_round_trip() {
+        let sparse = Sparse {
+            set: Multiset::from([0, 5, 0, 1]),
+        };
+        let json = serde_json::to_string(&sparse).unwrap();
+        assert_eq!(json, r#"{"set":{"1":5,"3":1}}"#);
+        assert_eq!(serde_json::from_str::<Sparse>(&json).unwrap(), sparse);
+
+        let empty = Sparse {
+            set: Multiset::empty(),
+        };
+        let json = serde_json::to_string(&empty).unwrap();
+        assert_eq!(json, r#"{"set":{}}"#);
+        assert_eq!(serde_json::from_str::<Sparse>(&json).unwrap(), empty);
+
+        // explicit zero counts are accepted
+        let json = r#"{"set":{"0":0,"2":4}}"#;
+        assert_eq!(
+            serde_json::from_str::<Sparse>(json).unwrap().set,
+            Multiset::from([0, 0, 4, 0])
+        );
+    }
+
+    #[test]
+    fn test_sparse_invalid() {
+        let err = serde_json::from_str::<Sparse>(r#"{"set":{"4":1}}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("element 4 out of range for multiset of 4 elements"));
+
+        let err = serde_json::from_str::<Sparse>(r#"{"set":{"1":1,"1":2}}"#).unwrap_err();
+        assert!(err.to_string().contains("duplicate element 1"));
+    }
+}
@@ -1,34 +1,47 @@
+use crate::backend::{self, Backend};
 use crate::chunks::ChunkUtils;
-use crate::{Counter, Multiset};
+use crate::distance;
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::entropy;
+use crate::kernels;
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::math;
+use crate::multiset_ops::check_same_len;
+use crate::{Counter, Error, Multiset, MultisetSlice, MultisetVec};
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::{EntropyEstimator, LogBase};
+use alloc::vec;
+use core::cmp::Ordering;
+use core::convert::TryFrom;
+use core::fmt::Debug;
+use core::mem::size_of;
+#[cfg(any(feature = "std", feature = "libm"))]
+use core::mem::MaybeUninit;
+use core::ops::{Add, AddAssign, Div, Mul, MulAssign, Sub, SubAssign};
 use num_traits::AsPrimitive;
-use packed_simd::*;
 use paste::paste;
 #[cfg(feature = "rand")]
 use rand::{Rng, RngCore};
-use std::fmt::Debug;
-use std::mem::MaybeUninit;
-use std::ops::{Add, Div, Mul};
-use std::cmp::Ordering;
 
 mod sealed {
-    use packed_simd::*;
-
     pub trait Sealed {}
 
     macro_rules! impl_sealed {
         ($($t:ty),*) => {$(impl Sealed for $t {})*};
     }
 
-    impl_sealed!(
-        u8, u16, u32, u64, usize, f64, m8x16, m8x32, m16x8, m16x16, m32x4, m32x8, msizex4, m64x4,
-        u8x16, u8x32, u16x8, u16x16, u32x4, u32x8, u64x4, usizex4, f64x4
-    );
+    impl_sealed!(u8, u16, u32, u64, u128, usize, f64);
 }
 
+#[cfg(feature = "simd")]
+mod packed;
+#[cfg(feature = "portable_simd")]
+mod portable;
+
 #[doc(hidden)]
 pub trait SimdTypes: sealed::Sealed + Sized {
-    type SIMD128: SimdBasic<Self>;
-    type SIMD256: SimdBasic<Self>;
+    type SIMD128: SimdBasic<Self> + SimdConvert<Self>;
+    type SIMD256: SimdBasic<Self> + SimdConvert<Self>;
     type SIMDFloat: SimdBasic<f64> + SimdFloat<f64>;
 
     const L128: usize;
@@ -36,26 +49,6 @@ pub trait SimdTypes: sealed::Sealed + Sized {
     const LF: usize;
 }
 
-macro_rules! impl_simd_types {
-    ($scalar:ty, $simd128:ty, $simd256:ty) => {
-        impl SimdTypes for $scalar {
-            type SIMD128 = $simd128;
-            type SIMD256 = $simd256;
-            type SIMDFloat = f64x4;
-
-            const L128: usize = <$simd128>::lanes();
-            const L256: usize = <$simd256>::lanes();
-            const LF: usize = f64x4::lanes();
-        }
-    };
-}
-
-impl_simd_types!(u8, u8x16, u8x32);
-impl_simd_types!(u16, u16x8, u16x16);
-impl_simd_types!(u32, u32x4, u32x8);
-impl_simd_types!(u64, u64x4, u64x4);
-impl_simd_types!(usize, usizex4, usizex4);
-
 #[doc(hidden)]
 pub trait SimdBool<N>: sealed::Sealed {
     type Select: SimdBasic<N>;
@@ -65,47 +58,15 @@ pub trait SimdBool<N>: sealed::Sealed {
     fn select(self, a: Self::Select, b: Self::Select) -> Self::Select;
 }
 
-macro_rules! impl_simd_bool {
-    ($scalar:ty, $simd:ty, $simd_bool:ty) => {
-        impl SimdBool<$scalar> for $simd_bool {
-            type Select = $simd;
-
-            #[inline]
-            fn all(self) -> bool {
-                Self::all(self)
-            }
-
-            #[inline]
-            fn any(self) -> bool {
-                Self::any(self)
-            }
-
-            #[inline]
-            fn count_true(self) -> usize {
-                Self::bitmask(self).count_ones() as usize
-            }
-
-            #[inline]
-            fn select(self, a: Self::Select, b: Self::Select) -> Self::Select {
-                Self::select(self, a, b)
-            }
-        }
-    };
-}
-
-impl_simd_bool!(u8, u8x16, m8x16);
-impl_simd_bool!(u8, u8x32, m8x32);
-impl_simd_bool!(u16, u16x8, m16x8);
-impl_simd_bool!(u16, u16x16, m16x16);
-impl_simd_bool!(u32, u32x4, m32x4);
-impl_simd_bool!(u32, u32x8, m32x8);
-impl_simd_bool!(u64, u64x4, m64x4);
-impl_simd_bool!(usize, usizex4, msizex4);
-impl_simd_bool!(f64, f64x4, m64x4);
-
 #[doc(hidden)]
 pub trait SimdBasic<N>:
-    sealed::Sealed + Copy + PartialEq + Add<Self, Output = Self> + Debug
+    sealed::Sealed
+    + Copy
+    + PartialEq
+    + Add<Self, Output = Self>
+    + Sub<Self, Output = Self>
+    + Mul<Self, Output = Self>
+    + Debug
 {
     const LANES: usize;
     type SIMDBool: SimdBool<N, Select = Self>;
@@ -120,72 +81,27 @@ pub trait SimdBasic<N>:
     fn lt(self, other: Self) -> Self::SIMDBool;
 }
 
-macro_rules! impl_simd_basic {
-    ($scalar:ty, $simd:ty, $simd_bool:ty) => {
-        impl SimdBasic<$scalar> for $simd {
-            const LANES: usize = <$simd>::lanes();
-
-            type SIMDBool = $simd_bool;
-
-            #[inline]
-            fn splat(value: $scalar) -> Self {
-                Self::splat(value)
-            }
-
-            #[inline]
-            unsafe fn from_slice_unaligned_unchecked(slice: &[$scalar]) -> Self {
-                Self::from_slice_unaligned_unchecked(slice)
-            }
-
-            #[inline]
-            unsafe fn write_to_slice_unaligned_unchecked(self, slice: &mut [$scalar]) {
-                Self::write_to_slice_unaligned_unchecked(self, slice);
-            }
-
-            //noinspection RsUnresolvedReference
-            #[inline]
-            fn max(self, other: Self) -> Self {
-                Self::max(self, other)
-            }
-
-            //noinspection RsUnresolvedReference
-            #[inline]
-            fn min(self, other: Self) -> Self {
-                Self::min(self, other)
-            }
-
-            #[inline]
-            fn ge(self, other: Self) -> Self::SIMDBool {
-                Self::ge(self, other)
-            }
-
-            #[inline]
-            fn gt(self, other: Self) -> Self::SIMDBool {
-                Self::gt(self, other)
-            }
-
-            #[inline]
-            fn le(self, other: Self) -> Self::SIMDBool {
-                Self::le(self, other)
-            }
+#[doc(hidden)]
+pub trait SimdConvert<N>: SimdBasic<N> {
+    // Converts each lane to `N2` and writes `Self::LANES` counts to `slice`.
+    // Every lane must fit in `N2`.
+    unsafe fn cast_to_slice_unaligned_unchecked<N2: Counter>(self, slice: &mut [N2]);
+}
 
-            #[inline]
-            fn lt(self, other: Self) -> Self::SIMDBool {
-                Self::lt(self, other)
-            }
-        }
-    };
+// Converts lanes one at a time, for vectors which have no counterpart with the
+// same number of lanes for every counter type.
+#[inline]
+fn cast_lanes<N: Counter, N2: Counter>(lanes: &[N], slice: &mut [N2]) {
+    for (s, l) in slice.iter_mut().zip(lanes.iter()) {
+        *s = N2::from_u128(<N as AsPrimitive<u128>>::as_(*l)).unwrap_or_else(N2::max_value);
+    }
 }
 
-impl_simd_basic!(u8, u8x16, m8x16);
-impl_simd_basic!(u8, u8x32, m8x32);
-impl_simd_basic!(u16, u16x8, m16x8);
-impl_simd_basic!(u16, u16x16, m16x16);
-impl_simd_basic!(u32, u32x4, m32x4);
-impl_simd_basic!(u32, u32x8, m32x8);
-impl_simd_basic!(u64, u64x4, m64x4);
-impl_simd_basic!(usize, usizex4, msizex4);
-impl_simd_basic!(f64, f64x4, m64x4);
+// The largest count of `N` which fits in `N2`.
+#[inline]
+fn cast_max<N: Counter, N2: Counter>() -> N {
+    N::from_u128(<N2 as AsPrimitive<u128>>::as_(N2::max_value())).unwrap_or_else(N::max_value)
+}
 
 #[doc(hidden)]
 pub trait SimdFloat<N>
@@ -194,78 +110,296 @@ where
         + Copy
         + Add<Self, Output = Self>
         + Mul<Self, Output = Self>
-        + Div<f64, Output = Self>,
+        + Div<Self, Output = Self>,
 {
     type SIMDBool: SimdBool<N, Select = Self>;
     //noinspection RsSelfConvention
     fn is_nan(self) -> Self::SIMDBool;
+    #[cfg(any(feature = "std", feature = "libm"))]
     fn ln(self) -> Self;
+    #[cfg(any(feature = "std", feature = "libm"))]
     fn powf(self, other: Self) -> Self;
+    #[cfg(any(feature = "std", feature = "libm"))]
+    fn sqrt(self) -> Self;
     fn sum(self) -> N;
 }
 
-impl SimdFloat<f64> for f64x4 {
-    type SIMDBool = m64x4;
+macro_rules! intersection_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> Self {
+            let mut data = core::mem::MaybeUninit::<[N; SIZE]>::uninit().assume_init();
+            self.data
+                .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    simd_a.min(simd_b).write_to_slice_unaligned_unchecked(out);
+                });
+            Multiset { data }
+        }
+    };
+}
 
-    #[inline]
-    fn is_nan(self) -> Self::SIMDBool {
-        Self::is_nan(self)
-    }
+macro_rules! union_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> Self {
+            let mut data = core::mem::MaybeUninit::<[N; SIZE]>::uninit().assume_init();
+            self.data
+                .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    simd_a.max(simd_b).write_to_slice_unaligned_unchecked(out);
+                });
+            Multiset { data }
+        }
+    };
+}
 
-    //noinspection RsUnresolvedReference
-    #[inline]
-    fn ln(self) -> Self {
-        Self::ln(self)
-    }
+macro_rules! intersection_vec_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> MultisetVec<N> {
+            let mut data = vec![N::zero(); self.data.len()];
+            self.data
+                .zip_map_chunks::<_, $lanes>(other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    simd_a.min(simd_b).write_to_slice_unaligned_unchecked(out);
+                });
+            MultisetVec { data }
+        }
+    };
+}
 
-    //noinspection RsUnresolvedReference
-    #[inline]
-    fn powf(self, other: Self) -> Self {
-        Self::powf(self, other)
-    }
+macro_rules! union_vec_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> MultisetVec<N> {
+            let mut data = vec![N::zero(); self.data.len()];
+            self.data
+                .zip_map_chunks::<_, $lanes>(other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    simd_a.max(simd_b).write_to_slice_unaligned_unchecked(out);
+                });
+            MultisetVec { data }
+        }
+    };
+}
 
-    #[inline]
-    fn sum(self) -> f64 {
-        Self::sum(self)
-    }
+macro_rules! saturating_add_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> Self {
+            let mut data = [N::zero(); SIZE];
+            self.data
+                .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    (simd_a + simd_b.min(<$simd>::splat(N::max_value()) - simd_a)).write_to_slice_unaligned_unchecked(out);
+                });
+            Multiset { data }
+        }
+    };
 }
 
-macro_rules! intersection_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+macro_rules! saturating_sub_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self, other: &Self) -> Self {
-            let mut data = std::mem::MaybeUninit::<[N; SIZE]>::uninit().assume_init();
+            let mut data = [N::zero(); SIZE];
             self.data
                 .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
                     let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
                     let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
-                    simd_a.min(simd_b).write_to_slice_unaligned_unchecked(out);
+                    (simd_a.max(simd_b) - simd_b).write_to_slice_unaligned_unchecked(out);
                 });
             Multiset { data }
         }
     };
 }
 
-macro_rules! union_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+macro_rules! wrapping_add_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self, other: &Self) -> Self {
-            let mut data = std::mem::MaybeUninit::<[N; SIZE]>::uninit().assume_init();
+            let mut data = [N::zero(); SIZE];
             self.data
                 .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
                     let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
                     let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
-                    simd_a.max(simd_b).write_to_slice_unaligned_unchecked(out);
+                    (simd_a + simd_b).write_to_slice_unaligned_unchecked(out);
+                });
+            Multiset { data }
+        }
+    };
+}
+
+macro_rules! wrapping_sub_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> Self {
+            let mut data = [N::zero(); SIZE];
+            self.data
+                .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    (simd_a - simd_b).write_to_slice_unaligned_unchecked(out);
+                });
+            Multiset { data }
+        }
+    };
+}
+
+macro_rules! wrapping_mul_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> Self {
+            let mut data = [N::zero(); SIZE];
+            self.data
+                .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    (simd_a * simd_b).write_to_slice_unaligned_unchecked(out);
+                });
+            Multiset { data }
+        }
+    };
+}
+
+macro_rules! difference_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> Self {
+            let mut data = [N::zero(); SIZE];
+            let zero = <$simd>::splat(N::zero());
+            self.data
+                .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    // When `a` is zero the minimum is zero as well, so only `b`
+                    // needs to be checked.
+                    simd_b.gt(zero).select(simd_a.min(simd_b), simd_a).write_to_slice_unaligned_unchecked(out);
+                });
+            Multiset { data }
+        }
+    };
+}
+
+macro_rules! symmetric_difference_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> Self {
+            let mut data = [N::zero(); SIZE];
+            let zero = <$simd>::splat(N::zero());
+            self.data
+                .zip_map_chunks::<_, $lanes>(&other.data, &mut data, |a, b, out| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    let min = simd_a.min(simd_b);
+                    min.gt(zero).select(min, simd_a.max(simd_b)).write_to_slice_unaligned_unchecked(out);
                 });
             Multiset { data }
         }
     };
 }
 
+// In place elementwise kernels, used by the assigning operators. `$op` is the
+// wrapping vector operator applied to each chunk of `self` and `other`.
+macro_rules! zip_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr, $op:tt) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&mut self, other: &Self) {
+            self.data
+                .zip_map_chunks_mut::<_, $lanes>(&other.data, |a, b| {
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                    (simd_a $op simd_b).write_to_slice_unaligned_unchecked(a);
+                });
+        }
+    };
+}
+
+// In place broadcast kernels, applying `$op` with `rhs` to every count.
+macro_rules! scalar_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr, $op:tt) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&mut self, rhs: N) {
+            let simd_rhs = <$simd>::splat(rhs);
+            self.data.map_chunks_mut::<_, $lanes>(|a| {
+                let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                (simd_a $op simd_rhs).write_to_slice_unaligned_unchecked(a);
+            });
+        }
+    };
+}
+
+macro_rules! wrapping_add_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        zip_assign_simd! { $(#[$attr])* $name, $simd, $lanes, + }
+    };
+}
+
+macro_rules! wrapping_sub_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        zip_assign_simd! { $(#[$attr])* $name, $simd, $lanes, - }
+    };
+}
+
+macro_rules! wrapping_mul_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        zip_assign_simd! { $(#[$attr])* $name, $simd, $lanes, * }
+    };
+}
+
+macro_rules! wrapping_add_scalar_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        scalar_assign_simd! { $(#[$attr])* $name, $simd, $lanes, + }
+    };
+}
+
+macro_rules! wrapping_sub_scalar_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        scalar_assign_simd! { $(#[$attr])* $name, $simd, $lanes, - }
+    };
+}
+
+macro_rules! wrapping_mul_scalar_assign_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        scalar_assign_simd! { $(#[$attr])* $name, $simd, $lanes, * }
+    };
+}
+
 macro_rules! count_non_zero_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self) -> usize {
@@ -278,7 +412,8 @@ macro_rules! count_non_zero_simd {
 }
 
 macro_rules! is_disjoint_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self, other: &Self) -> bool {
@@ -292,7 +427,8 @@ macro_rules! is_disjoint_simd {
 }
 
 macro_rules! is_subset_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self, other: &Self) -> bool {
@@ -306,7 +442,8 @@ macro_rules! is_subset_simd {
 }
 
 macro_rules! is_superset_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self, other: &Self) -> bool {
@@ -320,7 +457,8 @@ macro_rules! is_superset_simd {
 }
 
 macro_rules! is_any_lesser_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self, other: &Self) -> bool {
@@ -334,7 +472,8 @@ macro_rules! is_any_lesser_simd {
 }
 
 macro_rules! is_any_greater_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self, other: &Self) -> bool {
@@ -347,59 +486,241 @@ macro_rules! is_any_greater_simd {
     };
 }
 
+macro_rules! eq_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> bool {
+            self.data.zip_all_chunks::<_, $lanes>(&other.data, |a, b| {
+                let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
+                let simd_b = <$simd>::from_slice_unaligned_unchecked(b);
+                simd_a == simd_b
+            })
+        }
+    };
+}
+
+// The argmax and argmin kernels first reduce the counts to the largest or
+// smallest count across the lanes, then search for the element holding it. The
+// counts which don't fill a whole vector are handled as scalars rather than
+// padded, since padding would change the minimum.
+macro_rules! elem_count_max_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self) -> (usize, &N) {
+            let split = SIZE - SIZE % $lanes;
+            let (body, tail) = self.data.split_at(split);
+            let mut out = [N::zero(); $lanes];
+            body.chunks_exact($lanes)
+                .fold(<$simd>::splat(N::zero()), |acc, a| {
+                    acc.max(<$simd>::from_slice_unaligned_unchecked(a))
+                })
+                .write_to_slice_unaligned_unchecked(&mut out);
+            let max = out.iter().chain(tail).fold(N::zero(), |acc, c| acc.max(*c));
+            // `max_by_key` returns the last of several equal maxima, so the
+            // search runs backwards.
+            let elem = match tail.iter().rposition(|c| *c == max) {
+                Some(i) => split + i,
+                None => {
+                    let simd_max = <$simd>::splat(max);
+                    // The maximum is in the body if it is not in the tail.
+                    let chunk = body
+                        .chunks_exact($lanes)
+                        .rposition(|a| <$simd>::from_slice_unaligned_unchecked(a).ge(simd_max).any())
+                        .unwrap();
+                    let offset = chunk * $lanes;
+                    offset + body[offset..offset + $lanes].iter().rposition(|c| *c == max).unwrap()
+                }
+            };
+            (elem, self.data.get_unchecked(elem))
+        }
+    };
+}
+
+macro_rules! elem_count_min_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self) -> (usize, &N) {
+            let split = SIZE - SIZE % $lanes;
+            let (body, tail) = self.data.split_at(split);
+            let mut out = [N::zero(); $lanes];
+            body.chunks_exact($lanes)
+                .fold(<$simd>::splat(N::max_value()), |acc, a| {
+                    acc.min(<$simd>::from_slice_unaligned_unchecked(a))
+                })
+                .write_to_slice_unaligned_unchecked(&mut out);
+            let min = out.iter().chain(tail).fold(N::max_value(), |acc, c| acc.min(*c));
+            // `min_by_key` returns the first of several equal minima.
+            let simd_min = <$simd>::splat(min);
+            let elem = match body
+                .chunks_exact($lanes)
+                .position(|a| <$simd>::from_slice_unaligned_unchecked(a).le(simd_min).any())
+            {
+                Some(chunk) => {
+                    let offset = chunk * $lanes;
+                    offset + body[offset..offset + $lanes].iter().position(|c| *c == min).unwrap()
+                }
+                // The minimum is in the tail if it is not in the body.
+                None => split + tail.iter().position(|c| *c == min).unwrap(),
+            };
+            (elem, self.data.get_unchecked(elem))
+        }
+    };
+}
+
+macro_rules! choose_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&mut self, elem: usize) {
+            let count = self.data.get(elem).copied();
+            let zero = <$simd>::splat(N::zero());
+            self.data.map_chunks_mut::<_, $lanes>(|a| zero.write_to_slice_unaligned_unchecked(a));
+            if let Some(count) = count {
+                *self.data.get_unchecked_mut(elem) = count;
+            }
+        }
+    };
+}
+
 macro_rules! total_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self) -> usize {
-            if SIZE < <$simd>::LANES {
-                self.iter()
-                    .map(|e| <N as AsPrimitive<usize>>::as_(*e))
-                    .sum()
+            // Counters wider than `usize` are checked by the scalar kernel.
+            if self.data.len() < <$simd>::LANES || size_of::<N>() > size_of::<usize>() {
+                kernels::scalar::total(&self.data)
             } else {
+                // The lanes of the accumulator are flushed into `total` before
+                // they would overflow, so the result matches the scalar sum.
                 let mut out = [N::zero(); $lanes];
+                let mut total = 0usize;
+                let max = <$simd>::splat(N::max_value());
                 let sum_vec =
                     self.data
                         .fold_chunks::<_, _, $lanes>(<$simd>::splat(N::zero()), |acc, a| {
                             let simd_a = <$simd>::from_slice_unaligned_unchecked(a);
-                            acc + simd_a
+                            if acc.gt(max - simd_a).any() {
+                                acc.write_to_slice_unaligned_unchecked(&mut out);
+                                total += out
+                                    .iter()
+                                    .map(|e| <N as AsPrimitive<usize>>::as_(*e))
+                                    .sum::<usize>();
+                                simd_a
+                            } else {
+                                acc + simd_a
+                            }
                         });
                 sum_vec.write_to_slice_unaligned_unchecked(&mut out);
-                out.iter().map(|e| <N as AsPrimitive<usize>>::as_(*e)).sum()
+                total + out.iter().map(|e| <N as AsPrimitive<usize>>::as_(*e)).sum::<usize>()
             }
         }
     };
 }
 
-macro_rules! collision_entropy_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+macro_rules! is_any_count_greater_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
-        unsafe fn $name(&self) -> f64 {
-            let total: f64 = self.total() as f64;
-            -self
-                .data
-                .fold_chunks::<_, _, $lanes>(<$simd>::splat(0.0), |acc, slice| {
-                    let mut f64_slice = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+        unsafe fn $name(&self, max: N) -> bool {
+            let max = <$simd>::splat(max);
+            self.data.any_chunks::<_, $lanes>(|a| {
+                <$simd>::from_slice_unaligned_unchecked(a).gt(max).any()
+            })
+        }
+    };
+}
+
+macro_rules! cast_into_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name<N2: Counter>(&self, out: &mut [N2], max: N) {
+            let max = <$simd>::splat(max);
+            let chunks = self.data.chunks_exact($lanes);
+            let rem = chunks.remainder();
+            let mut out_chunks = out.chunks_exact_mut($lanes);
+            for (a, o) in chunks.zip(&mut out_chunks) {
+                <$simd>::from_slice_unaligned_unchecked(a)
+                    .min(max)
+                    .cast_to_slice_unaligned_unchecked(o);
+            }
+            let mut pad = [N::zero(); $lanes];
+            pad[..rem.len()].copy_from_slice(rem);
+            let mut pad_out = [N2::zero(); $lanes];
+            <$simd>::from_slice_unaligned_unchecked(&pad)
+                .min(max)
+                .cast_to_slice_unaligned_unchecked(&mut pad_out);
+            out_chunks.into_remainder().copy_from_slice(&pad_out[..rem.len()]);
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! collision_entropy_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self) -> f64 {
+            let total: f64 = self.total_u128() as f64;
+            let sum = self
+                .data
+                .fold_chunks::<_, _, $lanes>(<$simd>::splat(0.0), |acc, slice| {
+                    let mut f64_slice = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+                    for i in 0..<$simd>::LANES {
+                        *f64_slice.get_unchecked_mut(i) =
+                            <N as AsPrimitive<f64>>::as_(*slice.get_unchecked(i));
+                    }
+                    let data = <$simd>::from_slice_unaligned_unchecked(&f64_slice);
+                    acc + (data / <$simd>::splat(total)).powf(<$simd>::splat(2.0))
+                });
+            -math::log2(sum.sum())
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! power_sum_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, alpha: f64) -> f64 {
+            let total: f64 = self.total_u128() as f64;
+            self.data
+                .fold_chunks::<_, _, $lanes>(<$simd>::splat(0.0), |acc, slice| {
+                    let mut f64_slice = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
                     for i in 0..<$simd>::LANES {
                         *f64_slice.get_unchecked_mut(i) =
                             <N as AsPrimitive<f64>>::as_(*slice.get_unchecked(i));
                     }
                     let data = <$simd>::from_slice_unaligned_unchecked(&f64_slice);
-                    acc + (data / total).powf(<$simd>::splat(2.0))
+                    acc + (data / <$simd>::splat(total)).powf(<$simd>::splat(alpha))
                 })
                 .sum()
-                .log2()
         }
     };
 }
 
+#[cfg(any(feature = "std", feature = "libm"))]
 macro_rules! shannon_entropy_simd {
-    ($name:ident, $simd:ty, $lanes:expr) => {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
         #[doc(hidden)]
         #[inline]
         unsafe fn $name(&self) -> f64 {
-            let total: f64 = self.total() as f64;
+            let total: f64 = self.total_u128() as f64;
             -self
                 .data
                 .fold_chunks::<_, _, $lanes>(<$simd>::splat(0.0), |acc, slice| {
@@ -409,7 +730,7 @@ macro_rules! shannon_entropy_simd {
                             <N as AsPrimitive<f64>>::as_(*slice.get_unchecked(i));
                     }
                     let data = <$simd>::from_slice_unaligned_unchecked(&f64_slice);
-                    let prob = data / total;
+                    let prob = data / <$simd>::splat(total);
                     let prob_log = prob * prob.ln();
                     acc + prob_log.is_nan().select(<$simd>::splat(0.0), prob_log)
                 })
@@ -418,43 +739,235 @@ macro_rules! shannon_entropy_simd {
     };
 }
 
+// The dot product kernels convert the counts of both multisets to floats a
+// chunk at a time. `sqrt_dot` sums the square roots of the products instead.
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! dot_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> f64 {
+            self.data
+                .zip_fold_chunks::<_, _, $lanes>(&other.data, <$simd>::splat(0.0), |acc, a, b| {
+                    let mut a_f64 = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+                    let mut b_f64 = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+                    for i in 0..<$simd>::LANES {
+                        *a_f64.get_unchecked_mut(i) =
+                            <N as AsPrimitive<f64>>::as_(*a.get_unchecked(i));
+                        *b_f64.get_unchecked_mut(i) =
+                            <N as AsPrimitive<f64>>::as_(*b.get_unchecked(i));
+                    }
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(&a_f64);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(&b_f64);
+                    acc + simd_a * simd_b
+                })
+                .sum()
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! sqrt_dot_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self) -> f64 {
+            self.data
+                .zip_fold_chunks::<_, _, $lanes>(&other.data, <$simd>::splat(0.0), |acc, a, b| {
+                    let mut a_f64 = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+                    let mut b_f64 = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+                    for i in 0..<$simd>::LANES {
+                        *a_f64.get_unchecked_mut(i) =
+                            <N as AsPrimitive<f64>>::as_(*a.get_unchecked(i));
+                        *b_f64.get_unchecked_mut(i) =
+                            <N as AsPrimitive<f64>>::as_(*b.get_unchecked(i));
+                    }
+                    let simd_a = <$simd>::from_slice_unaligned_unchecked(&a_f64);
+                    let simd_b = <$simd>::from_slice_unaligned_unchecked(&b_f64);
+                    acc + (simd_a * simd_b).sqrt()
+                })
+                .sum()
+        }
+    };
+}
+
+// The divergence kernels fold the chunks which fill a whole vector and leave
+// the rest of the counts to the scalar kernel, as padding with zero counts
+// would add the terms of the pseudocounts of elements which do not exist. The
+// term of each divergence is given for the probabilities `$p` and `$q`, and
+// the sum of the terms is multiplied by `$scale`.
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! divergence_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr, $scalar:path, $scale:expr, |$p:ident, $q:ident| $term:expr) => {
+        $(#[$attr])*
+        #[doc(hidden)]
+        #[inline]
+        unsafe fn $name(&self, other: &Self, total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+            let whole = SIZE - SIZE % $lanes;
+            let sum = self.data[..whole].zip_fold_chunks::<_, _, $lanes>(
+                &other.data[..whole],
+                <$simd>::splat(0.0),
+                |acc, a, b| {
+                    let mut a_f64 = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+                    let mut b_f64 = MaybeUninit::<[f64; $lanes]>::uninit().assume_init();
+                    for i in 0..<$simd>::LANES {
+                        *a_f64.get_unchecked_mut(i) =
+                            <N as AsPrimitive<f64>>::as_(*a.get_unchecked(i));
+                        *b_f64.get_unchecked_mut(i) =
+                            <N as AsPrimitive<f64>>::as_(*b.get_unchecked(i));
+                    }
+                    let pseudocount = <$simd>::splat(pseudocount);
+                    let $p = (<$simd>::from_slice_unaligned_unchecked(&a_f64) + pseudocount)
+                        / <$simd>::splat(total_a);
+                    let $q = (<$simd>::from_slice_unaligned_unchecked(&b_f64) + pseudocount)
+                        / <$simd>::splat(total_b);
+                    acc + $term
+                },
+            );
+            $scale * sum.sum()
+                + $scalar(&self.data[whole..], &other.data[whole..], total_a, total_b, pseudocount)
+        }
+    };
+}
+
+// Zeroes the lanes of a divergence term which are NaN, which are those where
+// the probability multiplying the logarithm is zero.
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! zero_nan {
+    ($simd:ty, $term:expr) => {{
+        let term = $term;
+        term.is_nan().select(<$simd>::splat(0.0), term)
+    }};
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! kl_divergence_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        divergence_simd! {
+            $(#[$attr])* $name, $simd, $lanes, kernels::scalar::kl_divergence, 1.0,
+            |p, q| zero_nan!($simd, p * (p / q).ln())
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! cross_entropy_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        divergence_simd! {
+            $(#[$attr])* $name, $simd, $lanes, kernels::scalar::cross_entropy, 1.0,
+            |p, q| zero_nan!($simd, <$simd>::splat(0.0) - p * q.ln())
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! jensen_shannon_divergence_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        divergence_simd! {
+            $(#[$attr])* $name, $simd, $lanes, kernels::scalar::jensen_shannon_divergence, 0.5,
+            |p, q| {
+                let m = (p + q) / <$simd>::splat(2.0);
+                zero_nan!($simd, p * (p / m).ln()) + zero_nan!($simd, q * (q / m).ln())
+            }
+        }
+    };
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+macro_rules! total_variation_simd {
+    ($(#[$attr:meta])* $name:ident, $simd:ty, $lanes:expr) => {
+        divergence_simd! {
+            $(#[$attr])* $name, $simd, $lanes, kernels::scalar::total_variation, 0.5,
+            |p, q| (p - q).max(q - p)
+        }
+    };
+}
+
+// The kernel macro used for each target is `<kernel>_simd!`, where the kernel
+// defaults to the name of the dispatched function. Methods may take `&self` or
+// `&mut self`, and may have one generic parameter, which the kernel macro
+// declares itself.
 macro_rules! simd_dispatch {
-    (simd128 = $simd128:ty, simd256 = $simd256:ty, lanes128 = $lanes128:expr, lanes256 = $lanes256:expr;
-    pub fn $name:ident (&$self_:ty $(, $arg:ident: $typ:ty)*) -> $ret:ty $body:block) => {
+    (@dispatch [$($ref_:tt)+] simd128 = $simd128:ty, simd256 = $simd256:ty, lanes128 = $lanes128:expr, lanes256 = $lanes256:expr;
+    kernel = $kernel:ident;
+    $vis:vis fn $name:ident $(<$gen:ident: $bound:path>)? ($self_:ty $(, $arg:ident: $typ:ty)*) $(-> $ret:ty)? $body:block) => {
         paste! {
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-            #[target_feature(enable = "avx2,fma")]
-            [<$name _simd>]! { [<_ $name _avx2>], $simd256, $lanes256 }
+            [<$kernel _simd>]! { #[target_feature(enable = "avx2,fma")] [<_ $name _avx2>], $simd256, $lanes256 }
 
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-            #[target_feature(enable = "avx")]
-            [<$name _simd>]! { [<_ $name _avx>], $simd256, $lanes256 }
+            [<$kernel _simd>]! { #[target_feature(enable = "avx")] [<_ $name _avx>], $simd256, $lanes256 }
 
             #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-            #[target_feature(enable = "sse4.2")]
-            [<$name _simd>]! { [<_ $name _sse42>], $simd128, $lanes128 }
+            [<$kernel _simd>]! { #[target_feature(enable = "sse4.2")] [<_ $name _sse42>], $simd128, $lanes128 }
+
+            // On other targets the wide kernel is compiled for the baseline
+            // features of the target, which LLVM lowers as best it can.
+            #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+            [<$kernel _simd>]! { [<_ $name _portable>], $simd256, $lanes256 }
 
             #[doc(hidden)]
             #[inline]
-            fn [<_ $name _default>](&$self_, $($arg: $typ),*) -> $ret $body
+            #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+            fn [<_ $name _default>] $(<$gen: $bound>)? ($($ref_)+ $self_, $($arg: $typ),*) $(-> $ret)? $body
 
             #[doc(hidden)]
             #[inline]
-            pub fn $name(&self, $($arg: $typ),*) -> $ret {
+            $vis fn $name $(<$gen: $bound>)? ($($ref_)+ self, $($arg: $typ),*) $(-> $ret)? {
+                // Safety: `backend::current` only returns backends which the
+                // cpu supports.
+                #[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
                 unsafe {
-                    if is_x86_feature_detected!("avx2") {
-                        self.[<_ $name _avx2>]($($arg),*)
-                    } else if is_x86_feature_detected!("avx") {
-                        self.[<_ $name _avx>]($($arg),*)
-                    } else if is_x86_feature_detected!("sse4.2") {
-                        self.[<_ $name _sse42>]($($arg),*)
-                    } else {
-                        self.[<_ $name _default>]($($arg),*)
+                    match backend::current() {
+                        Backend::Avx2 => self.[<_ $name _avx2>]($($arg),*),
+                        Backend::Avx => self.[<_ $name _avx>]($($arg),*),
+                        Backend::Sse42 => self.[<_ $name _sse42>]($($arg),*),
+                        Backend::Scalar => self.[<_ $name _default>]($($arg),*),
                     }
                 }
+                #[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+                unsafe {
+                    self.[<_ $name _portable>]($($arg),*)
+                }
             }
         }
     };
+    (simd128 = $simd128:ty, simd256 = $simd256:ty, lanes128 = $lanes128:expr, lanes256 = $lanes256:expr;
+    kernel = $kernel:ident;
+    $vis:vis fn $name:ident $(<$gen:ident: $bound:path>)? (&mut $self_:ty $(, $arg:ident: $typ:ty)*) $(-> $ret:ty)? $body:block) => {
+        simd_dispatch! {
+            @dispatch [&mut] simd128 = $simd128, simd256 = $simd256, lanes128 = $lanes128, lanes256 = $lanes256;
+            kernel = $kernel;
+            $vis fn $name $(<$gen: $bound>)? ($self_ $(, $arg: $typ)*) $(-> $ret)? $body
+        }
+    };
+    (simd128 = $simd128:ty, simd256 = $simd256:ty, lanes128 = $lanes128:expr, lanes256 = $lanes256:expr;
+    kernel = $kernel:ident;
+    $vis:vis fn $name:ident $(<$gen:ident: $bound:path>)? (&$self_:ty $(, $arg:ident: $typ:ty)*) $(-> $ret:ty)? $body:block) => {
+        simd_dispatch! {
+            @dispatch [&] simd128 = $simd128, simd256 = $simd256, lanes128 = $lanes128, lanes256 = $lanes256;
+            kernel = $kernel;
+            $vis fn $name $(<$gen: $bound>)? ($self_ $(, $arg: $typ)*) $(-> $ret)? $body
+        }
+    };
+    (simd128 = $simd128:ty, simd256 = $simd256:ty, lanes128 = $lanes128:expr, lanes256 = $lanes256:expr;
+    $vis:vis fn $name:ident $(<$gen:ident: $bound:path>)? (&mut $self_:ty $(, $arg:ident: $typ:ty)*) $(-> $ret:ty)? $body:block) => {
+        simd_dispatch! {
+            simd128 = $simd128, simd256 = $simd256, lanes128 = $lanes128, lanes256 = $lanes256;
+            kernel = $name;
+            $vis fn $name $(<$gen: $bound>)? (&mut $self_ $(, $arg: $typ)*) $(-> $ret)? $body
+        }
+    };
+    (simd128 = $simd128:ty, simd256 = $simd256:ty, lanes128 = $lanes128:expr, lanes256 = $lanes256:expr;
+    $vis:vis fn $name:ident $(<$gen:ident: $bound:path>)? (&$self_:ty $(, $arg:ident: $typ:ty)*) $(-> $ret:ty)? $body:block) => {
+        simd_dispatch! {
+            simd128 = $simd128, simd256 = $simd256, lanes128 = $lanes128, lanes256 = $lanes256;
+            kernel = $name;
+            $vis fn $name $(<$gen: $bound>)? (&$self_ $(, $arg: $typ)*) $(-> $ret)? $body
+        }
+    };
 }
 
 // pub trait MultisetSimdFn<N> {
@@ -723,26 +1236,151 @@ where
     simd_dispatch! {
         simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
         pub fn intersection(&self, other: &Self) -> Self {
-            self.zip_map(other, |s1, s2| s1.min(s2))
+            self.zip_with(other, |s1, s2| s1.min(s2))
         }
     }
 
     simd_dispatch! {
         simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
         pub fn union(&self, other: &Self) -> Self {
-            self.zip_map(other, |s1, s2| s1.max(s2))
+            self.zip_with(other, |s1, s2| s1.max(s2))
         }
     }
 
     simd_dispatch! {
         simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
-        pub fn count_non_zero(&self) -> usize {
-            self.iter().fold(0, |acc, &elem| {
-                acc + <N as AsPrimitive<usize>>::as_(elem.min(N::zero()))
+        pub fn difference(&self, other: &Self) -> Self {
+            self.zip_with(other, |e1, e2| {
+                if e1 > N::zero() && e2 > N::zero() {
+                    e1.min(e2)
+                } else {
+                    e1
+                }
+            })
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn symmetric_difference(&self, other: &Self) -> Self {
+            self.zip_with(other, |e1, e2| {
+                if e1 > N::zero() && e2 > N::zero() {
+                    e1.min(e2)
+                } else {
+                    e1.max(e2)
+                }
             })
         }
     }
 
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn saturating_add(&self, other: &Self) -> Self {
+            self.zip_with(other, |a, b| a.saturating_add(&b))
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn saturating_sub(&self, other: &Self) -> Self {
+            self.zip_with(other, |a, b| a.saturating_sub(&b))
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn wrapping_add(&self, other: &Self) -> Self {
+            self.zip_with(other, |a, b| a.wrapping_add(&b))
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn wrapping_sub(&self, other: &Self) -> Self {
+            self.zip_with(other, |a, b| a.wrapping_sub(&b))
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn wrapping_mul(&self, other: &Self) -> Self {
+            self.zip_with(other, |a, b| a.wrapping_mul(&b))
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn wrapping_add_assign(&mut self, other: &Self) {
+            self.iter_mut().zip(other.iter()).for_each(|(a, b)| *a = a.wrapping_add(b));
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn wrapping_sub_assign(&mut self, other: &Self) {
+            self.iter_mut().zip(other.iter()).for_each(|(a, b)| *a = a.wrapping_sub(b));
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn wrapping_mul_assign(&mut self, other: &Self) {
+            self.iter_mut().zip(other.iter()).for_each(|(a, b)| *a = a.wrapping_mul(b));
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn wrapping_add_scalar_assign(&mut self, rhs: N) {
+            self.iter_mut().for_each(|a| *a = a.wrapping_add(&rhs));
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn wrapping_sub_scalar_assign(&mut self, rhs: N) {
+            self.iter_mut().for_each(|a| *a = a.wrapping_sub(&rhs));
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn wrapping_mul_scalar_assign(&mut self, rhs: N) {
+            self.iter_mut().for_each(|a| *a = a.wrapping_mul(&rhs));
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn wrapping_add_scalar(&self, rhs: N) -> Self {
+        let mut res = *self;
+        res.wrapping_add_scalar_assign(rhs);
+        res
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn wrapping_sub_scalar(&self, rhs: N) -> Self {
+        let mut res = *self;
+        res.wrapping_sub_scalar_assign(rhs);
+        res
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn wrapping_mul_scalar(&self, rhs: N) -> Self {
+        let mut res = *self;
+        res.wrapping_mul_scalar_assign(rhs);
+        res
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn count_non_zero(&self) -> usize {
+            self.iter().filter(|elem| **elem > N::zero()).count()
+        }
+    }
+
     #[doc(hidden)]
     #[inline]
     pub fn count_zero(&self) -> usize {
@@ -804,26 +1442,149 @@ where
         }
     }
 
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = eq;
+        fn eq_dispatch(&self, other: &Self) -> bool {
+            self.data == other.data
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn elem_count_max(&self) -> (usize, &N) {
+            // iter cannot be empty, so it's fine to unwrap
+            self.iter()
+                .enumerate()
+                .max_by_key(|(_, count)| *count)
+                .unwrap()
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn elem_max(&self) -> usize {
+        self.elem_count_max().0
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn count_max(&self) -> &N {
+        self.elem_count_max().1
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn elem_count_min(&self) -> (usize, &N) {
+            // iter cannot be empty, so it's fine to unwrap
+            self.iter()
+                .enumerate()
+                .min_by_key(|(_, count)| *count)
+                .unwrap()
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn elem_min(&self) -> usize {
+        self.elem_count_min().0
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn count_min(&self) -> &N {
+        self.elem_count_min().1
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn choose(&mut self, elem: usize) {
+            let mut res = [N::zero(); SIZE];
+            if elem < SIZE {
+                // Safety: Guaranteed by bounds check on `elem`.
+                unsafe { *res.get_unchecked_mut(elem) = *self.get_unchecked(elem) };
+            }
+            self.data = res
+        }
+    }
+
     simd_dispatch! {
         simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
         pub fn total(&self) -> usize {
-            self.iter().map(|e| <N as AsPrimitive<usize>>::as_(*e)).sum()
+            kernels::scalar::total(&self.data)
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn cast_into<N2: Counter>(&self, out: &mut [N2], max: N) {
+            for (o, count) in out.iter_mut().zip(self.data.iter()) {
+                let count = <N as AsPrimitive<u128>>::as_((*count).min(max));
+                *o = N2::from_u128(count).unwrap_or_else(N2::max_value);
+            }
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn cast<N2>(&self) -> Multiset<N2, SIZE>
+    where
+        N2: Counter + From<N>,
+    {
+        let mut res = Multiset::empty();
+        self.cast_into(&mut res.data, N::max_value());
+        res
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        fn is_any_count_greater(&self, max: N) -> bool {
+            self.data.iter().any(|count| *count > max)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn try_cast<N2>(&self) -> Result<Multiset<N2, SIZE>, Error>
+    where
+        N2: Counter + TryFrom<N>,
+    {
+        let max = cast_max::<N, N2>();
+        // The lanes only say whether any count overflows, so the element is
+        // found with a scalar scan.
+        if self.is_any_count_greater(max) {
+            let element = self.iter().position(|count| *count > max).unwrap_or(0);
+            return Err(Error::Overflow { element });
         }
+        let mut res = Multiset::empty();
+        self.cast_into(&mut res.data, max);
+        Ok(res)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn saturating_cast<N2>(&self) -> Multiset<N2, SIZE>
+    where
+        N2: Counter + TryFrom<N>,
+    {
+        let mut res = Multiset::empty();
+        self.cast_into(&mut res.data, cast_max::<N, N2>());
+        res
     }
 
     #[cfg(feature = "rand")]
     #[doc(hidden)]
     #[inline]
     pub fn choose_random<T: RngCore>(&mut self, rng: &mut T) {
-        let total = self.total();
+        let total = self.total_u128();
         if total == 0 {
             return;
         }
         let choice_value = rng.gen_range(1..=total);
         let mut res = [N::zero(); SIZE];
-        let mut acc = 0;
+        let mut acc = 0u128;
         for (i, elem) in self.iter().enumerate() {
-            acc += <N as AsPrimitive<usize>>::as_(*elem);
+            acc = acc.saturating_add(<N as AsPrimitive<u128>>::as_(*elem));
             if acc >= choice_value {
                 // Safety: `i` cannot be outside of `res`.
                 unsafe { *res.get_unchecked_mut(i) = *elem }
@@ -832,29 +1593,461 @@ where
         }
         self.data = res
     }
+}
 
+#[cfg(any(feature = "std", feature = "libm"))]
+#[allow(unused_braces)]
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE>
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
+{
     simd_dispatch! {
         simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
         pub fn collision_entropy(&self) -> f64 {
-            let total: f64 = self.total().as_();
-            -self.into_iter()
-                .fold(0.0, |acc, &frequency| {
-                    let freq_f64: f64 = <N as AsPrimitive<f64>>::as_(frequency);
-                    acc + (freq_f64 / total).powf(2.0)
-                })
-                .log2()
+            let total: f64 = self.total_u128().as_();
+            -math::log2(self.into_iter().fold(0.0, |acc, &frequency| {
+                let freq_f64: f64 = <N as AsPrimitive<f64>>::as_(frequency);
+                acc + math::powf(freq_f64 / total, 2.0)
+            }))
         }
     }
 
     simd_dispatch! {
         simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
         pub fn shannon_entropy(&self) -> f64 {
-            let total: f64 = self.total().as_();
+            let total: f64 = self.total_u128().as_();
             -self.into_iter().fold(0.0, |acc, &frequency| {
                 if frequency > N::zero() {
                     let freq_f64: f64 = <N as AsPrimitive<f64>>::as_(frequency);
                     let prob = freq_f64 / total;
-                    acc + prob * prob.ln()
+                    acc + prob * math::ln(prob)
+                } else {
+                    acc
+                }
+            })
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        fn power_sum(&self, alpha: f64) -> f64 {
+            kernels::power_sum(&self.data, self.total_u128().as_(), alpha)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn renyi_entropy(&self, alpha: f64, base: LogBase) -> f64 {
+        entropy::check_order(alpha);
+        if self.is_empty() {
+            0.0
+        } else if alpha == 1.0 {
+            base.convert_nats(self.shannon_entropy())
+        } else if alpha == 2.0 {
+            base.convert_bits(self.collision_entropy())
+        } else if alpha == 0.0 {
+            base.log(self.count_non_zero() as f64)
+        } else if alpha == f64::INFINITY {
+            let max: f64 = self.count_max().as_();
+            -base.log(max / self.total_u128() as f64)
+        } else {
+            base.log(self.power_sum(alpha)) / (1.0 - alpha)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn min_entropy(&self) -> f64 {
+        self.renyi_entropy(f64::INFINITY, LogBase::Two)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn hill_number(&self, q: f64) -> f64 {
+        entropy::check_order(q);
+        if self.is_empty() {
+            0.0
+        } else if q == 0.0 {
+            self.count_non_zero() as f64
+        } else if q == 1.0 {
+            math::exp(self.shannon_entropy())
+        } else if q == f64::INFINITY {
+            let max: f64 = self.count_max().as_();
+            self.total_u128() as f64 / max
+        } else {
+            math::powf(self.power_sum(q), 1.0 / (1.0 - q))
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn entropy_with(&self, estimator: EntropyEstimator) -> f64 {
+        let total = self.total_u128();
+        match estimator {
+            EntropyEstimator::PlugIn => self.shannon_entropy(),
+            EntropyEstimator::MillerMadow => {
+                entropy::miller_madow(self.shannon_entropy(), self.count_non_zero(), total)
+            }
+            EntropyEstimator::ChaoShen => entropy::chao_shen(&self.data, total),
+            EntropyEstimator::Grassberger => entropy::grassberger(&self.data, total),
+            EntropyEstimator::JamesStein => entropy::james_stein(&self.data, total),
+        }
+    }
+}
+
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE>
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
+{
+    #[doc(hidden)]
+    #[inline]
+    pub fn l1_distance(&self, other: &Self) -> f64 {
+        (self.union(other).total_u128() - self.intersection(other).total_u128()) as f64
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn linf_distance(&self, other: &Self) -> f64 {
+        if SIZE == 0 {
+            0.0
+        } else {
+            self.abs_difference(other).count_max().as_()
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn jaccard_index(&self, other: &Self) -> f64 {
+        distance::jaccard_index(
+            self.intersection(other).total_u128(),
+            self.union(other).total_u128(),
+        )
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn bray_curtis_dissimilarity(&self, other: &Self) -> f64 {
+        distance::bray_curtis(
+            self.union(other).total_u128() - self.intersection(other).total_u128(),
+            self.total_u128(),
+            other.total_u128(),
+        )
+    }
+
+    #[inline]
+    fn abs_difference(&self, other: &Self) -> Self {
+        self.union(other).wrapping_sub(&self.intersection(other))
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[allow(unused_braces)]
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE>
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
+{
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        fn dot(&self, other: &Self) -> f64 {
+            kernels::dot(&self.data, &other.data)
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        fn sqrt_dot(&self, other: &Self) -> f64 {
+            kernels::sqrt_dot(&self.data, &other.data)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn l2_distance(&self, other: &Self) -> f64 {
+        let diff = self.abs_difference(other);
+        math::sqrt(diff.dot(&diff))
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn cosine_similarity(&self, other: &Self) -> f64 {
+        distance::cosine_similarity(self.dot(other), self.dot(self), other.dot(other))
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn hellinger_distance(&self, other: &Self) -> f64 {
+        distance::hellinger_distance(self.sqrt_dot(other), self.total_u128(), other.total_u128())
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[allow(unused_braces)]
+impl<N: Counter, const SIZE: usize> Multiset<N, SIZE>
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
+{
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        kernel = kl_divergence;
+        fn kl_divergence_nonempty(&self, other: &Self, total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+            kernels::kl_divergence(&self.data, &other.data, total_a, total_b, pseudocount)
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        kernel = cross_entropy;
+        fn cross_entropy_nonempty(&self, other: &Self, total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+            kernels::cross_entropy(&self.data, &other.data, total_a, total_b, pseudocount)
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        kernel = jensen_shannon_divergence;
+        fn jensen_shannon_divergence_nonempty(&self, other: &Self, total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+            kernels::jensen_shannon_divergence(&self.data, &other.data, total_a, total_b, pseudocount)
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        kernel = total_variation;
+        fn total_variation_nonempty(&self, other: &Self, total_a: f64, total_b: f64, pseudocount: f64) -> f64 {
+            kernels::total_variation(&self.data, &other.data, total_a, total_b, pseudocount)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn kl_divergence(&self, other: &Self) -> f64 {
+        self.kl_divergence_with_pseudocount(other, 0.0)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn kl_divergence_with_pseudocount(&self, other: &Self, pseudocount: f64) -> f64 {
+        let (total_a, total_b) =
+            distance::smoothed_totals(self.total_u128(), other.total_u128(), SIZE, pseudocount);
+        distance::kl_divergence(total_a, total_b, || {
+            self.kl_divergence_nonempty(other, total_a, total_b, pseudocount)
+        })
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn cross_entropy(&self, other: &Self) -> f64 {
+        self.cross_entropy_with_pseudocount(other, 0.0)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn cross_entropy_with_pseudocount(&self, other: &Self, pseudocount: f64) -> f64 {
+        let (total_a, total_b) =
+            distance::smoothed_totals(self.total_u128(), other.total_u128(), SIZE, pseudocount);
+        distance::cross_entropy(total_a, total_b, || {
+            self.cross_entropy_nonempty(other, total_a, total_b, pseudocount)
+        })
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn jensen_shannon_divergence(&self, other: &Self) -> f64 {
+        let (total_a, total_b) = (self.total_u128() as f64, other.total_u128() as f64);
+        distance::jensen_shannon_divergence(total_a, total_b, || {
+            self.jensen_shannon_divergence_nonempty(other, total_a, total_b, 0.0)
+        })
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn total_variation(&self, other: &Self) -> f64 {
+        let (total_a, total_b) = (self.total_u128() as f64, other.total_u128() as f64);
+        distance::total_variation(total_a, total_b, || {
+            self.total_variation_nonempty(other, total_a, total_b, 0.0)
+        })
+    }
+}
+
+// The kernels for MultisetSlice assume both operands have the same length, so
+// the public methods check the lengths before dispatching.
+#[allow(unused_braces)]
+impl<'a, N: Counter> MultisetSlice<'a, N>
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
+{
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = intersection_vec;
+        fn intersection_same_len(&self, other: &Self) -> MultisetVec<N> {
+            self.iter().zip(other.iter()).map(|(a, b)| *a.min(b)).collect()
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn intersection(&self, other: &MultisetSlice<'_, N>) -> Result<MultisetVec<N>, Error> {
+        check_same_len(self.data, other.data)?;
+        Ok(MultisetSlice::new(self.data).intersection_same_len(&MultisetSlice::new(other.data)))
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = union_vec;
+        fn union_same_len(&self, other: &Self) -> MultisetVec<N> {
+            self.iter().zip(other.iter()).map(|(a, b)| *a.max(b)).collect()
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn union(&self, other: &MultisetSlice<'_, N>) -> Result<MultisetVec<N>, Error> {
+        check_same_len(self.data, other.data)?;
+        Ok(MultisetSlice::new(self.data).union_same_len(&MultisetSlice::new(other.data)))
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn count_non_zero(&self) -> usize {
+            self.iter().filter(|elem| **elem > N::zero()).count()
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn count_zero(&self) -> usize {
+        self.len() - self.count_non_zero()
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_singleton(&self) -> bool {
+        self.count_non_zero() == 1
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = is_disjoint;
+        fn is_disjoint_same_len(&self, other: &Self) -> bool {
+            self.iter()
+                .zip(other.iter())
+                .all(|(a, b)| a.min(b) == &N::zero())
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_disjoint(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        check_same_len(self.data, other.data)?;
+        Ok(MultisetSlice::new(self.data).is_disjoint_same_len(&MultisetSlice::new(other.data)))
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = is_subset;
+        fn is_subset_same_len(&self, other: &Self) -> bool {
+            self.iter().zip(other.iter()).all(|(a, b)| a <= b)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_subset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        check_same_len(self.data, other.data)?;
+        Ok(MultisetSlice::new(self.data).is_subset_same_len(&MultisetSlice::new(other.data)))
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = is_superset;
+        fn is_superset_same_len(&self, other: &Self) -> bool {
+            self.iter().zip(other.iter()).all(|(a, b)| a >= b)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_superset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        check_same_len(self.data, other.data)?;
+        Ok(MultisetSlice::new(self.data).is_superset_same_len(&MultisetSlice::new(other.data)))
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_proper_subset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        Ok(self.is_subset(other)? && self.data != other.data)
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_proper_superset(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        Ok(self.is_superset(other)? && self.data != other.data)
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = is_any_lesser;
+        fn is_any_lesser_same_len(&self, other: &Self) -> bool {
+            self.iter().zip(other.iter()).any(|(a, b)| a < b)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_any_lesser(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        check_same_len(self.data, other.data)?;
+        Ok(MultisetSlice::new(self.data).is_any_lesser_same_len(&MultisetSlice::new(other.data)))
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        kernel = is_any_greater;
+        fn is_any_greater_same_len(&self, other: &Self) -> bool {
+            self.iter().zip(other.iter()).any(|(a, b)| a > b)
+        }
+    }
+
+    #[doc(hidden)]
+    #[inline]
+    pub fn is_any_greater(&self, other: &MultisetSlice<'_, N>) -> Result<bool, Error> {
+        check_same_len(self.data, other.data)?;
+        Ok(MultisetSlice::new(self.data).is_any_greater_same_len(&MultisetSlice::new(other.data)))
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMD128, simd256 = N::SIMD256, lanes128 = {N::L128}, lanes256 = {N::L256};
+        pub fn total(&self) -> usize {
+            kernels::scalar::total(self.data)
+        }
+    }
+}
+
+#[cfg(any(feature = "std", feature = "libm"))]
+#[allow(unused_braces)]
+impl<'a, N: Counter> MultisetSlice<'a, N>
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
+{
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        pub fn collision_entropy(&self) -> f64 {
+            let total: f64 = self.total_u128().as_();
+            -math::log2(self.iter().fold(0.0, |acc, &frequency| {
+                let freq_f64: f64 = <N as AsPrimitive<f64>>::as_(frequency);
+                acc + math::powf(freq_f64 / total, 2.0)
+            }))
+        }
+    }
+
+    simd_dispatch! {
+        simd128 = N::SIMDFloat, simd256 = N::SIMDFloat, lanes128 = {N::LF}, lanes256 = {N::LF};
+        pub fn shannon_entropy(&self) -> f64 {
+            let total: f64 = self.total_u128().as_();
+            -self.iter().fold(0.0, |acc, &frequency| {
+                if frequency > N::zero() {
+                    let freq_f64: f64 = <N as AsPrimitive<f64>>::as_(frequency);
+                    let prob = freq_f64 / total;
+                    acc + prob * math::ln(prob)
                 } else {
                     acc
                 }
@@ -863,9 +2056,24 @@ where
     }
 }
 
+impl<N: Counter, const SIZE: usize> PartialEq for Multiset<N, SIZE>
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
+{
+    #[inline]
+    fn eq(&self, other: &Self) -> bool {
+        self.eq_dispatch(other)
+    }
+}
+
+impl<N: Counter, const SIZE: usize> Eq for Multiset<N, SIZE> where
+    [(); N::L128 * N::L256 * N::LF]: Sized
+{
+}
+
 impl<N: Counter, const SIZE: usize> PartialOrd for Multiset<N, SIZE>
-    where
-        [(); N::L128 * N::L256 * N::LF]: Sized,
+where
+    [(); N::L128 * N::L256 * N::LF]: Sized,
 {
     #[inline]
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
@@ -902,3 +2110,350 @@ impl<N: Counter, const SIZE: usize> PartialOrd for Multiset<N, SIZE>
         self.is_superset(other)
     }
 }
+
+// The arithmetic operators deliberately mirror the overflow semantics of the
+// primitive integers. With overflow checks on they take the scalar path, so
+// that a count which overflows panics. Otherwise they use the wrapping simd
+// kernels, so that it wraps as it would for the counter type. Overflow checks
+// are on in debug builds by default, but are set separately by the
+// `overflow-checks` profile option, so `cfg!(overflow_checks)` is used rather
+// than `cfg!(debug_assertions)`.
+macro_rules! impl_simd_op {
+    ($op:ident, $op_fn:ident, $op_assign:ident, $op_assign_fn:ident, $kernel:ident, $scalar_kernel:ident) => {
+        impl<N: Counter, const SIZE: usize> $op for Multiset<N, SIZE>
+        where
+            [(); N::L128 * N::L256 * N::LF]: Sized,
+        {
+            type Output = Multiset<N, SIZE>;
+
+            #[inline]
+            fn $op_fn(mut self, rhs: Self) -> Self::Output {
+                self.$op_assign_fn(rhs);
+                self
+            }
+        }
+
+        impl<N: Counter, const SIZE: usize> $op<N> for Multiset<N, SIZE>
+        where
+            [(); N::L128 * N::L256 * N::LF]: Sized,
+        {
+            type Output = Multiset<N, SIZE>;
+
+            #[inline]
+            fn $op_fn(mut self, rhs: N) -> Self::Output {
+                self.$op_assign_fn(rhs);
+                self
+            }
+        }
+
+        impl<N: Counter, const SIZE: usize> $op_assign for Multiset<N, SIZE>
+        where
+            [(); N::L128 * N::L256 * N::LF]: Sized,
+        {
+            #[inline]
+            fn $op_assign_fn(&mut self, rhs: Self) {
+                // Panics on overflow with overflow checks on and wraps
+                // otherwise, see above.
+                if cfg!(overflow_checks) {
+                    self.iter_mut()
+                        .zip(rhs)
+                        .for_each(|(l, r)| l.$op_assign_fn(r));
+                } else {
+                    self.$kernel(&rhs);
+                }
+            }
+        }
+
+        impl<N: Counter, const SIZE: usize> $op_assign<N> for Multiset<N, SIZE>
+        where
+            [(); N::L128 * N::L256 * N::LF]: Sized,
+        {
+            #[inline]
+            fn $op_assign_fn(&mut self, rhs: N) {
+                // Panics on overflow with overflow checks on and wraps
+                // otherwise, see above.
+                if cfg!(overflow_checks) {
+                    self.iter_mut().for_each(|l| l.$op_assign_fn(rhs));
+                } else {
+                    self.$scalar_kernel(rhs);
+                }
+            }
+        }
+    };
+}
+
+impl_simd_op!(
+    Add,
+    add,
+    AddAssign,
+    add_assign,
+    wrapping_add_assign,
+    wrapping_add_scalar_assign
+);
+impl_simd_op!(
+    Sub,
+    sub,
+    SubAssign,
+    sub_assign,
+    wrapping_sub_assign,
+    wrapping_sub_scalar_assign
+);
+impl_simd_op!(
+    Mul,
+    mul,
+    MulAssign,
+    mul_assign,
+    wrapping_mul_assign,
+    wrapping_mul_scalar_assign
+);
+
+#[cfg(all(test, any(target_arch = "x86", target_arch = "x86_64")))]
+mod tests {
+    use super::*;
+    #[cfg(any(feature = "std", feature = "libm"))]
+    use approx::assert_relative_eq;
+
+    // Counts which mix zeros, small values and the maximum value, so that the
+    // kernels see both disjoint lanes and lanes which would overflow.
+    fn counts<N: Counter, const SIZE: usize>(seed: usize) -> Multiset<N, SIZE> {
+        let mut res = Multiset::empty();
+        for (i, count) in res.data.iter_mut().enumerate() {
+            *count = match (i * 7 + seed) % 5 {
+                0 => N::zero(),
+                1 => N::max_value(),
+                n => N::from_usize(n).unwrap(),
+            };
+        }
+        res
+    }
+
+    macro_rules! test_matches_default {
+        ($test:ident, $t:ty, $size:expr) => {
+            #[test]
+            fn $test() {
+                let a = counts::<$t, $size>(0);
+                let b = counts::<$t, $size>(3);
+                assert_eq!(a.intersection(&b), a._intersection_default(&b));
+                assert_eq!(a.union(&b), a._union_default(&b));
+                assert_eq!(a.saturating_add(&b), a._saturating_add_default(&b));
+                assert_eq!(a.saturating_sub(&b), a._saturating_sub_default(&b));
+                assert_eq!(a.wrapping_add(&b), a._wrapping_add_default(&b));
+                assert_eq!(a.wrapping_sub(&b), a._wrapping_sub_default(&b));
+                assert_eq!(a.wrapping_mul(&b), a._wrapping_mul_default(&b));
+                assert_eq!(a.difference(&b), a._difference_default(&b));
+                assert_eq!(
+                    a.symmetric_difference(&b),
+                    a._symmetric_difference_default(&b)
+                );
+                // The counts repeat, so these also check the tie-breaking.
+                assert_eq!(a.elem_count_max(), a._elem_count_max_default());
+                assert_eq!(a.elem_count_min(), a._elem_count_min_default());
+                let c = counts::<$t, $size>(1);
+                assert_eq!(c.elem_count_max(), c._elem_count_max_default());
+                assert_eq!(c.elem_count_min(), c._elem_count_min_default());
+                for elem in [0, 3, $size - 1, $size].iter() {
+                    let mut x = a;
+                    let mut y = a;
+                    x.choose(*elem);
+                    y._choose_default(*elem);
+                    assert_eq!(x.data, y.data);
+                }
+                assert_eq!(a.count_non_zero(), a._count_non_zero_default());
+                for max in [2, <$t>::max_value()].iter() {
+                    assert_eq!(
+                        a.is_any_count_greater(*max),
+                        a._is_any_count_greater_default(*max)
+                    );
+                }
+                for (x, y) in [(a, b), (a, a), (a, Multiset::empty())].iter() {
+                    assert_eq!(x.eq_dispatch(y), x._eq_dispatch_default(y));
+                    assert_eq!(x.eq_dispatch(y), x.data == y.data);
+                    assert_eq!(x.is_disjoint(y), x._is_disjoint_default(y));
+                    assert_eq!(x.is_subset(y), x._is_subset_default(y));
+                    assert_eq!(x.is_superset(y), x._is_superset_default(y));
+                    assert_eq!(x.is_any_lesser(y), x._is_any_lesser_default(y));
+                    assert_eq!(x.is_any_greater(y), x._is_any_greater_default(y));
+                }
+                // The counts are clamped both to the largest count which fits
+                // in the target and to a count below the largest in `a`.
+                macro_rules! check_cast {
+                    ($n2:ty) => {
+                        let fits = (<$n2>::max_value() as u128).min(<$t>::max_value() as u128);
+                        for max in [fits as $t, 2].iter() {
+                            let mut x = [0 as $n2; $size];
+                            let mut y = x;
+                            a.cast_into(&mut x, *max);
+                            a._cast_into_default(&mut y, *max);
+                            assert_eq!(x, y);
+                        }
+                        // The counts of `a` which do not fit in the target are
+                        // clamped so that `try_cast` succeeds.
+                        let c: Multiset<$t, $size> =
+                            a.iter().map(|e| (*e).min(fits as $t)).collect();
+                        for x in [a, c].iter() {
+                            let mut expected = [0 as $n2; $size];
+                            let res = kernels::scalar::try_cast_counts(&x.data, &mut expected);
+                            assert_eq!(x.try_cast::<$n2>(), res.map(|_| Multiset::from(expected)));
+                            kernels::scalar::saturating_cast_counts(&x.data, &mut expected);
+                            assert_eq!(x.saturating_cast::<$n2>(), Multiset::from(expected));
+                        }
+                    };
+                }
+                check_cast!(u8);
+                check_cast!(u16);
+                check_cast!(u32);
+                check_cast!(u64);
+                check_cast!(u128);
+                check_cast!(usize);
+                assert_eq!(a.cast::<$t>(), a);
+                // `total` overflows usize for the widest counters, as with the
+                // scalar implementation.
+                if std::mem::size_of::<$t>() < 8 {
+                    assert_eq!(a.total(), a._total_default());
+                }
+                #[cfg(any(feature = "std", feature = "libm"))]
+                assert_relative_eq!(
+                    a.collision_entropy(),
+                    a._collision_entropy_default(),
+                    epsilon = 1e-12
+                );
+                #[cfg(any(feature = "std", feature = "libm"))]
+                assert_relative_eq!(
+                    a.shannon_entropy(),
+                    a._shannon_entropy_default(),
+                    epsilon = 1e-12
+                );
+                #[cfg(any(feature = "std", feature = "libm"))]
+                assert_relative_eq!(a.dot(&b), a._dot_default(&b), max_relative = 1e-12);
+                #[cfg(any(feature = "std", feature = "libm"))]
+                assert_relative_eq!(
+                    a.sqrt_dot(&b),
+                    a._sqrt_dot_default(&b),
+                    max_relative = 1e-12
+                );
+                #[cfg(any(feature = "std", feature = "libm"))]
+                for &alpha in [0.5, 3.0].iter() {
+                    assert_relative_eq!(
+                        a.power_sum(alpha),
+                        a._power_sum_default(alpha),
+                        max_relative = 1e-12
+                    );
+                }
+                #[cfg(any(feature = "std", feature = "libm"))]
+                for &pseudocount in [0.0, 1.0].iter() {
+                    let extra = pseudocount * $size as f64;
+                    let total_a = a.total_u128() as f64 + extra;
+                    let total_b = b.total_u128() as f64 + extra;
+                    let args = (total_a, total_b, pseudocount);
+                    macro_rules! check {
+                        ($method:ident, $default:ident) => {
+                            assert_relative_eq!(
+                                a.$method(&b, args.0, args.1, args.2),
+                                a.$default(&b, args.0, args.1, args.2),
+                                max_relative = 1e-12
+                            );
+                        };
+                    }
+                    check!(kl_divergence_nonempty, _kl_divergence_nonempty_default);
+                    check!(cross_entropy_nonempty, _cross_entropy_nonempty_default);
+                    check!(
+                        jensen_shannon_divergence_nonempty,
+                        _jensen_shannon_divergence_nonempty_default
+                    );
+                    check!(total_variation_nonempty, _total_variation_nonempty_default);
+                }
+            }
+        };
+    }
+
+    macro_rules! test_assign_matches_default {
+        ($test:ident, $t:ty, $size:expr) => {
+            #[test]
+            fn $test() {
+                let a = counts::<$t, $size>(0);
+                let b = counts::<$t, $size>(3);
+                macro_rules! check {
+                    ($method:ident, $default:ident, $rhs:expr) => {
+                        let mut x = a;
+                        let mut y = a;
+                        x.$method($rhs);
+                        y.$default($rhs);
+                        assert_eq!(x, y);
+                    };
+                }
+                check!(wrapping_add_assign, _wrapping_add_assign_default, &b);
+                check!(wrapping_sub_assign, _wrapping_sub_assign_default, &b);
+                check!(wrapping_mul_assign, _wrapping_mul_assign_default, &b);
+                check!(
+                    wrapping_add_scalar_assign,
+                    _wrapping_add_scalar_assign_default,
+                    3
+                );
+                check!(
+                    wrapping_sub_scalar_assign,
+                    _wrapping_sub_scalar_assign_default,
+                    3
+                );
+                check!(
+                    wrapping_mul_scalar_assign,
+                    _wrapping_mul_scalar_assign_default,
+                    3
+                );
+            }
+        };
+    }
+
+    test_assign_matches_default!(test_assign_matches_default_u8, u8, 67);
+    test_assign_matches_default!(test_assign_matches_default_u16, u16, 37);
+    test_assign_matches_default!(test_assign_matches_default_u32, u32, 19);
+    test_assign_matches_default!(test_assign_matches_default_u64, u64, 11);
+    test_assign_matches_default!(test_assign_matches_default_u128, u128, 5);
+    test_assign_matches_default!(test_assign_matches_default_usize, usize, 11);
+
+    #[test]
+    fn test_ops() {
+        let a = Multiset::<u16, 37>::from_elements(&[3, 3, 36]);
+        let b = Multiset::<u16, 37>::from_elements(&[0, 3, 3, 5, 36, 36]);
+        assert_eq!(a + b, a.wrapping_add(&b));
+        assert_eq!(b - a, b.wrapping_sub(&a));
+        assert_eq!(a * b, a.wrapping_mul(&b));
+        assert_eq!(a + 2, a.wrapping_add_scalar(2));
+        assert_eq!(a * 2, a.wrapping_mul_scalar(2));
+        assert_eq!((a + 2) - 2, a);
+
+        let mut c = a;
+        c += b;
+        c -= a;
+        assert_eq!(c, b);
+        c *= 3;
+        c += 1;
+        c -= 1;
+        assert_eq!(c, b * 3);
+    }
+
+    // `test_ops` only reaches the simd kernels without overflow checks, so the
+    // overflowing operators are checked for both settings.
+    #[cfg(overflow_checks)]
+    #[test]
+    #[should_panic]
+    fn test_ops_overflow_panics() {
+        let a = Multiset::<u16, 37>::from_elements(&[3, 3, 36]);
+        let _ = Multiset::empty() - a;
+    }
+
+    #[cfg(not(overflow_checks))]
+    #[test]
+    fn test_ops_overflow_wraps() {
+        let a = Multiset::<u16, 37>::from_elements(&[3, 3, 36]);
+        let b = Multiset::empty();
+        assert_eq!(b - a, b.wrapping_sub(&a));
+        assert_eq!(b - 1, b.wrapping_sub_scalar(1));
+    }
+
+    test_matches_default!(test_matches_default_u8, u8, 67);
+    test_matches_default!(test_matches_default_u16, u16, 37);
+    test_matches_default!(test_matches_default_u32, u32, 19);
+    test_matches_default!(test_matches_default_u64, u64, 11);
+    test_matches_default!(test_matches_default_u128, u128, 5);
+    test_matches_default!(test_matches_default_usize, usize, 11);
+}
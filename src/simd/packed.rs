@@ -0,0 +1,209 @@
+// Implementations of the simd traits for the vector types of the
+// packed_simd crate, used by the `simd` feature.
+
+use super::sealed::Sealed;
+use super::{cast_lanes, SimdBasic, SimdBool, SimdConvert, SimdFloat, SimdTypes};
+use crate::Counter;
+use packed_simd::*;
+
+macro_rules! impl_sealed {
+    ($($t:ty),*) => {$(impl Sealed for $t {})*};
+}
+
+impl_sealed!(
+    m8x16, m8x32, m16x8, m16x16, m32x4, m32x8, msizex4, m64x4, m128x1, m128x2, u8x16, u8x32, u16x8,
+    u16x16, u32x4, u32x8, u64x4, u128x1, u128x2, usizex4, f64x4
+);
+
+macro_rules! impl_simd_types {
+    ($scalar:ty, $simd128:ty, $simd256:ty) => {
+        impl SimdTypes for $scalar {
+            type SIMD128 = $simd128;
+            type SIMD256 = $simd256;
+            type SIMDFloat = f64x4;
+
+            const L128: usize = <$simd128>::lanes();
+            const L256: usize = <$simd256>::lanes();
+            const LF: usize = f64x4::lanes();
+        }
+    };
+}
+
+impl_simd_types!(u8, u8x16, u8x32);
+impl_simd_types!(u16, u16x8, u16x16);
+impl_simd_types!(u32, u32x4, u32x8);
+impl_simd_types!(u64, u64x4, u64x4);
+// There are no 128 bit integer lanes on x86, so these vectors are lowered to
+// scalar operations and act as the fallback for u128 counters.
+impl_simd_types!(u128, u128x1, u128x2);
+impl_simd_types!(usize, usizex4, usizex4);
+
+macro_rules! impl_simd_bool {
+    ($scalar:ty, $simd:ty, $simd_bool:ty) => {
+        impl SimdBool<$scalar> for $simd_bool {
+            type Select = $simd;
+
+            #[inline]
+            fn all(self) -> bool {
+                Self::all(self)
+            }
+
+            #[inline]
+            fn any(self) -> bool {
+                Self::any(self)
+            }
+
+            #[inline]
+            fn count_true(self) -> usize {
+                Self::bitmask(self).count_ones() as usize
+            }
+
+            #[inline]
+            fn select(self, a: Self::Select, b: Self::Select) -> Self::Select {
+                Self::select(self, a, b)
+            }
+        }
+    };
+}
+
+impl_simd_bool!(u8, u8x16, m8x16);
+impl_simd_bool!(u8, u8x32, m8x32);
+impl_simd_bool!(u16, u16x8, m16x8);
+impl_simd_bool!(u16, u16x16, m16x16);
+impl_simd_bool!(u32, u32x4, m32x4);
+impl_simd_bool!(u32, u32x8, m32x8);
+impl_simd_bool!(u64, u64x4, m64x4);
+impl_simd_bool!(u128, u128x1, m128x1);
+impl_simd_bool!(u128, u128x2, m128x2);
+impl_simd_bool!(usize, usizex4, msizex4);
+impl_simd_bool!(f64, f64x4, m64x4);
+
+macro_rules! impl_simd_basic {
+    ($scalar:ty, $simd:ty, $simd_bool:ty) => {
+        impl SimdBasic<$scalar> for $simd {
+            const LANES: usize = <$simd>::lanes();
+
+            type SIMDBool = $simd_bool;
+
+            #[inline]
+            fn splat(value: $scalar) -> Self {
+                Self::splat(value)
+            }
+
+            #[inline]
+            unsafe fn from_slice_unaligned_unchecked(slice: &[$scalar]) -> Self {
+                Self::from_slice_unaligned_unchecked(slice)
+            }
+
+            #[inline]
+            unsafe fn write_to_slice_unaligned_unchecked(self, slice: &mut [$scalar]) {
+                Self::write_to_slice_unaligned_unchecked(self, slice);
+            }
+
+            //noinspection RsUnresolvedReference
+            #[inline]
+            fn max(self, other: Self) -> Self {
+                Self::max(self, other)
+            }
+
+            //noinspection RsUnresolvedReference
+            #[inline]
+            fn min(self, other: Self) -> Self {
+                Self::min(self, other)
+            }
+
+            #[inline]
+            fn ge(self, other: Self) -> Self::SIMDBool {
+                Self::ge(self, other)
+            }
+
+            #[inline]
+            fn gt(self, other: Self) -> Self::SIMDBool {
+                Self::gt(self, other)
+            }
+
+            #[inline]
+            fn le(self, other: Self) -> Self::SIMDBool {
+                Self::le(self, other)
+            }
+
+            #[inline]
+            fn lt(self, other: Self) -> Self::SIMDBool {
+                Self::lt(self, other)
+            }
+        }
+    };
+}
+
+impl_simd_basic!(u8, u8x16, m8x16);
+impl_simd_basic!(u8, u8x32, m8x32);
+impl_simd_basic!(u16, u16x8, m16x8);
+impl_simd_basic!(u16, u16x16, m16x16);
+impl_simd_basic!(u32, u32x4, m32x4);
+impl_simd_basic!(u32, u32x8, m32x8);
+impl_simd_basic!(u64, u64x4, m64x4);
+impl_simd_basic!(u128, u128x1, m128x1);
+impl_simd_basic!(u128, u128x2, m128x2);
+impl_simd_basic!(usize, usizex4, msizex4);
+impl_simd_basic!(f64, f64x4, m64x4);
+
+// packed_simd only has vectors of up to 512 bits, so u8x32 for example cannot
+// be cast to a vector of u64 lanes, and the lanes are converted one at a time.
+macro_rules! impl_simd_convert {
+    ($scalar:ty, $simd:ty) => {
+        impl SimdConvert<$scalar> for $simd {
+            #[inline]
+            unsafe fn cast_to_slice_unaligned_unchecked<N2: Counter>(self, slice: &mut [N2]) {
+                let mut lanes = [0 as $scalar; <$simd>::lanes()];
+                self.write_to_slice_unaligned_unchecked(&mut lanes);
+                cast_lanes(&lanes, slice)
+            }
+        }
+    };
+}
+
+impl_simd_convert!(u8, u8x16);
+impl_simd_convert!(u8, u8x32);
+impl_simd_convert!(u16, u16x8);
+impl_simd_convert!(u16, u16x16);
+impl_simd_convert!(u32, u32x4);
+impl_simd_convert!(u32, u32x8);
+impl_simd_convert!(u64, u64x4);
+impl_simd_convert!(u128, u128x1);
+impl_simd_convert!(u128, u128x2);
+impl_simd_convert!(usize, usizex4);
+
+impl SimdFloat<f64> for f64x4 {
+    type SIMDBool = m64x4;
+
+    #[inline]
+    fn is_nan(self) -> Self::SIMDBool {
+        Self::is_nan(self)
+    }
+
+    //noinspection RsUnresolvedReference
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn ln(self) -> Self {
+        Self::ln(self)
+    }
+
+    //noinspection RsUnresolvedReference
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn powf(self, other: Self) -> Self {
+        Self::powf(self, other)
+    }
+
+    //noinspection RsUnresolvedReference
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn sqrt(self) -> Self {
+        Self::sqrt(self)
+    }
+
+    #[inline]
+    fn sum(self) -> f64 {
+        Self::sum(self)
+    }
+}
@@ -0,0 +1,416 @@
+// Implementations of the simd traits for the portable vector types of
+// `core::simd`, used by the `portable_simd` feature.
+//
+// The vector types mirror those used with packed_simd, so the kernels are
+// compiled with the same number of lanes for either backend.
+
+use super::sealed::Sealed;
+use super::{cast_lanes, SimdBasic, SimdBool, SimdConvert, SimdFloat, SimdTypes};
+#[cfg(any(feature = "std", feature = "libm"))]
+use crate::math;
+use crate::Counter;
+use core::fmt::{self, Debug, Formatter};
+use core::mem::size_of;
+use core::ops::{Add, Mul, Sub};
+use core::simd::cmp::{SimdOrd, SimdPartialOrd};
+use core::simd::num::SimdFloat as _;
+use core::simd::num::SimdUint;
+use core::simd::{Mask, Select, Simd};
+#[cfg(feature = "std")]
+use std::simd::StdFloat;
+
+macro_rules! impl_sealed {
+    ($($t:ty),*) => {$(impl Sealed for $t {})*};
+}
+
+impl_sealed!(
+    Mask<i8, 16>, Mask<i8, 32>, Mask<i16, 8>, Mask<i16, 16>, Mask<i32, 4>, Mask<i32, 8>,
+    Mask<i64, 4>, Mask<isize, 4>, M128x1, M128x2, Simd<u8, 16>, Simd<u8, 32>, Simd<u16, 8>,
+    Simd<u16, 16>, Simd<u32, 4>, Simd<u32, 8>, Simd<u64, 4>, Simd<usize, 4>, U128x1, U128x2,
+    Simd<f64, 4>
+);
+
+macro_rules! impl_simd_types {
+    ($scalar:ty, $simd128:ty, $simd256:ty) => {
+        impl SimdTypes for $scalar {
+            type SIMD128 = $simd128;
+            type SIMD256 = $simd256;
+            type SIMDFloat = Simd<f64, 4>;
+
+            const L128: usize = <$simd128 as SimdBasic<$scalar>>::LANES;
+            const L256: usize = <$simd256 as SimdBasic<$scalar>>::LANES;
+            const LF: usize = <Simd<f64, 4> as SimdBasic<f64>>::LANES;
+        }
+    };
+}
+
+impl_simd_types!(u8, Simd<u8, 16>, Simd<u8, 32>);
+impl_simd_types!(u16, Simd<u16, 8>, Simd<u16, 16>);
+impl_simd_types!(u32, Simd<u32, 4>, Simd<u32, 8>);
+impl_simd_types!(u64, Simd<u64, 4>, Simd<u64, 4>);
+// core::simd has no 128 bit integer lanes, so u128 counters use the array
+// backed vectors below, which are lowered to scalar operations.
+impl_simd_types!(u128, U128x1, U128x2);
+impl_simd_types!(usize, Simd<usize, 4>, Simd<usize, 4>);
+
+macro_rules! impl_simd_bool {
+    ($scalar:ty, $lanes:expr, $mask:ty) => {
+        impl SimdBool<$scalar> for Mask<$mask, $lanes> {
+            type Select = Simd<$scalar, $lanes>;
+
+            #[inline]
+            fn all(self) -> bool {
+                Self::all(self)
+            }
+
+            #[inline]
+            fn any(self) -> bool {
+                Self::any(self)
+            }
+
+            #[inline]
+            fn count_true(self) -> usize {
+                Self::to_bitmask(self).count_ones() as usize
+            }
+
+            #[inline]
+            fn select(self, a: Self::Select, b: Self::Select) -> Self::Select {
+                Select::select(self, a, b)
+            }
+        }
+    };
+}
+
+impl_simd_bool!(u8, 16, i8);
+impl_simd_bool!(u8, 32, i8);
+impl_simd_bool!(u16, 8, i16);
+impl_simd_bool!(u16, 16, i16);
+impl_simd_bool!(u32, 4, i32);
+impl_simd_bool!(u32, 8, i32);
+impl_simd_bool!(u64, 4, i64);
+impl_simd_bool!(usize, 4, isize);
+impl_simd_bool!(f64, 4, i64);
+
+macro_rules! impl_simd_basic {
+    ($scalar:ty, $lanes:expr, $mask:ty, $max:path, $min:path) => {
+        impl SimdBasic<$scalar> for Simd<$scalar, $lanes> {
+            const LANES: usize = $lanes;
+
+            type SIMDBool = Mask<$mask, $lanes>;
+
+            #[inline]
+            fn splat(value: $scalar) -> Self {
+                Self::splat(value)
+            }
+
+            #[inline]
+            unsafe fn from_slice_unaligned_unchecked(slice: &[$scalar]) -> Self {
+                core::ptr::read_unaligned(slice.as_ptr() as *const Self)
+            }
+
+            #[inline]
+            unsafe fn write_to_slice_unaligned_unchecked(self, slice: &mut [$scalar]) {
+                core::ptr::write_unaligned(slice.as_mut_ptr() as *mut Self, self)
+            }
+
+            #[inline]
+            fn max(self, other: Self) -> Self {
+                $max(self, other)
+            }
+
+            #[inline]
+            fn min(self, other: Self) -> Self {
+                $min(self, other)
+            }
+
+            #[inline]
+            fn ge(self, other: Self) -> Self::SIMDBool {
+                self.simd_ge(other)
+            }
+
+            #[inline]
+            fn gt(self, other: Self) -> Self::SIMDBool {
+                self.simd_gt(other)
+            }
+
+            #[inline]
+            fn le(self, other: Self) -> Self::SIMDBool {
+                self.simd_le(other)
+            }
+
+            #[inline]
+            fn lt(self, other: Self) -> Self::SIMDBool {
+                self.simd_lt(other)
+            }
+        }
+    };
+    ($scalar:ty, $lanes:expr, $mask:ty) => {
+        impl_simd_basic!($scalar, $lanes, $mask, SimdOrd::simd_max, SimdOrd::simd_min);
+    };
+}
+
+impl_simd_basic!(u8, 16, i8);
+impl_simd_basic!(u8, 32, i8);
+impl_simd_basic!(u16, 8, i16);
+impl_simd_basic!(u16, 16, i16);
+impl_simd_basic!(u32, 4, i32);
+impl_simd_basic!(u32, 8, i32);
+impl_simd_basic!(u64, 4, i64);
+impl_simd_basic!(usize, 4, isize);
+impl_simd_basic!(
+    f64,
+    4,
+    i64,
+    core::simd::num::SimdFloat::simd_max,
+    core::simd::num::SimdFloat::simd_min
+);
+
+// The vector casts of core::simd keep the number of lanes, so the counts are
+// converted a whole vector at a time, apart from u128 targets which have no
+// vector type and are converted one lane at a time.
+macro_rules! impl_simd_convert {
+    ($scalar:ty, $lanes:expr) => {
+        impl SimdConvert<$scalar> for Simd<$scalar, $lanes> {
+            #[inline]
+            unsafe fn cast_to_slice_unaligned_unchecked<N2: Counter>(self, slice: &mut [N2]) {
+                let ptr = slice.as_mut_ptr();
+                match size_of::<N2>() {
+                    1 => core::ptr::write_unaligned(ptr as *mut Simd<u8, $lanes>, self.cast()),
+                    2 => core::ptr::write_unaligned(ptr as *mut Simd<u16, $lanes>, self.cast()),
+                    4 => core::ptr::write_unaligned(ptr as *mut Simd<u32, $lanes>, self.cast()),
+                    8 => core::ptr::write_unaligned(ptr as *mut Simd<u64, $lanes>, self.cast()),
+                    _ => cast_lanes(&self.to_array(), slice),
+                }
+            }
+        }
+    };
+}
+
+impl_simd_convert!(u8, 16);
+impl_simd_convert!(u8, 32);
+impl_simd_convert!(u16, 8);
+impl_simd_convert!(u16, 16);
+impl_simd_convert!(u32, 4);
+impl_simd_convert!(u32, 8);
+impl_simd_convert!(u64, 4);
+impl_simd_convert!(usize, 4);
+
+impl SimdFloat<f64> for Simd<f64, 4> {
+    type SIMDBool = Mask<i64, 4>;
+
+    #[inline]
+    fn is_nan(self) -> Self::SIMDBool {
+        core::simd::num::SimdFloat::is_nan(self)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn ln(self) -> Self {
+        StdFloat::ln(self)
+    }
+
+    // StdFloat needs std, so without it ln is applied to each lane with libm.
+    #[cfg(all(not(feature = "std"), feature = "libm"))]
+    #[inline]
+    fn ln(self) -> Self {
+        let mut res = self.to_array();
+        for r in res.iter_mut() {
+            *r = math::ln(*r);
+        }
+        Self::from_array(res)
+    }
+
+    // There is no vector powf in core::simd, so it is applied to each lane to
+    // give the same results as the scalar implementation.
+    #[cfg(any(feature = "std", feature = "libm"))]
+    #[inline]
+    fn powf(self, other: Self) -> Self {
+        let mut res = self.to_array();
+        for (r, o) in res.iter_mut().zip(other.to_array().iter()) {
+            *r = math::powf(*r, *o);
+        }
+        Self::from_array(res)
+    }
+
+    #[cfg(feature = "std")]
+    #[inline]
+    fn sqrt(self) -> Self {
+        StdFloat::sqrt(self)
+    }
+
+    #[cfg(all(not(feature = "std"), feature = "libm"))]
+    #[inline]
+    fn sqrt(self) -> Self {
+        let mut res = self.to_array();
+        for r in res.iter_mut() {
+            *r = math::sqrt(*r);
+        }
+        Self::from_array(res)
+    }
+
+    #[inline]
+    fn sum(self) -> f64 {
+        self.reduce_sum()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+// u128 vectors
+////////////////////////////////////////////////////////////////////////////////
+
+macro_rules! impl_u128_vector {
+    ($name:ident, $mask:ident, $lanes:expr) => {
+        #[doc(hidden)]
+        #[derive(Clone, Copy, PartialEq)]
+        #[repr(transparent)]
+        pub struct $name([u128; $lanes]);
+
+        #[doc(hidden)]
+        #[derive(Clone, Copy)]
+        pub struct $mask([bool; $lanes]);
+
+        impl $name {
+            #[inline]
+            fn zip_map(self, other: Self, f: impl Fn(u128, u128) -> u128) -> Self {
+                let mut res = self.0;
+                for (r, o) in res.iter_mut().zip(other.0.iter()) {
+                    *r = f(*r, *o);
+                }
+                $name(res)
+            }
+
+            #[inline]
+            fn zip_test(self, other: Self, f: impl Fn(&u128, &u128) -> bool) -> $mask {
+                let mut res = [false; $lanes];
+                for (r, (a, b)) in res.iter_mut().zip(self.0.iter().zip(other.0.iter())) {
+                    *r = f(a, b);
+                }
+                $mask(res)
+            }
+        }
+
+        impl Debug for $name {
+            fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+                Debug::fmt(&self.0, f)
+            }
+        }
+
+        impl Add for $name {
+            type Output = Self;
+
+            #[inline]
+            fn add(self, other: Self) -> Self {
+                self.zip_map(other, u128::wrapping_add)
+            }
+        }
+
+        impl Sub for $name {
+            type Output = Self;
+
+            #[inline]
+            fn sub(self, other: Self) -> Self {
+                self.zip_map(other, u128::wrapping_sub)
+            }
+        }
+
+        impl Mul for $name {
+            type Output = Self;
+
+            #[inline]
+            fn mul(self, other: Self) -> Self {
+                self.zip_map(other, u128::wrapping_mul)
+            }
+        }
+
+        impl SimdBool<u128> for $mask {
+            type Select = $name;
+
+            #[inline]
+            fn all(self) -> bool {
+                self.0.iter().all(|b| *b)
+            }
+
+            #[inline]
+            fn any(self) -> bool {
+                self.0.iter().any(|b| *b)
+            }
+
+            #[inline]
+            fn count_true(self) -> usize {
+                self.0.iter().filter(|b| **b).count()
+            }
+
+            #[inline]
+            fn select(self, a: $name, b: $name) -> $name {
+                let mut res = b.0;
+                for (r, (m, a)) in res.iter_mut().zip(self.0.iter().zip(a.0.iter())) {
+                    if *m {
+                        *r = *a;
+                    }
+                }
+                $name(res)
+            }
+        }
+
+        impl SimdBasic<u128> for $name {
+            const LANES: usize = $lanes;
+
+            type SIMDBool = $mask;
+
+            #[inline]
+            fn splat(value: u128) -> Self {
+                $name([value; $lanes])
+            }
+
+            #[inline]
+            unsafe fn from_slice_unaligned_unchecked(slice: &[u128]) -> Self {
+                core::ptr::read_unaligned(slice.as_ptr() as *const Self)
+            }
+
+            #[inline]
+            unsafe fn write_to_slice_unaligned_unchecked(self, slice: &mut [u128]) {
+                core::ptr::write_unaligned(slice.as_mut_ptr() as *mut Self, self)
+            }
+
+            #[inline]
+            fn max(self, other: Self) -> Self {
+                self.zip_map(other, u128::max)
+            }
+
+            #[inline]
+            fn min(self, other: Self) -> Self {
+                self.zip_map(other, u128::min)
+            }
+
+            #[inline]
+            fn ge(self, other: Self) -> $mask {
+                self.zip_test(other, u128::ge)
+            }
+
+            #[inline]
+            fn gt(self, other: Self) -> $mask {
+                self.zip_test(other, u128::gt)
+            }
+
+            #[inline]
+            fn le(self, other: Self) -> $mask {
+                self.zip_test(other, u128::le)
+            }
+
+            #[inline]
+            fn lt(self, other: Self) -> $mask {
+                self.zip_test(other, u128::lt)
+            }
+        }
+
+        impl SimdConvert<u128> for $name {
+            #[inline]
+            unsafe fn cast_to_slice_unaligned_unchecked<N2: Counter>(self, slice: &mut [N2]) {
+                cast_lanes(&self.0, slice)
+            }
+        }
+    };
+}
+
+impl_u128_vector!(U128x1, M128x1, 1);
+impl_u128_vector!(U128x2, M128x2, 2);